@@ -0,0 +1,240 @@
+//! Smoke test exercising the full API surface against a real drive.
+//!
+//! It reads the same `ONEDRIVE_API_TEST_*` variables as the real tests, from the environment
+//! or from `.env` generated by the `onedrive-api-test` binary in the current directory.
+//!
+//! **DANGER:**
+//! This MODIFIES YOUR FILES on OneDrive! It only touches a newly created folder, which is
+//! removed at the end, but you should still ALWAYS use a test-only Microsoft account.
+//!
+//! USAGE: cargo run -p onedrive-api-test --features integration-tests --bin smoke
+use anyhow::{ensure, Context as _, Result};
+use onedrive_api::{
+    option::ObjectOption, Auth, DriveLocation, FileName, ItemLocation, OneDrive, Permission,
+    TokenRefresher,
+};
+use std::{collections::HashMap, env, fs, future::Future, process::exit};
+
+const ENV_PREFIX: &str = "ONEDRIVE_API_TEST_";
+const DOT_ENV_FILE: &str = ".env";
+
+// Upload parts must be multiples of 320 KiB, except the last one.
+const PART_SIZE: usize = 320 << 10;
+
+/// Load config from `.env`, overridden by the environment.
+fn load_config() -> HashMap<String, String> {
+    let mut config = HashMap::new();
+    if let Ok(content) = fs::read_to_string(DOT_ENV_FILE) {
+        for line in content.lines() {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+                config.insert(key.trim().to_owned(), value.to_owned());
+            }
+        }
+    }
+    config.extend(env::vars());
+    config
+}
+
+fn get_config(config: &HashMap<String, String>, key: &str) -> Result<String> {
+    config
+        .get(&format!("{}{}", ENV_PREFIX, key))
+        .cloned()
+        .with_context(|| format!("Missing `{}{}`", ENV_PREFIX, key))
+}
+
+struct Runner {
+    passed: usize,
+    failed: usize,
+}
+
+impl Runner {
+    async fn step<T>(&mut self, name: &str, fut: impl Future<Output = Result<T>>) -> Option<T> {
+        match fut.await {
+            Ok(v) => {
+                self.passed += 1;
+                eprintln!("ok     {}", name);
+                Some(v)
+            }
+            Err(err) => {
+                self.failed += 1;
+                eprintln!("FAILED {}: {:#}", name, err);
+                None
+            }
+        }
+    }
+}
+
+async fn login(config: &HashMap<String, String>) -> Result<TokenRefresher> {
+    let auth = Auth::new(
+        get_config(config, "CLIENT_ID")?,
+        Permission::new_read().write(true).offline_access(true),
+        get_config(config, "REDIRECT_URI")?,
+    );
+    let client_secret = get_config(config, "CLIENT_SECRET").ok();
+    let token = auth
+        .login_with_refresh_token(
+            &get_config(config, "REFRESH_TOKEN")?,
+            client_secret.as_deref(),
+        )
+        .await?;
+    let mut refresher = TokenRefresher::new(auth, &token, client_secret);
+    refresher.refresh().await?;
+    Ok(refresher)
+}
+
+async fn run_file_steps(
+    runner: &mut Runner,
+    onedrive: &OneDrive,
+    folder_id: &onedrive_api::ItemId,
+) {
+    let small_name = FileName::new("small.txt").unwrap();
+    let small_loc = ItemLocation::child_of_id(folder_id, small_name);
+    runner
+        .step("upload small file", async {
+            let item = onedrive.upload_small(small_loc, &b"smoke"[..]).await?;
+            ensure!(item.size == Some(5), "Unexpected size {:?}", item.size);
+            Ok(())
+        })
+        .await;
+
+    runner
+        .step("get item with option", async {
+            let item = onedrive
+                .get_item_with_option(small_loc, ObjectOption::new().select_core())
+                .await?
+                .context("Unexpected not modified")?;
+            ensure!(item.name.as_deref() == Some("small.txt"), "Unexpected name");
+            Ok(())
+        })
+        .await;
+
+    let renamed = FileName::new("renamed.txt").unwrap();
+    runner
+        .step("move (rename) item", async {
+            let item = onedrive.move_(small_loc, folder_id, Some(renamed)).await?;
+            ensure!(
+                item.name.as_deref() == Some("renamed.txt"),
+                "Unexpected name"
+            );
+            Ok(())
+        })
+        .await;
+
+    let large_name = FileName::new("large.bin").unwrap();
+    runner
+        .step("upload session", async {
+            let data = (0..PART_SIZE + 100).map(|i| i as u8).collect::<Vec<_>>();
+            let file_size = data.len() as u64;
+            let (session, _) = onedrive
+                .new_upload_session(ItemLocation::child_of_id(folder_id, large_name))
+                .await?;
+            let ret = session
+                .upload_part(
+                    data[..PART_SIZE].to_vec(),
+                    0..PART_SIZE as u64,
+                    file_size,
+                    onedrive.client(),
+                )
+                .await?;
+            ensure!(ret.is_none(), "Upload completed too early");
+            let item = session
+                .upload_part(
+                    data[PART_SIZE..].to_vec(),
+                    PART_SIZE as u64..file_size,
+                    file_size,
+                    onedrive.client(),
+                )
+                .await?
+                .context("Upload not completed")?;
+            ensure!(item.size == Some(file_size as i64), "Unexpected size");
+            Ok(())
+        })
+        .await;
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let config = load_config();
+    let mut runner = Runner {
+        passed: 0,
+        failed: 0,
+    };
+
+    let refresher = match runner.step("login and refresh token", login(&config)).await {
+        Some(refresher) => refresher,
+        None => exit(1),
+    };
+    let onedrive = OneDrive::new(refresher.shared_token(), DriveLocation::me());
+
+    runner
+        .step("get drive", async { Ok(onedrive.get_drive().await?) })
+        .await;
+
+    let folder_name = format!("onedrive-api-smoke-{}", std::process::id());
+    let folder_id = runner
+        .step("create folder", async {
+            let name = FileName::new(&folder_name).context("Invalid folder name")?;
+            onedrive
+                .create_folder(ItemLocation::root(), name)
+                .await?
+                .id
+                .context("Missing `id`")
+        })
+        .await;
+    let folder_id = match folder_id {
+        Some(id) => id,
+        None => exit(1),
+    };
+
+    run_file_steps(&mut runner, &onedrive, &folder_id).await;
+
+    runner
+        .step("list children", async {
+            let mut names = onedrive
+                .list_children(&folder_id)
+                .await?
+                .into_iter()
+                .filter_map(|item| item.name)
+                .collect::<Vec<_>>();
+            names.sort();
+            ensure!(
+                names == ["large.bin", "renamed.txt"],
+                "Unexpected children {:?}",
+                names
+            );
+            Ok(())
+        })
+        .await;
+
+    runner
+        .step("track changes", async {
+            let (items, _) = onedrive
+                .track_changes_from_initial(&folder_id)
+                .await?
+                .fetch_all(&onedrive)
+                .await?;
+            ensure!(
+                items
+                    .iter()
+                    .any(|item| item.name.as_deref() == Some("renamed.txt")),
+                "Missing changes",
+            );
+            Ok(())
+        })
+        .await;
+
+    runner
+        .step("delete folder", async {
+            Ok(onedrive.delete(&folder_id).await?)
+        })
+        .await;
+
+    eprintln!("\n{} passed, {} failed", runner.passed, runner.failed);
+    if runner.failed != 0 {
+        exit(1);
+    }
+    Ok(())
+}
@@ -33,8 +33,8 @@ async fn test_get_drive() {
     let drive_id = drive1.id.as_ref().expect("drive1 has no id");
 
     // #2
-    let drive2 = OneDrive::new(onedrive.access_token().to_owned(), drive_id.clone())
-        .get_drive_with_option(ObjectOption::new().select(&[DriveField::id, DriveField::owner]))
+    let drive2 = OneDrive::new(onedrive.access_token(), drive_id.clone())
+        .get_drive_with_option(ObjectOption::new().select_identity())
         .await
         .expect("Cannot get drive #2");
     assert_eq!(&drive1.id, &drive2.id); // Checked to be `Some`.
@@ -44,7 +44,7 @@ async fn test_get_drive() {
     // #3
     assert_eq!(
         OneDrive::new(
-            onedrive.access_token().to_owned(),
+            onedrive.access_token(),
             DriveId(format!("{}_inva_lid", drive_id.as_str())),
         )
         .get_drive()
@@ -56,7 +56,7 @@ async fn test_get_drive() {
     );
 }
 
-// 3 requests
+// 4 requests
 #[tokio::test]
 async fn test_get_item() {
     let onedrive = onedrive().await;
@@ -67,6 +67,13 @@ async fn test_get_item() {
         .await
         .expect("Cannot get item by path");
     let item_id = item_by_path.id.clone().expect("Missing `id`");
+    assert_eq!(
+        item_by_path
+            .odata_context
+            .as_ref()
+            .and_then(|ctx| ctx.entity_type()),
+        Some("driveItem"),
+    );
 
     // #2
     let item_by_id = onedrive
@@ -87,11 +94,23 @@ async fn test_get_item() {
     assert_eq!(item_custom.id.as_ref(), Some(&item_id), "`id` mismatch",);
     assert!(item_custom.size.is_none(), "`size` should not be selected");
 
+    // #4
+    let drive_id = item_by_path
+        .parent_reference
+        .as_ref()
+        .and_then(|r| r.drive_id.clone())
+        .expect("Missing `parentReference.driveId`");
+    let item_in_drive = onedrive
+        .get_item_in(&DriveLocation::from_id(drive_id), ItemLocation::root())
+        .await
+        .expect("Cannot get item in drive");
+    assert_eq!(item_in_drive.id, item_by_path.id);
+
     // `If-None-Match` may be ignored by server.
     // So we don't test it.
 }
 
-// 7 requests
+// 8 requests
 #[tokio::test]
 async fn test_folder_create_and_list_children() {
     let onedrive = onedrive().await;
@@ -164,11 +183,14 @@ async fn test_folder_create_and_list_children() {
 
     // #3
     let page2 = fetcher
-        .fetch_next_page(&onedrive)
+        .fetch_next_page_with_meta(&onedrive)
         .await
         .expect("Cannot fetch page 2")
         .expect("Page 2 should not be None");
-    assert_eq!(page2.len(), 1);
+    assert_eq!(page2.items_in_page(), 1);
+    assert!(page2.next_link.is_none(), "Page 2 should be the last page");
+    assert!(page2.odata_context.is_some(), "Missing `@odata.context`");
+    let page2 = page2.items;
 
     assert!(
         fetcher
@@ -212,10 +234,26 @@ async fn test_folder_create_and_list_children() {
     assert_eq!(items_origin, items_expand);
 
     // #7
+    let items_query = onedrive
+        .children(container_loc)
+        .select(&[DriveItemField::name])
+        .order_by(DriveItemField::name, Order::Descending)
+        .fetch()
+        .await
+        .expect("Cannot list children with query builder")
+        .into_iter()
+        .map(|item| item.name.expect("Missing `name`"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        items_query,
+        items_origin.iter().rev().cloned().collect::<Vec<_>>(),
+    );
+
+    // #8
     onedrive.delete(container_loc).await.unwrap();
 }
 
-// 4 requests
+// 7 requests
 #[tokio::test]
 async fn test_folder_create_and_delete() {
     let onedrive = onedrive().await;
@@ -242,6 +280,18 @@ async fn test_folder_create_and_delete() {
     );
 
     // #3
+    let renamed = onedrive
+        .create_folder_with_option(
+            ItemLocation::root(),
+            folder_name,
+            DriveItemPutOption::new().conflict_behavior(ConflictBehavior::Rename),
+        )
+        .await
+        .expect("Cannot re-create folder with rename");
+    assert!(renamed.was_renamed, "Conflicting folder should be renamed");
+    assert_ne!(renamed.item.name.as_deref(), Some(folder_name.as_str()));
+
+    // #4
     assert_eq!(
         onedrive
             .delete(invalid_loc)
@@ -251,11 +301,21 @@ async fn test_folder_create_and_delete() {
         Some(StatusCode::NOT_FOUND),
     );
 
-    // #4
-    onedrive.delete(folder_loc).await.unwrap();
+    // #5
+    assert!(!onedrive
+        .delete_if_exists(invalid_loc)
+        .await
+        .expect("Deleting non-existent folder should be ok"));
+
+    // #6, #7
+    assert!(onedrive.delete_if_exists(folder_loc).await.unwrap());
+    onedrive
+        .delete(renamed.item.id.as_ref().expect("Missing `id`"))
+        .await
+        .unwrap();
 }
 
-// 4 requests
+// 5 requests
 #[tokio::test]
 async fn test_folder_create_and_update() {
     let onedrive = onedrive().await;
@@ -279,6 +339,10 @@ async fn test_folder_create_and_update() {
         .await
         .expect("Cannot create folder");
 
+    assert!(
+        item_before.e_tag.is_some(),
+        "Missing `e_tag` of created folder"
+    );
     let (btime_before, mtime_before) =
         get_bmtime(&item_before).expect("Invalid file_system_info before update");
     assert_ne!(btime_before, FAKE_TIME);
@@ -295,6 +359,11 @@ async fn test_folder_create_and_update() {
         .await
         .expect("Cannot update folder metadata");
     assert_eq!(get_bmtime(&item_response), Some((FAKE_TIME, FAKE_TIME)));
+    assert!(
+        item_response.e_tag.is_some(),
+        "Missing `e_tag` of updated folder"
+    );
+    assert_ne!(item_response.e_tag, item_before.e_tag);
 
     // #3
     let item_after = onedrive
@@ -304,6 +373,22 @@ async fn test_folder_create_and_update() {
     assert_eq!(get_bmtime(&item_after), Some((FAKE_TIME, FAKE_TIME)));
 
     // #4
+    let mut patch = DriveItem::default();
+    patch.description = Some("minimal".to_owned());
+    let item_minimal = onedrive
+        .update_item_with_option(
+            folder_loc,
+            &patch,
+            ObjectOption::new().prefer_return(ReturnPreference::Minimal),
+        )
+        .await
+        .expect("Cannot update folder with minimal response");
+    assert!(
+        item_minimal.e_tag.is_some(),
+        "Missing `e_tag` of minimal response"
+    );
+
+    // #5
     onedrive.delete(folder_loc).await.unwrap();
 }
 
@@ -321,10 +406,12 @@ async fn test_file_upload_small_and_move() {
     let file2_loc = rooted_location(file2_name);
 
     // #1
-    onedrive
+    let item1 = onedrive
         .upload_small(file1_loc, CONTENT1)
         .await
         .expect("Cannot upload file 1");
+    assert!(item1.e_tag.is_some(), "Missing `e_tag` of uploaded file");
+    assert!(item1.c_tag.is_some(), "Missing `c_tag` of uploaded file");
 
     // #2
     onedrive
@@ -343,7 +430,7 @@ async fn test_file_upload_small_and_move() {
     );
 
     // #4
-    onedrive
+    let moved = onedrive
         .move_with_option(
             file1_loc,
             ItemLocation::root(),
@@ -351,7 +438,9 @@ async fn test_file_upload_small_and_move() {
             DriveItemPutOption::new().conflict_behavior(ConflictBehavior::Replace),
         )
         .await
-        .expect("Cannot move with overwrite");
+        .expect("Cannot move with overwrite")
+        .item;
+    assert!(moved.e_tag.is_some(), "Missing `e_tag` of moved file");
 
     // #5
     assert_eq!(
@@ -405,8 +494,8 @@ async fn test_file_upload_small_and_copy() {
             .expect("Failed to check `copy` progress")
             .status
         {
-            CopyStatus::NotStarted | CopyStatus::InProgress => {}
-            CopyStatus::Completed => break,
+            AsyncOperationStatus::NotStarted | AsyncOperationStatus::InProgress => {}
+            AsyncOperationStatus::Completed => break,
             status => panic!("Unexpected fail of `copy`: {:?}", status),
         }
 
@@ -448,14 +537,23 @@ async fn test_file_upload_session() {
         "Upload session will expire at {:?}",
         meta1.expiration_date_time,
     );
+    assert_eq!(
+        sess.expiration_date_time(),
+        Some(&*meta1.expiration_date_time),
+    );
+    assert!(sess.expires_at().is_some(), "Cannot parse expiration time");
+    assert!(!sess.is_expired());
+
+    let mut verifier = UploadVerifier::new();
 
     // #2
     assert!(
-        sess.upload_part(
+        sess.upload_part_with_checksum(
             &CONTENT[RANGE1],
             as_range_u64(RANGE1),
             CONTENT_LEN,
-            onedrive.client()
+            onedrive.client(),
+            &mut verifier,
         )
         .await
         .expect("Cannot upload part 1")
@@ -472,7 +570,7 @@ async fn test_file_upload_session() {
     assert!(
         next_ranges.len() == 1
             && next_ranges[0].start == RANGE2.start as u64
-            && next_ranges[0].end.map_or(true, |x| x == RANGE2.end as u64),
+            && next_ranges[0].end.unwrap_or(RANGE2.end as u64) == RANGE2.end as u64,
         "Invalid `next_expected_ranges`: {:?}",
         next_ranges
     );
@@ -492,15 +590,18 @@ async fn test_file_upload_session() {
     );
 
     // #5
-    sess.upload_part(
+    sess.upload_part_with_checksum(
         &CONTENT[RANGE2],
         as_range_u64(RANGE2),
         CONTENT_LEN,
         onedrive.client(),
+        &mut verifier,
     )
     .await
-    .expect("Failed to upload part 2")
+    .expect("Failed to upload part 2 or checksum mismatched")
     .expect("Uploading should be completed");
+    assert_eq!(verifier.chunks().len(), 2);
+    assert_ne!(verifier.verified(), Some(false));
 
     // #6
     let download_url = onedrive.get_item_download_url(item_loc).await.unwrap();
@@ -512,6 +613,607 @@ async fn test_file_upload_session() {
     onedrive.delete(item_loc).await.unwrap();
 }
 
+// 7 requests
+#[tokio::test]
+async fn test_folder_size() {
+    let onedrive = onedrive().await;
+
+    const CONTENT1: &[u8] = b"12345";
+    const CONTENT2: &[u8] = b"123";
+
+    let container_name = gen_filename();
+    let container_loc = rooted_location(container_name);
+
+    // #1
+    let container_id = onedrive
+        .create_folder(ItemLocation::root(), container_name)
+        .await
+        .expect("Cannot create container folder")
+        .id
+        .expect("Missing `id`");
+
+    // #2
+    let sub_id = onedrive
+        .create_folder(&container_id, gen_filename())
+        .await
+        .expect("Cannot create sub folder")
+        .id
+        .expect("Missing `id`");
+
+    // #3, #4
+    onedrive
+        .upload_small(
+            ItemLocation::child_of_id(&container_id, gen_filename()),
+            CONTENT1,
+        )
+        .await
+        .expect("Cannot upload file 1");
+    onedrive
+        .upload_small(ItemLocation::child_of_id(&sub_id, gen_filename()), CONTENT2)
+        .await
+        .expect("Cannot upload file 2");
+
+    // #5, #6
+    let size = onedrive
+        .folder_size(container_loc)
+        .await
+        .expect("Cannot get folder size");
+    assert_eq!(size.total_bytes, (CONTENT1.len() + CONTENT2.len()) as u64);
+    assert_eq!(size.file_count, 2);
+    assert_eq!(size.folder_count, 1);
+
+    // #7
+    onedrive.delete(container_loc).await.unwrap();
+}
+
+// 6 requests
+#[tokio::test]
+async fn test_get_item_path() {
+    let onedrive = onedrive().await;
+
+    let container_name = gen_filename();
+    let container_loc = rooted_location(container_name);
+    let sub_name = gen_filename();
+
+    // #1
+    onedrive
+        .create_folder(ItemLocation::root(), container_name)
+        .await
+        .expect("Cannot create container folder");
+
+    // #2
+    let sub_id = onedrive
+        .create_folder(container_loc, sub_name)
+        .await
+        .expect("Cannot create sub folder")
+        .id
+        .expect("Missing `id`");
+
+    // #3
+    let root_id = onedrive
+        .get_item(ItemLocation::root())
+        .await
+        .expect("Cannot get root")
+        .id
+        .expect("Missing `id`");
+
+    // #4, #5
+    assert_eq!(onedrive.get_item_path(&root_id).await.unwrap(), "/");
+    assert_eq!(
+        onedrive.get_item_path(&sub_id).await.unwrap(),
+        format!("/{}/{}", container_name.as_str(), sub_name.as_str()),
+    );
+
+    // #6
+    onedrive.delete(container_loc).await.unwrap();
+}
+
+// 1 request
+#[tokio::test]
+async fn test_get_socket_io_endpoint() {
+    let onedrive = onedrive().await;
+
+    // #1
+    let endpoint = onedrive
+        .get_socket_io_endpoint()
+        .await
+        .expect("Cannot get Socket.IO endpoint");
+    assert!(endpoint.notification_url.starts_with("https://"));
+}
+
+// 4 requests
+#[tokio::test]
+async fn test_default_conflict_behavior() {
+    let onedrive = onedrive()
+        .await
+        .with_default_conflict_behavior(Some(ConflictBehavior::Fail))
+        .with_name_conflict_lookup(true);
+
+    let file_loc = rooted_location(gen_filename());
+
+    // #1
+    let item = onedrive
+        .upload_small(file_loc, &b"aaa"[..])
+        .await
+        .expect("Cannot upload file");
+
+    // #2, #3
+    let err = onedrive
+        .upload_small(file_loc, &b"bbb"[..])
+        .await
+        .expect_err("Should not replace with default conflict behavior `Fail`");
+    assert_eq!(err.status_code(), Some(StatusCode::CONFLICT));
+    assert!(err.is_name_conflict(), "Should be a name conflict: {}", err);
+    let existing = err
+        .conflicting_item()
+        .expect("Existing item should be looked up");
+    assert_eq!(existing.id, item.id);
+    assert_eq!(existing.size, Some(3));
+
+    // #4
+    onedrive.delete(file_loc).await.unwrap();
+}
+
+// 5 requests
+#[tokio::test]
+async fn test_upload_many() {
+    let onedrive = onedrive().await;
+
+    const CONTENTS: [&[u8]; 3] = [b"1", b"22", b"333"];
+
+    let container_name = gen_filename();
+    let container_loc = rooted_location(container_name);
+    let names = CONTENTS.iter().map(|_| gen_filename()).collect::<Vec<_>>();
+
+    // #1
+    let container_id = onedrive
+        .create_folder(ItemLocation::root(), container_name)
+        .await
+        .expect("Cannot create container folder")
+        .id
+        .expect("Missing `id`");
+
+    // #2
+    let results = onedrive
+        .upload_many(
+            names
+                .iter()
+                .zip(&CONTENTS)
+                .map(|(name, content)| (ItemLocation::child_of_id(&container_id, name), *content)),
+        )
+        .await;
+    assert_eq!(results.len(), CONTENTS.len());
+    for (ret, content) in results.into_iter().zip(&CONTENTS) {
+        let created = ret.expect("Cannot upload file");
+        assert!(!created.was_renamed);
+        assert!(created.item.e_tag.is_some());
+        assert_eq!(created.item.size, Some(content.len() as i64));
+    }
+
+    // #3
+    let children = onedrive
+        .list_children(container_loc)
+        .await
+        .expect("Cannot list children");
+    assert_eq!(children.len(), CONTENTS.len());
+
+    // #4
+    let missing_name = gen_filename();
+    let results = onedrive
+        .delete_many_if_exists(
+            names
+                .iter()
+                .chain(Some(&missing_name))
+                .map(|name| ItemLocation::child_of_id(&container_id, name)),
+        )
+        .await;
+    let deleted = results
+        .into_iter()
+        .map(|ret| ret.expect("Cannot delete file"))
+        .collect::<Vec<_>>();
+    assert_eq!(deleted, [true, true, true, false]);
+
+    // #5
+    onedrive.delete(container_loc).await.unwrap();
+}
+
+// 6 requests
+#[tokio::test]
+async fn test_upload_session_resume() {
+    let onedrive = onedrive().await;
+
+    const CONTENT: &[u8] = b"12345678";
+    const CHANGED: &[u8] = b"02345678";
+    const CONTENT_LEN: u64 = CONTENT.len() as u64;
+
+    let item_loc = rooted_location(gen_filename());
+
+    // #1
+    let (sess, _) = onedrive
+        .new_upload_session(item_loc)
+        .await
+        .expect("Cannot create upload session");
+    let mut checkpoint = UploadCheckpoint::new(&sess, CONTENT_LEN);
+    let mut verifier = UploadVerifier::new();
+
+    // #2
+    sess.upload_part_with_checksum(
+        &CONTENT[..2],
+        0..2,
+        CONTENT_LEN,
+        onedrive.client(),
+        &mut verifier,
+    )
+    .await
+    .expect("Cannot upload part 1");
+    checkpoint.update(&verifier);
+
+    let checkpoint: UploadCheckpoint =
+        serde_json::from_str(&serde_json::to_string(&checkpoint).unwrap()).unwrap();
+    assert_eq!(checkpoint.uploaded(), 2);
+
+    // #3
+    assert!(
+        checkpoint
+            .resume(CHANGED, CONTENT_LEN, onedrive.client())
+            .await
+            .expect("Cannot resume with changed content")
+            .is_none(),
+        "Changed content should be detected",
+    );
+
+    // #4
+    let ResumedUpload {
+        session,
+        offset,
+        mut verifier,
+        ..
+    } = checkpoint
+        .resume(CONTENT, CONTENT_LEN, onedrive.client())
+        .await
+        .expect("Cannot resume")
+        .expect("Content is not changed");
+    assert_eq!(offset, 2);
+
+    // #5
+    session
+        .upload_part_with_checksum(
+            &CONTENT[2..],
+            2..CONTENT_LEN,
+            CONTENT_LEN,
+            onedrive.client(),
+            &mut verifier,
+        )
+        .await
+        .expect("Cannot upload part 2")
+        .expect("Uploading should be completed");
+    assert_ne!(verifier.verified(), Some(false));
+
+    // #6
+    onedrive.delete(item_loc).await.unwrap();
+}
+
+// 4 requests
+#[tokio::test]
+async fn test_probe_capabilities() {
+    let onedrive = onedrive().await;
+
+    // #1, #2, #3, #4
+    let caps = onedrive
+        .probe_capabilities()
+        .await
+        .expect("Cannot probe capabilities");
+    assert!(caps.read, "Should be able to read own drive");
+    assert!(caps.write, "Should be able to write own drive");
+}
+
+// 1 request
+#[tokio::test]
+async fn test_request_coalescing() {
+    let onedrive = onedrive().await.with_request_coalescing(true);
+    let root = ItemLocation::root();
+
+    // #1
+    let (a, b) = tokio::join!(onedrive.get_item(root), onedrive.get_item(root));
+    let (a, b) = (a.expect("Cannot get root"), b.expect("Cannot get root"));
+    assert!(a.id.is_some());
+    assert_eq!(a.id, b.id);
+    assert_eq!(a.e_tag, b.e_tag);
+}
+
+// 6 requests, or more if changes are not visible immediately
+#[tokio::test]
+async fn test_watch_item() {
+    let onedrive = onedrive().await;
+    let folder_name = gen_filename();
+
+    // #1
+    let folder_id = onedrive
+        .create_folder(ItemLocation::root(), folder_name)
+        .await
+        .expect("Cannot create folder")
+        .id
+        .expect("Missing `id`");
+    let mut watcher = onedrive.watch_item(&folder_id, std::time::Duration::from_secs(1));
+
+    // #2
+    match watcher.next_event().await.expect("Cannot watch item") {
+        ItemEvent::Initial(item) => assert_eq!(item.id.as_ref(), Some(&folder_id)),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+
+    // #3
+    let mut patch = DriveItem::default();
+    patch.description = Some("watched".to_owned());
+    onedrive
+        .update_item(&folder_id, &patch)
+        .await
+        .expect("Cannot update folder");
+
+    // #4
+    match watcher.next_event().await.expect("Cannot watch item") {
+        ItemEvent::Modified(item) => assert_eq!(item.description.as_deref(), Some("watched")),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+
+    // #5
+    onedrive.delete(&folder_id).await.unwrap();
+
+    // #6
+    assert!(matches!(
+        watcher.next_event().await.expect("Cannot watch item"),
+        ItemEvent::Deleted,
+    ));
+}
+
+// 6 requests
+#[tokio::test]
+async fn test_list_children_consistent() {
+    let onedrive = onedrive().await;
+
+    // #1
+    let folder_id = onedrive
+        .create_folder(ItemLocation::root(), gen_filename())
+        .await
+        .expect("Cannot create folder")
+        .id
+        .expect("Missing `id`");
+
+    // #2, #3
+    for name in &["a.txt", "b.txt"] {
+        onedrive
+            .upload_small(
+                ItemLocation::child_of_id(&folder_id, FileName::new(name).unwrap()),
+                &b"data"[..],
+            )
+            .await
+            .expect("Cannot upload file");
+    }
+
+    // #4, #5
+    let mut names = onedrive
+        .list_children_consistent(&folder_id)
+        .await
+        .expect("Cannot list children")
+        .into_iter()
+        .map(|item| item.name.expect("Missing `name`"))
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["a.txt", "b.txt"]);
+
+    // #6
+    onedrive.delete(&folder_id).await.unwrap();
+}
+
+// 12 requests
+#[tokio::test]
+async fn test_remote_lock() {
+    use std::time::Duration;
+
+    let onedrive = onedrive().await;
+    let ttl = Duration::from_secs(600);
+
+    // #1
+    let folder_id = onedrive
+        .create_folder(ItemLocation::root(), gen_filename())
+        .await
+        .expect("Cannot create folder")
+        .id
+        .expect("Missing `id`");
+
+    // #2, #3
+    let mut lock = RemoteLock::acquire(&onedrive, &folder_id, "first", ttl)
+        .await
+        .expect("Cannot acquire lock");
+
+    // #4, #5, #6
+    let err = RemoteLock::acquire(&onedrive, &folder_id, "second", ttl)
+        .await
+        .expect_err("Lock should be exclusive");
+    assert!(err.is_name_conflict(), "Unexpected error: {}", err);
+
+    // #7
+    lock.refresh().await.expect("Cannot refresh lock");
+
+    // #8
+    lock.release().await.expect("Cannot release lock");
+
+    // #9, #10, #11
+    RemoteLock::acquire(&onedrive, &folder_id, "second", ttl)
+        .await
+        .expect("Cannot acquire released lock")
+        .release()
+        .await
+        .unwrap();
+
+    // #12
+    onedrive.delete(&folder_id).await.unwrap();
+}
+
+// 8 requests
+#[tokio::test]
+async fn test_upload_replace_atomic() {
+    let onedrive = onedrive().await;
+
+    // #1
+    let folder_id = onedrive
+        .create_folder(ItemLocation::root(), gen_filename())
+        .await
+        .expect("Cannot create folder")
+        .id
+        .expect("Missing `id`");
+
+    // #2
+    let file_name = gen_filename();
+    let file_loc = ItemLocation::child_of_id(&folder_id, file_name);
+    onedrive
+        .upload_small(file_loc, &b"old"[..])
+        .await
+        .expect("Cannot upload file");
+
+    // #3, #4, #5, #6
+    let item = onedrive
+        .upload_replace_atomic(file_loc, &b"new content"[..])
+        .await
+        .expect("Cannot replace file");
+    assert_eq!(item.name.as_deref(), Some(file_name.as_str()));
+    assert_eq!(item.size, Some(11));
+
+    // #7
+    let children = onedrive.list_children(&folder_id).await.unwrap();
+    assert_eq!(children.len(), 1, "Temporary file is left: {:?}", children);
+    assert_eq!(children[0].size, Some(11));
+
+    // #8
+    onedrive.delete(&folder_id).await.unwrap();
+}
+
+// 3 requests
+#[tokio::test]
+async fn test_root_shortcuts() {
+    let onedrive = onedrive().await;
+
+    // #1
+    let root = onedrive.get_root().await.expect("Cannot get root");
+    assert!(root.id.is_some() && root.folder.is_some());
+    assert!(root.web_url.is_none(), "Root contains unselected `webUrl`");
+
+    // #2
+    let items = onedrive
+        .root_delta()
+        .await
+        .expect("Cannot track root changes")
+        .fetch_next_page(&onedrive)
+        .await
+        .expect("Cannot fetch changes")
+        .expect("Missing the first page");
+    assert!(items.iter().any(|item| item.id == root.id), "Missing root");
+
+    // #3
+    let err = OneDrive::new(onedrive.access_token(), DriveId("inva_lid".to_owned()))
+        .get_root()
+        .await
+        .expect_err("Drive id should be invalid");
+    assert!(err.is_client_error(), "Unexpected error: {}", err);
+}
+
+// 1 request
+#[tokio::test]
+async fn test_get_drive_summary() {
+    let onedrive = onedrive().await;
+
+    // #1
+    let summary = onedrive
+        .get_drive_summary()
+        .await
+        .expect("Cannot get drive summary");
+    assert!(summary.drive_id.is_some(), "Missing drive id");
+    let quota = summary.quota.expect("Missing quota");
+    assert!(quota.total.is_some() && quota.used.is_some());
+    assert!(
+        summary.root_child_count.is_some(),
+        "Missing child count of root"
+    );
+}
+
+// 7 requests
+#[tokio::test]
+async fn test_track_folder_changes() {
+    let onedrive = onedrive().await;
+
+    use std::collections::HashSet;
+
+    let container_name = gen_filename();
+    let container_loc = rooted_location(container_name);
+
+    // #1
+    let container_id = onedrive
+        .create_folder(ItemLocation::root(), container_name)
+        .await
+        .expect("Cannot create container folder")
+        .id
+        .expect("Missing `id`");
+
+    // #2
+    let folder1_id = onedrive
+        .create_folder(container_loc, gen_filename())
+        .await
+        .expect("Failed to create folder1")
+        .id
+        .expect("Missing `id`");
+
+    {
+        // #3
+        let (initial_changes, _) = onedrive
+            .track_changes_from_initial(&container_id)
+            .await
+            .expect("Cannot track initial changes of folder")
+            .fetch_all(&onedrive)
+            .await
+            .expect("Cannot fetch all initial changes of folder");
+        let ids = initial_changes
+            .into_iter()
+            .map(|item| item.id.expect("Missing `id`"))
+            .collect::<HashSet<ItemId>>();
+        assert!(ids.contains(&container_id));
+        assert!(ids.contains(&folder1_id));
+    }
+
+    // #4
+    let delta_url = onedrive
+        .get_latest_delta_url(&container_id)
+        .await
+        .expect("Failed to get latest delta url of folder");
+
+    // #5
+    let folder2_id = onedrive
+        .create_folder(container_loc, gen_filename())
+        .await
+        .expect("Failed to create folder2")
+        .id
+        .expect("Missing `id`");
+
+    {
+        // #6
+        let (delta_changes, _) = onedrive
+            .track_changes_from_delta_url(&delta_url)
+            .await
+            .expect("Failed to track changes with delta url")
+            .fetch_all(&onedrive)
+            .await
+            .expect("Failed to fetch all changes with delta url");
+        let ids = delta_changes
+            .into_iter()
+            .map(|item| item.id.expect("Missing `id`"))
+            .collect::<HashSet<ItemId>>();
+        assert!(ids.contains(&folder2_id));
+        assert!(!ids.contains(&folder1_id));
+    }
+
+    // #7
+    onedrive.delete(container_loc).await.unwrap();
+}
+
 // 8 requests
 // This test fetch all changes from root folder, which may contains lots of files and take lots of time.
 #[tokio::test]
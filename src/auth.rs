@@ -1,11 +1,14 @@
 use crate::{
     error::{Error, Result},
-    util::handle_oauth2_error_response,
+    util::{handle_oauth2_error_response, RequestBuilderExt as _},
 };
 use reqwest::Client;
 use serde::Deserialize;
+use std::fmt;
 use url::Url;
 
+const AUTHORITY_URL: &str = "https://login.microsoftonline.com";
+
 /// A list of the Microsoft Graph permissions that you want the user to consent to.
 ///
 /// # See also
@@ -61,6 +64,154 @@ impl Permission {
     }
 }
 
+/// The credential of a confidential client, used to prove the identity of the application.
+///
+/// It is set through [`Auth::with_client_credential`][with_cred].
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/azure/active-directory/develop/active-directory-certificate-credentials)
+///
+/// [with_cred]: ./struct.Auth.html#method.with_client_credential
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum ClientCredential {
+    /// A client secret (application password).
+    Secret(String),
+    /// A pre-signed client assertion, which is a JWT signed with the private key of a
+    /// certificate registered for the application.
+    ///
+    /// Note that the assertion has an expiration time. It should be replaced
+    /// before it expires.
+    Assertion(String),
+    /// A certificate to sign a new client assertion for every request.
+    ///
+    /// This requires feature `client-certificate`.
+    #[cfg(feature = "client-certificate")]
+    Certificate(ClientCertificate),
+}
+
+// Avoid leaking secrets in logs.
+impl fmt::Debug for ClientCredential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Secret(_) => f.write_str("Secret(..)"),
+            Self::Assertion(_) => f.write_str("Assertion(..)"),
+            #[cfg(feature = "client-certificate")]
+            Self::Certificate(cert) => f.debug_tuple("Certificate").field(cert).finish(),
+        }
+    }
+}
+
+impl ClientCredential {
+    // `token_url` is the audience of signed assertions.
+    #[cfg_attr(not(feature = "client-certificate"), allow(unused_variables))]
+    fn to_params(&self, client_id: &str, token_url: &str) -> Result<Vec<(&'static str, String)>> {
+        const ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+        let assertion = match self {
+            Self::Secret(secret) => return Ok(vec![("client_secret", secret.clone())]),
+            Self::Assertion(assertion) => assertion.clone(),
+            #[cfg(feature = "client-certificate")]
+            Self::Certificate(cert) => cert.sign_assertion(client_id, token_url)?,
+        };
+        Ok(vec![
+            ("client_assertion_type", ASSERTION_TYPE.to_owned()),
+            ("client_assertion", assertion),
+        ])
+    }
+}
+
+/// A certificate for signing client assertions. (Feature `client-certificate`)
+///
+/// It is used as [`ClientCredential::Certificate`][cert].
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/azure/active-directory/develop/active-directory-certificate-credentials)
+///
+/// [cert]: ./enum.ClientCredential.html#variant.Certificate
+#[cfg(feature = "client-certificate")]
+#[derive(Clone)]
+pub struct ClientCertificate {
+    // Base64url-encoded SHA-1 thumbprint.
+    x5t: String,
+    key: jsonwebtoken::EncodingKey,
+}
+
+#[cfg(feature = "client-certificate")]
+impl fmt::Debug for ClientCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ClientCertificate")
+            .field("x5t", &self.x5t)
+            .finish()
+    }
+}
+
+#[cfg(feature = "client-certificate")]
+impl ClientCertificate {
+    /// How long a signed client assertion is valid.
+    pub const ASSERTION_LIFETIME: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+    /// Create a certificate credential from the hex-encoded SHA-1 thumbprint of
+    /// the certificate, and the PEM-encoded RSA private key.
+    ///
+    /// The thumbprint is shown in the Azure portal after uploading the certificate.
+    ///
+    /// Return `None` if `thumbprint` is not a valid hex-encoded SHA-1 hash,
+    /// or `private_key_pem` is not a valid RSA private key.
+    pub fn from_rsa_pem(thumbprint: &str, private_key_pem: &[u8]) -> Option<Self> {
+        if thumbprint.len() != 40 || !thumbprint.is_ascii() {
+            return None;
+        }
+        let digest = (0..thumbprint.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&thumbprint[i..i + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let key = jsonwebtoken::EncodingKey::from_rsa_pem(private_key_pem).ok()?;
+        Some(Self {
+            x5t: base64::encode_config(&digest, base64::URL_SAFE_NO_PAD),
+            key,
+        })
+    }
+
+    fn sign_assertion(&self, client_id: &str, audience: &str) -> Result<String> {
+        use std::{
+            sync::atomic::{AtomicU64, Ordering},
+            time::{SystemTime, UNIX_EPOCH},
+        };
+
+        #[derive(serde::Serialize)]
+        struct Claims<'a> {
+            aud: &'a str,
+            iss: &'a str,
+            sub: &'a str,
+            jti: String,
+            nbf: u64,
+            exp: u64,
+        }
+
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let claims = Claims {
+            aud: audience,
+            iss: client_id,
+            sub: client_id,
+            jti: format!(
+                "{:x}-{:x}",
+                now.as_nanos(),
+                COUNTER.fetch_add(1, Ordering::Relaxed),
+            ),
+            nbf: now.as_secs(),
+            exp: (now + Self::ASSERTION_LIFETIME).as_secs(),
+        };
+        let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+        header.x5t = Some(self.x5t.clone());
+        jsonwebtoken::encode(&header, &claims, &self.key)
+            .map_err(|_| Error::invalid_argument("Failed to sign client assertion"))
+    }
+}
+
 /// OAuth2 authentication and authorization basics for Microsoft Graph.
 ///
 /// # See also
@@ -71,9 +222,14 @@ pub struct Auth {
     client_id: String,
     permission: Permission,
     redirect_uri: String,
+    client_credential: Option<ClientCredential>,
+    tenant: String,
 }
 
 impl Auth {
+    /// The default tenant, allowing both work or school accounts and personal accounts.
+    pub const DEFAULT_TENANT: &'static str = "common";
+
     /// Create an new instance for OAuth2 to Microsoft Graph
     /// with specified client identifier and permission.
     pub fn new(client_id: String, permission: Permission, redirect_uri: String) -> Self {
@@ -94,9 +250,63 @@ impl Auth {
             client_id,
             permission,
             redirect_uri,
+            client_credential: None,
+            tenant: Self::DEFAULT_TENANT.to_owned(),
         }
     }
 
+    /// Set the tenant (authority) to sign in to, eg. a tenant id, a domain name like
+    /// `contoso.onmicrosoft.com`, `organizations` or `consumers`.
+    ///
+    /// It is used in URLs of the authorization and token endpoints, and as the audience
+    /// of client assertions signed by [`ClientCredential::Certificate`][cert].
+    /// A specific tenant is required by [`login_with_client_credentials`][client_cred].
+    ///
+    /// Default to be [`DEFAULT_TENANT`][default].
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/azure/active-directory/develop/active-directory-v2-protocols#endpoints)
+    ///
+    /// [cert]: ./enum.ClientCredential.html#variant.Certificate
+    /// [client_cred]: #method.login_with_client_credentials
+    /// [default]: #associatedconstant.DEFAULT_TENANT
+    pub fn with_tenant(mut self, tenant: String) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    /// Get the tenant set by [`with_tenant`][with_tenant].
+    ///
+    /// [with_tenant]: #method.with_tenant
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    fn endpoint_url(&self, endpoint: &str) -> String {
+        format!("{}/{}/oauth2/v2.0/{}", AUTHORITY_URL, self.tenant, endpoint)
+    }
+
+    /// Set the credential used for confidential clients.
+    ///
+    /// It is used in all login requests when no `client_secret` is passed explicitly,
+    /// which makes it possible to authenticate with a certificate instead of a client secret.
+    ///
+    /// # See also
+    /// [`ClientCredential`][cred]
+    ///
+    /// [cred]: ./enum.ClientCredential.html
+    pub fn with_client_credential(mut self, credential: ClientCredential) -> Self {
+        self.client_credential = Some(credential);
+        self
+    }
+
+    /// Get the client credential set by [`with_client_credential`][with_cred].
+    ///
+    /// [with_cred]: #method.with_client_credential
+    pub fn client_credential(&self) -> Option<&ClientCredential> {
+        self.client_credential.as_ref()
+    }
+
     /// Get the `client_id` used to create this instance.
     pub fn client_id(&self) -> &str {
         &self.client_id
@@ -114,7 +324,7 @@ impl Auth {
 
     fn auth_url(&self, response_type: &str) -> String {
         Url::parse_with_params(
-            "https://login.microsoftonline.com/common/oauth2/v2.0/authorize",
+            &self.endpoint_url("authorize"),
             &[
                 ("client_id", &*self.client_id),
                 ("scope", &self.permission.to_scope_string()),
@@ -123,7 +333,7 @@ impl Auth {
             ],
         )
         .unwrap()
-        .into_string()
+        .into()
     }
 
     /// Get the URL for web browser for code flow.
@@ -137,13 +347,23 @@ impl Auth {
     async fn request_authorize(
         &self,
         require_refresh: bool,
+        client_secret: Option<&str>,
         params: &[(&str, &str)],
     ) -> Result<TokenResponse> {
+        let token_url = self.endpoint_url("token");
+        let credential_params = match (client_secret, &self.client_credential) {
+            (Some(secret), _) => vec![("client_secret", secret.to_owned())],
+            (None, Some(cred)) => cred.to_params(&self.client_id, &token_url)?,
+            (None, None) => vec![("client_secret", String::new())],
+        };
+        let mut form = params.to_vec();
+        form.extend(credential_params.iter().map(|(k, v)| (*k, &**v)));
+
         let resp = self
             .client
-            .post("https://login.microsoftonline.com/common/oauth2/v2.0/token")
-            .form(params)
-            .send()
+            .post(&token_url)
+            .form(&form)
+            .send_logged()
             .await?;
 
         // Handle special error response.
@@ -167,9 +387,9 @@ impl Auth {
     ) -> Result<TokenResponse> {
         self.request_authorize(
             self.permission.offline_access,
+            client_secret,
             &[
                 ("client_id", &self.client_id as &str),
-                ("client_secret", client_secret.unwrap_or("")),
                 ("code", code),
                 ("grant_type", "authorization_code"),
                 ("redirect_uri", &self.redirect_uri),
@@ -205,9 +425,9 @@ impl Auth {
 
         self.request_authorize(
             true,
+            client_secret,
             &[
                 ("client_id", &self.client_id as &str),
-                ("client_secret", client_secret.unwrap_or("")),
                 ("grant_type", "refresh_token"),
                 ("redirect_uri", &self.redirect_uri),
                 ("refresh_token", refresh_token),
@@ -215,6 +435,42 @@ impl Auth {
         )
         .await
     }
+
+    /// Login as the application itself, without a user, using the client credentials flow.
+    ///
+    /// The application must be a confidential client, with `client_secret` passed or
+    /// a [client credential][with_cred] set, and granted application permissions by
+    /// an administrator. The [tenant][with_tenant] must be set to a specific one.
+    /// Permissions of the current [`Auth`][auth] are ignored, and all application
+    /// permissions granted are requested. No [`refresh_token`][refresh_token] is returned,
+    /// so simply login again when the access token expires.
+    ///
+    /// Note that there is no current user for the returned token, so only drives addressed
+    /// explicitly (eg. [`DriveLocation::from_user`][from_user]) can be accessed.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/auth-v2-service?view=graph-rest-1.0#4-get-an-access-token)
+    ///
+    /// [with_cred]: #method.with_client_credential
+    /// [with_tenant]: #method.with_tenant
+    /// [auth]: ./struct.Auth.html
+    /// [refresh_token]: ./struct.TokenResponse.html#structfield.refresh_token
+    /// [from_user]: ./struct.DriveLocation.html#method.from_user
+    pub async fn login_with_client_credentials(
+        &self,
+        client_secret: Option<&str>,
+    ) -> Result<TokenResponse> {
+        self.request_authorize(
+            false,
+            client_secret,
+            &[
+                ("client_id", &self.client_id as &str),
+                ("grant_type", "client_credentials"),
+                ("scope", "https://graph.microsoft.com/.default"),
+            ],
+        )
+        .await
+    }
 }
 
 /// Tokens and some additional data returned by a successful authorization.
@@ -227,7 +483,9 @@ pub struct TokenResponse {
     /// Indicates the token type value. The only type that Azure AD supports is Bearer.
     pub token_type: String,
     /// A list of the Microsoft Graph permissions that the access_token is valid for.
-    #[serde(deserialize_with = "space_separated_strings")]
+    ///
+    /// It is empty for the client credentials flow, which does not respond it.
+    #[serde(default, deserialize_with = "space_separated_strings")]
     pub scope: Vec<String>,
     /// How long the access token is valid (in seconds).
     #[serde(rename = "expires_in")]
@@ -271,3 +529,36 @@ where
 
     deserializer.deserialize_str(Visitor)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant() {
+        let auth = Auth::new(
+            "client".to_owned(),
+            Permission::new_read(),
+            "https://example.com/".to_owned(),
+        );
+        assert_eq!(auth.tenant(), Auth::DEFAULT_TENANT);
+        assert_eq!(
+            auth.endpoint_url("token"),
+            "https://login.microsoftonline.com/common/oauth2/v2.0/token",
+        );
+        let auth = auth.with_tenant("contoso.onmicrosoft.com".to_owned());
+        assert!(auth.code_auth_url().starts_with(
+            "https://login.microsoftonline.com/contoso.onmicrosoft.com/oauth2/v2.0/authorize?"
+        ));
+    }
+
+    #[test]
+    fn test_client_credentials_token_response() {
+        let resp: TokenResponse = serde_json::from_str(
+            r#"{ "token_type": "Bearer", "expires_in": 3599, "ext_expires_in": 3599, "access_token": "a" }"#,
+        )
+        .unwrap();
+        assert!(resp.scope.is_empty());
+        assert_eq!(resp.refresh_token, None);
+    }
+}
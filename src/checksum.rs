@@ -0,0 +1,272 @@
+use crate::{
+    error::{Error, Result},
+    resource::DriveItem,
+};
+use std::ops::Range;
+
+/// The QuickXorHash checksum used by OneDrive.
+///
+/// It is the only hash available on all kinds of drives (personal, business and SharePoint),
+/// and is returned in `file.hashes.quickXorHash` of [`DriveItem`][drive_item].
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/onedrive/developer/code-snippets/quickxorhash?view=odsp-graph-online)
+///
+/// [drive_item]: ./resource/struct.DriveItem.html
+#[derive(Clone, Debug, Default)]
+pub struct QuickXorHash {
+    data: [u8; Self::WIDTH_BYTES],
+    len: u64,
+}
+
+impl QuickXorHash {
+    const WIDTH_BYTES: usize = 20;
+    const WIDTH_BITS: u64 = 160;
+    const SHIFT: u64 = 11;
+
+    /// Create an empty hasher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed data into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            let bit = (self.len % Self::WIDTH_BITS * Self::SHIFT) % Self::WIDTH_BITS;
+            let (idx, shift) = ((bit / 8) as usize, bit % 8);
+            let v = u16::from(b) << shift;
+            self.data[idx] ^= v as u8;
+            self.data[(idx + 1) % Self::WIDTH_BYTES] ^= (v >> 8) as u8;
+            self.len += 1;
+        }
+    }
+
+    /// Get the count of bytes fed.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Check if no byte is fed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the raw 20-byte digest.
+    pub fn digest(&self) -> [u8; 20] {
+        let mut ret = self.data;
+        for (x, l) in ret[Self::WIDTH_BYTES - 8..]
+            .iter_mut()
+            .zip(&self.len.to_le_bytes())
+        {
+            *x ^= l;
+        }
+        ret
+    }
+
+    /// Get the base64-encoded digest, in the same format as `quickXorHash` returned by API.
+    pub fn digest_base64(&self) -> String {
+        base64::encode(self.digest())
+    }
+}
+
+/// Get the `quickXorHash` of a `DriveItem` from its `file.hashes` facet.
+pub(crate) fn quick_xor_hash_of(item: &DriveItem) -> Option<&str> {
+    item.file
+        .as_ref()?
+        .get("hashes")?
+        .get("quickXorHash")?
+        .as_str()
+}
+
+/// The checksum of an uploaded chunk.
+///
+/// # See also
+/// [`UploadVerifier`][verifier]
+///
+/// [verifier]: ./struct.UploadVerifier.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChunkReport {
+    /// The byte range of the chunk in the file.
+    pub range: Range<u64>,
+    /// The base64-encoded QuickXorHash of the chunk alone.
+    pub quick_xor_hash: String,
+}
+
+/// Checksum tracker for uploading in an [`UploadSession`][session].
+///
+/// Graph API does not accept checksums of upload parts. Instead, chunks uploaded through
+/// [`UploadSession::upload_part_with_checksum`][upload_part] are hashed locally, and
+/// the hash of the whole file is validated against the `quickXorHash` of the final item.
+///
+/// [session]: ./struct.UploadSession.html
+/// [upload_part]: ./struct.UploadSession.html#method.upload_part_with_checksum
+#[derive(Clone, Debug, Default)]
+pub struct UploadVerifier {
+    hasher: QuickXorHash,
+    chunks: Vec<ChunkReport>,
+    verified: Option<bool>,
+}
+
+impl UploadVerifier {
+    /// Create a verifier for a new upload from the beginning of the file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn from_prefix(hasher: QuickXorHash) -> Self {
+        let mut chunks = Vec::new();
+        if !hasher.is_empty() {
+            chunks.push(ChunkReport {
+                range: 0..hasher.len(),
+                quick_xor_hash: hasher.digest_base64(),
+            });
+        }
+        Self {
+            hasher,
+            chunks,
+            verified: None,
+        }
+    }
+
+    /// Get the count of bytes recorded, which is also the offset of the next chunk.
+    pub fn uploaded_len(&self) -> u64 {
+        self.hasher.len()
+    }
+
+    /// Get reports of all chunks recorded, in upload order.
+    pub fn chunks(&self) -> &[ChunkReport] {
+        &self.chunks
+    }
+
+    /// Get the base64-encoded QuickXorHash of all chunks recorded.
+    pub fn quick_xor_hash(&self) -> String {
+        self.hasher.digest_base64()
+    }
+
+    /// Get the verification result of the final item.
+    ///
+    /// It is `None` if the upload is not completed yet, or the server
+    /// did not return the hash of the item.
+    pub fn verified(&self) -> Option<bool> {
+        self.verified
+    }
+
+    pub(crate) fn record(&mut self, data: &[u8], range: Range<u64>) {
+        assert_eq!(
+            range.start,
+            self.hasher.len(),
+            "Chunks must be uploaded sequentially from the beginning for checksum",
+        );
+        let mut chunk_hasher = QuickXorHash::new();
+        chunk_hasher.update(data);
+        self.hasher.update(data);
+        self.chunks.push(ChunkReport {
+            range,
+            quick_xor_hash: chunk_hasher.digest_base64(),
+        });
+    }
+
+    pub(crate) fn verify(&mut self, item: &DriveItem) -> Result<()> {
+        let actual = match quick_xor_hash_of(item) {
+            Some(actual) => actual,
+            None => return Ok(()),
+        };
+        let expected = self.quick_xor_hash();
+        let matched = expected == actual;
+        self.verified = Some(matched);
+        if matched {
+            Ok(())
+        } else {
+            Err(Error::checksum_mismatch(expected, actual.to_owned()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Bit-by-bit reference implementation.
+    fn reference_digest(data: &[u8]) -> [u8; 20] {
+        let mut bits = [false; 160];
+        for (i, &b) in data.iter().enumerate() {
+            for j in 0..8 {
+                if b >> j & 1 == 1 {
+                    let pos = (i * 11 + j) % 160;
+                    bits[pos] = !bits[pos];
+                }
+            }
+        }
+        let mut ret = [0u8; 20];
+        for (pos, &bit) in bits.iter().enumerate() {
+            if bit {
+                ret[pos / 8] |= 1 << (pos % 8);
+            }
+        }
+        for (x, l) in ret[12..].iter_mut().zip(&(data.len() as u64).to_le_bytes()) {
+            *x ^= l;
+        }
+        ret
+    }
+
+    #[test]
+    fn test_quick_xor_hash() {
+        assert_eq!(
+            QuickXorHash::new().digest_base64(),
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+        );
+
+        // Known digests, computed by the reference implementation in Microsoft's
+        // OneDrive API documentation.
+        for (data, expected) in [
+            (&b"J"[..], "SgAAAAAAAAAAAAAAAQAAAAAAAAA="),
+            (&b"\xb5\xb4"[..], "taAFAAAAAAAAAAAAAgAAAAAAAAA="),
+        ] {
+            let mut hasher = QuickXorHash::new();
+            hasher.update(data);
+            assert_eq!(hasher.digest_base64(), expected);
+        }
+
+        let data = (0..1000u32)
+            .map(|i| (i * 7 + i / 13) as u8)
+            .collect::<Vec<_>>();
+        let mut hasher = QuickXorHash::new();
+        for chunk in data.chunks(37) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.len(), 1000);
+        assert_eq!(hasher.digest(), reference_digest(&data));
+        assert_eq!(hasher.digest_base64(), "iZszAA9j9Se8iKx9lPRm0o+wdiY=");
+    }
+
+    #[test]
+    fn test_upload_verifier() {
+        let mut verifier = UploadVerifier::new();
+        verifier.record(b"hello, ", 0..7);
+        verifier.record(b"world", 7..12);
+        assert_eq!(verifier.chunks().len(), 2);
+        assert_eq!(verifier.chunks()[1].range, 7..12);
+
+        let mut hasher = QuickXorHash::new();
+        hasher.update(b"hello, world");
+        let expected = hasher.digest_base64();
+        assert_eq!(verifier.quick_xor_hash(), expected);
+
+        let mut item = DriveItem::default();
+        verifier.verify(&item).unwrap();
+        assert_eq!(verifier.verified(), None);
+
+        item.file = Some(Box::new(
+            serde_json::json!({ "hashes": { "quickXorHash": expected } }),
+        ));
+        verifier.verify(&item).unwrap();
+        assert_eq!(verifier.verified(), Some(true));
+
+        item.file = Some(Box::new(serde_json::json!({
+            "hashes": { "quickXorHash": "AAAAAAAAAAAAAAAAAAAAAAAAAAA=" },
+        })));
+        assert!(verifier.verify(&item).unwrap_err().is_checksum_mismatch());
+        assert_eq!(verifier.verified(), Some(false));
+    }
+}
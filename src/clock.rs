@@ -0,0 +1,77 @@
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+/// The future returned by [`Sleeper::sleep`][sleep].
+///
+/// [sleep]: ./trait.Sleeper.html#tymethod.sleep
+pub type SleepFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// A source of the current time.
+///
+/// It is used to check token expiry in [`TokenRefresher`][refresher], and by time-dependent
+/// logic of [`OneDrive`][with_clock]. Its time can be passed to methods like
+/// [`UploadSession::is_expired_at`][is_expired_at].
+/// Replace it with a fake clock to test time-dependent logic deterministically.
+///
+/// [refresher]: ./struct.TokenRefresher.html
+/// [with_clock]: ./struct.OneDrive.html#method.with_clock
+/// [is_expired_at]: ./struct.UploadSession.html#method.is_expired_at
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Get the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// A timer to wait for some time.
+///
+/// It is used for sleeping before retrying throttled requests (`Retry-After`), between
+/// refreshes in [`TokenRefresher::run`][run] and between polls of long-running operations.
+/// Implement it to integrate the timer of your runtime, or to skip waiting in tests.
+///
+/// [run]: ./struct.TokenRefresher.html#method.run
+pub trait Sleeper: fmt::Debug + Send + Sync {
+    /// Return a future which completes after `duration`.
+    fn sleep(&self, duration: Duration) -> SleepFuture;
+}
+
+/// The default [`Clock`][clock] using the system time.
+///
+/// [clock]: ./trait.Clock.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// The default [`Sleeper`][sleeper] using `tokio::time::sleep`.
+///
+/// It requires the tokio timer to be enabled in the runtime.
+///
+/// [sleeper]: ./trait.Sleeper.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioSleeper;
+
+impl Sleeper for TokioSleeper {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+impl<T: Clock + ?Sized> Clock for Arc<T> {
+    fn now(&self) -> SystemTime {
+        (**self).now()
+    }
+}
+
+impl<T: Sleeper + ?Sized> Sleeper for Arc<T> {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        (**self).sleep(duration)
+    }
+}
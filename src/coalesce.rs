@@ -0,0 +1,269 @@
+use crate::{
+    error::{Error, Result},
+    util::execute_logged,
+};
+use bytes::{Bytes, BytesMut};
+use futures_util::future::{BoxFuture, FutureExt as _, WeakShared};
+use reqwest::{header::HeaderMap, Client, Request, Response, StatusCode};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+type ResponseFuture = BoxFuture<'static, std::result::Result<Received, Arc<reqwest::Error>>>;
+
+/// In-flight requests by their keys. Entries are weak and tagged with a unique id,
+/// so that an abandoned request is neither kept alive nor shared with later requests.
+type InFlight = Arc<Mutex<HashMap<String, (u64, WeakShared<ResponseFuture>)>>>;
+
+#[derive(Clone, Debug)]
+enum Received {
+    Response(Arc<RawResponse>),
+    TooLarge(u64),
+}
+
+/// A fully received response, which can be shared by all waiters of a coalesced request.
+#[derive(Debug)]
+struct RawResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl RawResponse {
+    /// Receive the whole body, or return `TooLarge` once it exceeds `limit` bytes.
+    async fn receive(mut resp: Response, limit: Option<u64>) -> reqwest::Result<Received> {
+        let limit = limit.unwrap_or(u64::MAX);
+        if resp.content_length().is_some_and(|len| len > limit) {
+            return Ok(Received::TooLarge(limit));
+        }
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let mut body = BytesMut::new();
+        while let Some(chunk) = resp.chunk().await? {
+            if (body.len() + chunk.len()) as u64 > limit {
+                return Ok(Received::TooLarge(limit));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(Received::Response(Arc::new(Self {
+            status,
+            headers,
+            body: body.freeze(),
+        })))
+    }
+
+    fn to_response(&self) -> Response {
+        let mut resp = http::Response::new(self.body.clone());
+        *resp.status_mut() = self.status;
+        *resp.headers_mut() = self.headers.clone();
+        resp.into()
+    }
+}
+
+/// Deduplicator of identical in-flight requests.
+///
+/// Requests are identical if they have the same method, url (including the query)
+/// and headers. Only requests without body should be sent through it.
+#[derive(Default)]
+pub(crate) struct Coalescer {
+    in_flight: InFlight,
+    next_id: AtomicU64,
+}
+
+impl fmt::Debug for Coalescer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Coalescer")
+            .field("in_flight", &self.in_flight.lock().unwrap().len())
+            .finish()
+    }
+}
+
+/// Removes the entry of a request from `in_flight` when its future completes or
+/// is dropped by all waiters.
+struct RemoveGuard {
+    in_flight: InFlight,
+    key: String,
+    id: u64,
+}
+
+impl Drop for RemoveGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        // The entry may already be replaced by a newer request after this one is abandoned.
+        if in_flight
+            .get(&self.key)
+            .is_some_and(|(id, _)| *id == self.id)
+        {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+impl Coalescer {
+    /// Send `request`, or wait for an identical one already in flight and share its response.
+    ///
+    /// The response body is received into memory at most `limit` bytes.
+    pub(crate) async fn execute(
+        &self,
+        client: &Client,
+        request: Request,
+        limit: Option<u64>,
+    ) -> Result<Response> {
+        let key = request_key(&request, limit);
+        let fut = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key).and_then(|(_, weak)| weak.upgrade()) {
+                Some(fut) => fut,
+                None => {
+                    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                    let guard = RemoveGuard {
+                        in_flight: self.in_flight.clone(),
+                        key: key.clone(),
+                        id,
+                    };
+                    let resp = execute_logged(client, request);
+                    let fut = async move {
+                        // Dropped on completion or when abandoned, so later requests
+                        // never get this response.
+                        let _guard = guard;
+                        match resp.await {
+                            Ok(resp) => RawResponse::receive(resp, limit).await.map_err(Arc::new),
+                            Err(err) => Err(Arc::new(err)),
+                        }
+                    }
+                    .boxed()
+                    .shared();
+                    let weak = fut.downgrade().expect("Not polled yet");
+                    in_flight.insert(key, (id, weak));
+                    fut
+                }
+            }
+        };
+        match fut.await {
+            Ok(Received::Response(raw)) => Ok(raw.to_response()),
+            Ok(Received::TooLarge(limit)) => Err(Error::response_too_large(limit)),
+            Err(err) => Err(Error::from_shared_request(err)),
+        }
+    }
+}
+
+fn request_key(request: &Request, limit: Option<u64>) -> String {
+    let mut key = format!("{} {} {:?}", request.method(), request.url(), limit);
+    for (name, value) in request.headers() {
+        key.push('\n');
+        key.push_str(name.as_str());
+        key.push_str(": ");
+        key.push_str(&String::from_utf8_lossy(value.as_bytes()));
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{resource::DriveItem, util::ResponseExt as _};
+    use reqwest::header;
+
+    #[test]
+    fn test_request_key() {
+        let client = Client::new();
+        let key_of = |req: reqwest::RequestBuilder| request_key(&req.build().unwrap(), None);
+        let get = || client.get("https://example.com/items/1?$select=id");
+
+        assert_eq!(key_of(get()), key_of(get()));
+        assert_ne!(
+            key_of(get()),
+            key_of(client.get("https://example.com/items/1?$select=name")),
+        );
+        assert_ne!(
+            key_of(get()),
+            key_of(get().header(header::IF_NONE_MATCH, "tag"))
+        );
+        assert_ne!(
+            key_of(get().bearer_auth("a")),
+            key_of(get().bearer_auth("b"))
+        );
+    }
+
+    #[test]
+    fn test_remove_abandoned() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let _rt = rt.enter();
+        let client = Client::new();
+        let coalescer = Coalescer::default();
+        let req = client.get("http://127.0.0.1:9/").build().unwrap();
+        // Polled once and then dropped by its only waiter.
+        let _ = coalescer.execute(&client, req, None).now_or_never();
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+
+        // A guard of an abandoned request does not remove a newer entry with the same key.
+        let fut = async { Ok(Received::TooLarge(0)) }.boxed().shared();
+        let weak = fut.downgrade().unwrap();
+        coalescer
+            .in_flight
+            .lock()
+            .unwrap()
+            .insert("key".to_owned(), (1, weak));
+        drop(RemoveGuard {
+            in_flight: coalescer.in_flight.clone(),
+            key: "key".to_owned(),
+            id: 0,
+        });
+        assert!(coalescer.in_flight.lock().unwrap().contains_key("key"));
+    }
+
+    #[test]
+    fn test_receive_limit() {
+        let resp = || Response::from(http::Response::new(&b"0123456789"[..]));
+        let received = RawResponse::receive(resp(), Some(10))
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert!(matches!(received, Received::Response(raw) if raw.body.len() == 10));
+        let received = RawResponse::receive(resp(), Some(9))
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert!(matches!(received, Received::TooLarge(9)));
+    }
+
+    #[test]
+    fn test_shared_response() {
+        let raw = RawResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(br#"{ "id": "1", "name": "a" }"#),
+        };
+        for _ in 0..2 {
+            let item: DriveItem = raw
+                .to_response()
+                .parse(false)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+            assert_eq!(item.name.as_deref(), Some("a"));
+        }
+
+        let raw = RawResponse {
+            status: StatusCode::NOT_FOUND,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(br#"{ "error": { "code": "itemNotFound", "message": "" } }"#),
+        };
+        let err = raw
+            .to_response()
+            .parse_optional::<DriveItem>(false)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.status_code(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(err.error_response().unwrap().code, "itemNotFound");
+    }
+}
@@ -1,5 +1,6 @@
-use crate::resource::{ErrorResponse, OAuth2ErrorResponse};
+use crate::resource::{DriveItem, ErrorResponse, OAuth2ErrorResponse};
 use reqwest::StatusCode;
+use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 
 /// An alias to `Result` of [`Error`][error].
@@ -19,24 +20,75 @@ enum ErrorKind {
     // Errors about ser/de are included.
     #[error("Request error: {0}")]
     RequestError(reqwest::Error),
+    #[error("Request error: {0}")]
+    SharedRequestError(Arc<reqwest::Error>),
     #[error("Unexpected response: {reason}")]
     UnexpectedResponse { reason: &'static str },
     #[error("Api error with {status}: ({}) {}", .response.code, .response.message)]
     ErrorResponse {
         status: StatusCode,
         response: ErrorResponse,
+        retry_after: Option<Duration>,
     },
     #[error("OAuth2 error with {status}: ({}) {}", .response.error, .response.error_description)]
     OAuth2Error {
         status: StatusCode,
         response: OAuth2ErrorResponse,
     },
+    #[error("Token store error: {0}")]
+    TokenStoreError(std::io::Error),
+    #[error("Request accepted as an async job (monitor: {})", .monitor_url.as_deref().unwrap_or("unknown"))]
+    AsyncJobAccepted { monitor_url: Option<String> },
+    #[error("Upload session expired (expiration: {}): {source}", .expiration_date_time.as_deref().unwrap_or("unknown"))]
+    SessionExpired {
+        expiration_date_time: Option<String>,
+        source: Error,
+    },
+    #[error("I/O error: {0}")]
+    IoError(std::io::Error),
+    #[error("Name conflict with existing item {}: {source}", .existing.as_ref().and_then(|item| item.id.as_ref()).map_or("unknown", |id| id.as_str()))]
+    NameConflict {
+        existing: Option<Box<DriveItem>>,
+        source: Error,
+    },
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("Response exceeds the limit of {limit} {unit}")]
+    ResponseLimitExceeded { limit: u64, unit: &'static str },
+    #[error("Invalid argument: {reason}")]
+    InvalidArgument { reason: &'static str },
+    #[error("Deserialization error: {0}")]
+    DeserializeError(serde_json::Error),
 }
 
 impl Error {
-    pub(crate) fn from_error_response(status: StatusCode, response: ErrorResponse) -> Self {
+    pub(crate) fn from_error_response(
+        status: StatusCode,
+        response: ErrorResponse,
+        retry_after: Option<Duration>,
+    ) -> Self {
         Self {
-            inner: Box::new(ErrorKind::ErrorResponse { status, response }),
+            inner: Box::new(ErrorKind::ErrorResponse {
+                status,
+                response,
+                retry_after,
+            }),
+        }
+    }
+
+    /// An error as if the server rejected an `If-Match` precondition.
+    pub(crate) fn precondition_failed(message: &str) -> Self {
+        let response = ErrorResponse {
+            code: "preconditionFailed".to_owned(),
+            message: message.to_owned(),
+            inner_error: None,
+        };
+        Self::from_error_response(StatusCode::PRECONDITION_FAILED, response, None)
+    }
+
+    pub(crate) fn from_shared_request(source: Arc<reqwest::Error>) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::SharedRequestError(source)),
         }
     }
 
@@ -46,6 +98,18 @@ impl Error {
         }
     }
 
+    pub(crate) fn invalid_argument(reason: &'static str) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::InvalidArgument { reason }),
+        }
+    }
+
+    pub(crate) fn from_deserialize(source: serde_json::Error) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::DeserializeError(source)),
+        }
+    }
+
     pub(crate) fn from_oauth2_error_response(
         status: StatusCode,
         response: OAuth2ErrorResponse,
@@ -55,10 +119,233 @@ impl Error {
         }
     }
 
+    pub(crate) fn from_token_store(source: std::io::Error) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::TokenStoreError(source)),
+        }
+    }
+
+    pub(crate) fn from_io(source: std::io::Error) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::IoError(source)),
+        }
+    }
+
+    /// Split the error of a request covering `n` operations into one error for each of them.
+    ///
+    /// Errors which cannot be cloned are kept for the first operation only, and the rest
+    /// get an unexpected response error.
+    pub(crate) fn split(self, n: usize) -> Vec<Self> {
+        let kind = match *self.inner {
+            ErrorKind::RequestError(source) => ErrorKind::SharedRequestError(Arc::new(source)),
+            kind => kind,
+        };
+        let clone = |kind: &ErrorKind| match kind {
+            ErrorKind::SharedRequestError(source) => ErrorKind::SharedRequestError(source.clone()),
+            ErrorKind::ErrorResponse {
+                status,
+                response,
+                retry_after,
+            } => ErrorKind::ErrorResponse {
+                status: *status,
+                response: response.clone(),
+                retry_after: *retry_after,
+            },
+            ErrorKind::UnexpectedResponse { reason } => ErrorKind::UnexpectedResponse { reason },
+            ErrorKind::AsyncJobAccepted { monitor_url } => ErrorKind::AsyncJobAccepted {
+                monitor_url: monitor_url.clone(),
+            },
+            ErrorKind::ResponseLimitExceeded { limit, unit } => ErrorKind::ResponseLimitExceeded {
+                limit: *limit,
+                unit,
+            },
+            _ => ErrorKind::UnexpectedResponse {
+                reason: "The request covering this operation failed",
+            },
+        };
+        let mut errors = (1..n)
+            .map(|_| Self {
+                inner: Box::new(clone(&kind)),
+            })
+            .collect::<Vec<_>>();
+        if n != 0 {
+            errors.insert(
+                0,
+                Self {
+                    inner: Box::new(kind),
+                },
+            );
+        }
+        errors
+    }
+
+    pub(crate) fn session_expired(source: Error, expiration_date_time: Option<String>) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::SessionExpired {
+                expiration_date_time,
+                source,
+            }),
+        }
+    }
+
+    pub(crate) fn async_job_accepted(monitor_url: Option<String>) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::AsyncJobAccepted { monitor_url }),
+        }
+    }
+
+    pub(crate) fn name_conflict(source: Error, existing: Option<DriveItem>) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::NameConflict {
+                existing: existing.map(Box::new),
+                source,
+            }),
+        }
+    }
+
+    pub(crate) fn checksum_mismatch(expected: String, actual: String) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::ChecksumMismatch { expected, actual }),
+        }
+    }
+
+    pub(crate) fn response_too_large(limit: u64) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::ResponseLimitExceeded {
+                limit,
+                unit: "bytes",
+            }),
+        }
+    }
+
+    pub(crate) fn too_many_items(limit: usize) -> Self {
+        Self {
+            inner: Box::new(ErrorKind::ResponseLimitExceeded {
+                limit: limit as u64,
+                unit: "items",
+            }),
+        }
+    }
+
+    /// Check if the error is caused by an unexpected HTTP 202 ACCEPTED response,
+    /// which means the request is accepted and will be processed as an async job.
+    ///
+    /// # See also
+    /// [`async_job_monitor_url`][monitor_url]
+    ///
+    /// [monitor_url]: #method.async_job_monitor_url
+    pub fn is_async_job_accepted(&self) -> bool {
+        matches!(&*self.inner, ErrorKind::AsyncJobAccepted { .. })
+    }
+
+    /// Get the url to monitor the async job from the `Location` header, if the error is
+    /// caused by an unexpected HTTP 202 ACCEPTED response.
+    ///
+    /// The progress of the job can be fetched through
+    /// [`CopyProgressMonitor::from_monitor_url`][monitor].
+    ///
+    /// [monitor]: ./struct.CopyProgressMonitor.html#method.from_monitor_url
+    pub fn async_job_monitor_url(&self) -> Option<&str> {
+        match &*self.inner {
+            ErrorKind::AsyncJobAccepted { monitor_url } => monitor_url.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Check if the error is caused by an expired (or already finished or cancelled)
+    /// upload session.
+    ///
+    /// The upload should be restarted with a new upload session.
+    ///
+    /// # See also
+    /// [`UploadSession::is_expired`][is_expired]
+    ///
+    /// [is_expired]: ./struct.UploadSession.html#method.is_expired
+    pub fn is_session_expired(&self) -> bool {
+        matches!(&*self.inner, ErrorKind::SessionExpired { .. })
+    }
+
+    /// Check if the error is caused by an existing item with the same name,
+    /// that is, HTTP 409 CONFLICT with error code `nameAlreadyExists`.
+    ///
+    /// # See also
+    /// [`conflicting_item`][conflicting_item]
+    ///
+    /// [conflicting_item]: #method.conflicting_item
+    pub fn is_name_conflict(&self) -> bool {
+        match &*self.inner {
+            ErrorKind::NameConflict { .. } => true,
+            ErrorKind::ErrorResponse {
+                status, response, ..
+            } => *status == StatusCode::CONFLICT && response.code == "nameAlreadyExists",
+            _ => false,
+        }
+    }
+
+    /// Get the existing item causing the name conflict, if it is looked up.
+    ///
+    /// Graph API does not report the existing item in conflict errors. It is only available
+    /// when [`OneDrive::with_name_conflict_lookup`][lookup] is enabled, and contains
+    /// `id`, `name`, `e_tag`, `c_tag`, `size`, `file` and `folder` of the item.
+    ///
+    /// [lookup]: ./struct.OneDrive.html#method.with_name_conflict_lookup
+    pub fn conflicting_item(&self) -> Option<&DriveItem> {
+        match &*self.inner {
+            ErrorKind::NameConflict { existing, .. } => existing.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Check if the error is caused by a mismatch between the locally computed checksum
+    /// and the one reported by the server after an upload.
+    ///
+    /// The uploaded item is created but its content may be corrupted.
+    ///
+    /// # See also
+    /// [`UploadSession::upload_part_with_checksum`][upload_part]
+    ///
+    /// [upload_part]: ./struct.UploadSession.html#method.upload_part_with_checksum
+    pub fn is_checksum_mismatch(&self) -> bool {
+        matches!(&*self.inner, ErrorKind::ChecksumMismatch { .. })
+    }
+
+    /// Check if the error is caused by failing to save or load a refresh token
+    /// through a [`TokenStore`][store].
+    ///
+    /// [store]: ./trait.TokenStore.html
+    pub fn is_token_store_error(&self) -> bool {
+        matches!(&*self.inner, ErrorKind::TokenStoreError(_))
+    }
+
+    /// Check if the error is caused by a response exceeding the size or item count limits
+    /// set by [`OneDrive::with_response_limits`][limits].
+    ///
+    /// [limits]: ./struct.OneDrive.html#method.with_response_limits
+    pub fn is_response_limit_exceeded(&self) -> bool {
+        matches!(&*self.inner, ErrorKind::ResponseLimitExceeded { .. })
+    }
+
+    /// Check if the error is caused by arguments inconsistent with each other or with
+    /// the `OneDrive`, detected locally before sending any request.
+    pub fn is_invalid_argument(&self) -> bool {
+        matches!(&*self.inner, ErrorKind::InvalidArgument { .. })
+    }
+
+    /// Check if the error is caused by malformed data provided locally,
+    /// eg. a state saved by [`Transfer::save_state`][save_state].
+    ///
+    /// [save_state]: ./trait.Transfer.html#tymethod.save_state
+    pub fn is_deserialize_error(&self) -> bool {
+        matches!(&*self.inner, ErrorKind::DeserializeError(_))
+    }
+
     /// Get the error response from API if caused by error status code.
     pub fn error_response(&self) -> Option<&ErrorResponse> {
         match &*self.inner {
             ErrorKind::ErrorResponse { response, .. } => Some(response),
+            ErrorKind::SessionExpired { source, .. } | ErrorKind::NameConflict { source, .. } => {
+                source.error_response()
+            }
             _ => None,
         }
     }
@@ -75,12 +362,82 @@ impl Error {
     pub fn status_code(&self) -> Option<StatusCode> {
         match &*self.inner {
             ErrorKind::RequestError(source) => source.status(),
-            ErrorKind::UnexpectedResponse { .. } => None,
+            ErrorKind::SharedRequestError(source) => source.status(),
+            ErrorKind::UnexpectedResponse { .. }
+            | ErrorKind::TokenStoreError(_)
+            | ErrorKind::IoError(_)
+            | ErrorKind::ChecksumMismatch { .. }
+            | ErrorKind::ResponseLimitExceeded { .. }
+            | ErrorKind::InvalidArgument { .. }
+            | ErrorKind::DeserializeError(_) => None,
+            ErrorKind::AsyncJobAccepted { .. } => Some(StatusCode::ACCEPTED),
+            ErrorKind::SessionExpired { source, .. } | ErrorKind::NameConflict { source, .. } => {
+                source.status_code()
+            }
             ErrorKind::ErrorResponse { status, .. } | ErrorKind::OAuth2Error { status, .. } => {
                 Some(*status)
             }
         }
     }
+
+    /// Check if the error is caused by an HTTP 4xx status code.
+    ///
+    /// These errors are usually caused by invalid requests, and should not be retried
+    /// without changes, except for those being [transient][is_transient].
+    ///
+    /// [is_transient]: #method.is_transient
+    pub fn is_client_error(&self) -> bool {
+        self.status_code()
+            .is_some_and(|status| status.is_client_error())
+    }
+
+    /// Check if the error is caused by an HTTP 5xx status code.
+    pub fn is_server_error(&self) -> bool {
+        self.status_code()
+            .is_some_and(|status| status.is_server_error())
+    }
+
+    /// Check if the error is transient, so the same request may succeed if retried later.
+    ///
+    /// It includes timeouts and connection failures, and HTTP 408 REQUEST_TIMEOUT,
+    /// 429 TOO_MANY_REQUESTS, 502 BAD_GATEWAY, 503 SERVICE_UNAVAILABLE and
+    /// 504 GATEWAY_TIMEOUT. The time to wait may be given by [`retry_after`][retry_after].
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/best-practices-concept#handling-expected-errors)
+    ///
+    /// [retry_after]: #method.retry_after
+    pub fn is_transient(&self) -> bool {
+        let is_transport_error = |source: &reqwest::Error| {
+            source.status().is_none() && (source.is_timeout() || source.is_connect())
+        };
+        match &*self.inner {
+            ErrorKind::RequestError(source) if is_transport_error(source) => true,
+            ErrorKind::SharedRequestError(source) if is_transport_error(source) => true,
+            _ => matches!(
+                self.status_code(),
+                Some(StatusCode::REQUEST_TIMEOUT)
+                    | Some(StatusCode::TOO_MANY_REQUESTS)
+                    | Some(StatusCode::BAD_GATEWAY)
+                    | Some(StatusCode::SERVICE_UNAVAILABLE)
+                    | Some(StatusCode::GATEWAY_TIMEOUT)
+            ),
+        }
+    }
+
+    /// Get the time to wait before retrying, if the API responded `Retry-After`.
+    ///
+    /// It is usually set when the request is throttled (HTTP 429 TOO_MANY_REQUESTS or
+    /// 503 SERVICE_UNAVAILABLE).
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/throttling)
+    pub fn retry_after(&self) -> Option<Duration> {
+        match &*self.inner {
+            ErrorKind::ErrorResponse { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
 }
 
 impl From<reqwest::Error> for Error {
@@ -48,10 +48,32 @@
 //!
 //!   Be carefully using it and **do NOT use it in production**.
 //!
+//! - `client-certificate`
+//!
+//!   Support signing client assertions with a certificate in [`ClientCertificate`][client_cert],
+//!   to authenticate confidential clients without client secrets.
+//!
+//! - `token-encryption`
+//!
+//!   Provide [`AesGcmCipher`][aes_gcm] to encrypt refresh tokens persisted by
+//!   [`FileTokenStore`][file_store].
+//!
+//! # Logging
+//! Every request is logged at debug level through the [`log`][log] facade, including
+//! the method, url, response status and timing. Enable debug logs of target `onedrive_api`
+//! in your logger (eg. `RUST_LOG=onedrive_api=debug` with `env_logger`) to diagnose API issues.
+//!
+//! Access tokens are never logged. Pre-authenticated urls, like download urls and
+//! upload session urls, are redacted except their origin.
+//!
 //! [ms_onedrive]: https://products.office.com/en-us/onedrive/online-cloud-storage
 //! [ms_graph]: https://docs.microsoft.com/graph/overview
 //! [one_drive]: ./struct.OneDrive.html
 //! [auth]: ./struct.Auth.html
+//! [client_cert]: ./struct.ClientCertificate.html
+//! [aes_gcm]: ./struct.AesGcmCipher.html
+//! [file_store]: ./struct.FileTokenStore.html
+//! [log]: https://docs.rs/log
 //! [api]: ./trait.Api.html
 //! [api_execute]: ./trait.Api.html#tymethod.execute
 //! [client]: ./trait.Client.html
@@ -61,25 +83,45 @@
 use serde::{de, Serialize};
 
 mod auth;
+mod checksum;
+mod clock;
+mod coalesce;
 mod error;
+mod lock;
 mod onedrive;
 pub mod option;
 pub mod resource;
+pub mod search;
+mod token;
+mod transfer;
 mod util;
 
 pub use self::{
-    auth::{Auth, Permission, TokenResponse},
+    auth::{Auth, ClientCredential, Permission, TokenResponse},
+    checksum::{ChunkReport, QuickXorHash, UploadVerifier},
+    clock::{Clock, SleepFuture, Sleeper, SystemClock, TokioSleeper},
     error::{Error, Result},
+    lock::RemoteLock,
     onedrive::{
-        CopyProgressMonitor, ListChildrenFetcher, OneDrive, TrackChangeFetcher, UploadSession,
-        UploadSessionMeta,
+        Capabilities, ChildrenQuery, CopyProgressMonitor, CreatedItem, DriveSummary, FolderSize,
+        ItemEvent, ItemWatcher, ListChildrenFetcher, OneDrive, Page, ResponseLimits, ResumedUpload,
+        Timeouts, TrackChangeFetcher, UploadCheckpoint, UploadSession, UploadSessionMeta,
     },
     resource::{DriveId, ItemId, Tag},
-    util::{DriveLocation, FileName, ItemLocation},
+    token::{FileTokenStore, SharedToken, TokenCipher, TokenRefresher, TokenStore},
+    transfer::{
+        DownloadTransfer, Transfer, TransferFuture, TransferProgress, TransferQueue, TransferState,
+        UploadTransfer,
+    },
+    util::{DeltaToken, DriveLocation, FileName, ItemLocation},
 };
 
+#[cfg(feature = "client-certificate")]
+pub use self::auth::ClientCertificate;
 #[cfg(feature = "beta")]
-pub use self::onedrive::{CopyProgress, CopyStatus};
+pub use self::onedrive::{ActivityFetcher, AsyncOperationStatus, CopyProgress};
+#[cfg(feature = "token-encryption")]
+pub use self::token::AesGcmCipher;
 
 /// The conflict resolution behavior for actions that create a new item.
 ///
@@ -99,7 +141,7 @@ pub enum ConflictBehavior {
 }
 
 /// A half-open byte range `start..end` or `start..`.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ExpectRange {
     /// The lower bound of the range (inclusive).
     pub start: u64,
@@ -107,6 +149,74 @@ pub struct ExpectRange {
     pub end: Option<u64>,
 }
 
+impl ExpectRange {
+    /// Get the length of the range, or `None` if it has no upper bound.
+    pub fn len(&self) -> Option<u64> {
+        Some(self.end? - self.start)
+    }
+
+    /// Check if the range is empty. A range without upper bound is never empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Get the intersection of two ranges, or `None` if they do not overlap.
+    pub fn intersect(&self, other: &ExpectRange) -> Option<ExpectRange> {
+        let start = self.start.max(other.start);
+        let end = match (self.end, other.end) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        match end {
+            Some(end) if end <= start => None,
+            _ => Some(ExpectRange { start, end }),
+        }
+    }
+
+    /// Split the range into consecutive chunks of at most `chunk_size` bytes.
+    ///
+    /// A range without upper bound is considered to end at `file_size`.
+    /// It can be used to plan [`UploadSession::upload_part`][upload_part] calls when resuming.
+    ///
+    /// # Panic
+    /// Panic if `chunk_size` is zero.
+    ///
+    /// [upload_part]: ./struct.UploadSession.html#method.upload_part
+    pub fn split_into_chunks(&self, chunk_size: u64, file_size: u64) -> Vec<std::ops::Range<u64>> {
+        assert_ne!(chunk_size, 0, "Zero chunk size");
+        let end = self.end.unwrap_or(file_size).min(file_size);
+        let mut chunks = Vec::new();
+        let mut start = self.start;
+        while start < end {
+            let chunk_end = end.min(start.saturating_add(chunk_size));
+            chunks.push(start..chunk_end);
+            start = chunk_end;
+        }
+        chunks
+    }
+}
+
+/// Sort ranges and merge overlapping or adjacent ones.
+pub(crate) fn deserialize_expect_ranges<'de, D: de::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<ExpectRange>, D::Error> {
+    let mut ranges = <Vec<ExpectRange> as de::Deserialize>::deserialize(deserializer)?;
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<ExpectRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end.is_none_or(|end| range.start <= end) => {
+                last.end = match (last.end, range.end) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    _ => None,
+                };
+            }
+            _ => merged.push(range),
+        }
+    }
+    Ok(merged)
+}
+
 impl<'de> de::Deserialize<'de> for ExpectRange {
     fn deserialize<D: de::Deserializer<'de>>(
         deserializer: D,
@@ -160,8 +270,8 @@ mod tests {
 
     #[test]
     fn test_range_parsing() {
-        let max = format!("0-{}", u64::max_value() - 1);
-        let overflow = format!("0-{}", u64::max_value());
+        let max = format!("0-{}", u64::MAX - 1);
+        let overflow = format!("0-{}", u64::MAX);
         let cases = [
             (
                 "42-196",
@@ -188,7 +298,7 @@ mod tests {
                 &max,
                 Some(ExpectRange {
                     start: 0,
-                    end: Some(u64::max_value()),
+                    end: Some(u64::MAX),
                 }),
             ),
             (&overflow, None),
@@ -205,4 +315,56 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_range_utils() {
+        let r = |start, end| ExpectRange { start, end };
+
+        assert_eq!(r(2, Some(5)).len(), Some(3));
+        assert_eq!(r(2, None).len(), None);
+        assert!(!r(2, None).is_empty());
+
+        assert_eq!(r(0, Some(5)).intersect(&r(3, Some(8))), Some(r(3, Some(5))));
+        assert_eq!(r(0, None).intersect(&r(3, Some(8))), Some(r(3, Some(8))));
+        assert_eq!(r(4, None).intersect(&r(2, None)), Some(r(4, None)));
+        assert_eq!(r(0, Some(3)).intersect(&r(3, Some(8))), None);
+
+        assert_eq!(
+            r(1, Some(8)).split_into_chunks(3, 10),
+            vec![1..4, 4..7, 7..8]
+        );
+        assert_eq!(r(5, None).split_into_chunks(4, 10), vec![5..9, 9..10]);
+        assert_eq!(r(10, None).split_into_chunks(4, 10), vec![]);
+    }
+
+    #[test]
+    fn test_range_merging() {
+        #[derive(serde::Deserialize)]
+        struct Meta {
+            #[serde(deserialize_with = "deserialize_expect_ranges")]
+            ranges: Vec<ExpectRange>,
+        }
+
+        let meta: Meta = serde_json::from_str(
+            r#"{ "ranges": ["20-29", "0-9", "10-14", "12-15", "40-", "45-49"] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            meta.ranges,
+            vec![
+                ExpectRange {
+                    start: 0,
+                    end: Some(16)
+                },
+                ExpectRange {
+                    start: 20,
+                    end: Some(30)
+                },
+                ExpectRange {
+                    start: 40,
+                    end: None
+                },
+            ],
+        );
+    }
 }
@@ -0,0 +1,291 @@
+use crate::{
+    error::{Error, Result},
+    option::{DriveItemPutOption, ObjectOption},
+    resource::{DriveItem, DriveItemField, ItemId, Tag},
+    util::{parse_timestamp, FileName, ItemLocation},
+    ConflictBehavior, OneDrive,
+};
+use reqwest::StatusCode;
+use std::time::{Duration, SystemTime};
+
+/// A cooperative lock on a remote folder, held by a lock file in it.
+///
+/// Multiple instances of a tool (eg. on different machines) can coordinate exclusive
+/// access to a folder by acquiring the lock before modifying it. The lock is only
+/// respected by cooperating clients using it, and does not prevent any other access.
+///
+/// The lock is a file named [`FILE_NAME`][file_name] created with
+/// [`ConflictBehavior::Fail`][fail], so at most one instance can hold it at any time.
+/// The holder should [`refresh`][refresh] it periodically as a heartbeat. A lock not
+/// refreshed within its `ttl` is considered stale, and can be taken over by others.
+/// The refresh time is the last modified time set by the server, so clients never
+/// write their own clock into the lock file. Staleness is still judged by the local
+/// clock of the acquiring client, so `ttl` should be much larger than the clock skew.
+///
+/// It is released when [`release`][release] is called or it is dropped. Dropping
+/// releases it in background, and requires a tokio runtime. Otherwise, the lock file
+/// is left and will be taken over after it becomes stale.
+///
+/// # Example
+/// ```
+/// use onedrive_api::{ItemLocation, OneDrive, RemoteLock};
+/// use std::time::Duration;
+///
+/// # async fn run(onedrive: &OneDrive) -> onedrive_api::Result<()> {
+/// let folder = ItemLocation::from_path("/sync").unwrap();
+/// let mut lock = RemoteLock::acquire(onedrive, folder, "host-1", Duration::from_secs(60)).await?;
+/// // Modify the folder exclusively, and refresh the lock every less than 60s.
+/// lock.refresh().await?;
+/// lock.release().await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [file_name]: #associatedconstant.FILE_NAME
+/// [fail]: ./enum.ConflictBehavior.html#variant.Fail
+/// [refresh]: #method.refresh
+/// [release]: #method.release
+#[derive(Debug)]
+pub struct RemoteLock {
+    onedrive: OneDrive,
+    item_id: ItemId,
+    e_tag: Tag,
+    owner: String,
+    released: bool,
+}
+
+impl RemoteLock {
+    /// The name of the lock file.
+    pub const FILE_NAME: &'static str = ".lock";
+
+    /// Acquire the lock of a folder with `owner` recorded in the lock file.
+    ///
+    /// If the lock is held by others but is stale, that is, not refreshed within `ttl`
+    /// according to [`OneDrive::clock`][clock], it is taken over.
+    ///
+    /// # Errors
+    /// Will return `Err` with [`Error::is_name_conflict`][is_conflict] being `true`
+    /// if the lock is held by others.
+    ///
+    /// Will return `Err` if the server does not return the `eTag` of the created lock file,
+    /// since refreshing and releasing must be conditional on it. The lock file is deleted
+    /// in this case.
+    ///
+    /// [clock]: ./struct.OneDrive.html#method.clock
+    /// [is_conflict]: ./struct.Error.html#method.is_name_conflict
+    pub async fn acquire<'a>(
+        onedrive: &OneDrive,
+        folder: impl Into<ItemLocation<'a>>,
+        owner: &str,
+        ttl: Duration,
+    ) -> Result<Self> {
+        let onedrive = onedrive
+            .clone()
+            .with_default_conflict_behavior(Some(ConflictBehavior::Fail))
+            .with_name_conflict_lookup(false);
+        let folder_id = onedrive
+            .get_item_with_option(folder, ObjectOption::new().select(&[DriveItemField::id]))
+            .await?
+            .and_then(|item| item.id)
+            .ok_or_else(|| Error::unexpected_response("Missing `id`"))?;
+        let file_name = FileName::new(Self::FILE_NAME).unwrap();
+        let location = ItemLocation::child_of_id(&folder_id, file_name);
+
+        let mut took_over = false;
+        loop {
+            let err = match onedrive.upload_small(location, owner.to_owned()).await {
+                Ok(item) => {
+                    let item_id = item
+                        .id
+                        .ok_or_else(|| Error::unexpected_response("Missing `id`"))?;
+                    let e_tag = match item.e_tag {
+                        Some(tag) => tag,
+                        None => {
+                            // Just created by us and not stale, so no one else can take it over.
+                            let _ = onedrive.delete(&item_id).await;
+                            return Err(Error::unexpected_response("Missing `eTag`"));
+                        }
+                    };
+                    return Ok(Self {
+                        item_id,
+                        e_tag,
+                        owner: owner.to_owned(),
+                        onedrive,
+                        released: false,
+                    });
+                }
+                Err(err) if err.is_name_conflict() && !took_over => err,
+                Err(err) => return Err(err),
+            };
+
+            let existing = match onedrive.get_item(location).await {
+                Ok(item) => item,
+                // Released just now.
+                Err(e) if e.status_code() == Some(StatusCode::NOT_FOUND) => {
+                    took_over = true;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let (id, tag) = match takeover_target(&existing, ttl, onedrive.clock().now()) {
+                Some(target) => target,
+                None => return Err(err),
+            };
+            // Others may take it over or refresh it concurrently.
+            match onedrive
+                .delete_with_option(id, DriveItemPutOption::new().if_match(tag))
+                .await
+            {
+                Ok(()) => {}
+                Err(e)
+                    if matches!(
+                        e.status_code(),
+                        Some(StatusCode::NOT_FOUND) | Some(StatusCode::PRECONDITION_FAILED)
+                    ) => {}
+                Err(e) => return Err(e),
+            }
+            took_over = true;
+        }
+    }
+
+    /// Get the id of the lock file.
+    pub fn item_id(&self) -> &ItemId {
+        &self.item_id
+    }
+
+    /// Refresh the lock as a heartbeat, to prevent it from becoming stale.
+    ///
+    /// The lock file is rewritten with the same content, so the server updates its
+    /// last modified time.
+    ///
+    /// # Errors
+    /// Will return `Err` with HTTP 412 PRECONDITION_FAILED or 404 NOT_FOUND if
+    /// the lock is lost, eg. taken over by others after being stale.
+    pub async fn refresh(&mut self) -> Result<()> {
+        let created = self
+            .onedrive
+            .upload_small_with_option(
+                &self.item_id,
+                self.owner.clone(),
+                self.put_option()
+                    .conflict_behavior(ConflictBehavior::Replace),
+            )
+            .await?;
+        self.e_tag = created
+            .item
+            .e_tag
+            .ok_or_else(|| Error::unexpected_response("Missing `eTag`"))?;
+        Ok(())
+    }
+
+    /// Release the lock by deleting the lock file.
+    ///
+    /// # Errors
+    /// Will return `Err` with HTTP 412 PRECONDITION_FAILED if the lock is lost.
+    /// The lock file is not deleted in this case.
+    pub async fn release(mut self) -> Result<()> {
+        self.released = true;
+        self.onedrive
+            .delete_with_option(&self.item_id, self.put_option())
+            .await
+    }
+
+    fn put_option(&self) -> DriveItemPutOption {
+        DriveItemPutOption::new().if_match(&self.e_tag)
+    }
+}
+
+impl Drop for RemoteLock {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let onedrive = self.onedrive.clone();
+            let item_id = self.item_id.clone();
+            let option = self.put_option();
+            handle.spawn(async move {
+                let _ = onedrive.delete_with_option(&item_id, option).await;
+            });
+        }
+    }
+}
+
+/// Get the id and tag of an existing lock file to take over, if it is stale at `now`.
+///
+/// A lock file without a tag is never taken over, since the deletion must be conditional.
+fn takeover_target(
+    existing: &DriveItem,
+    ttl: Duration,
+    now: SystemTime,
+) -> Option<(&ItemId, &Tag)> {
+    let is_stale = refreshed_at(existing).is_some_and(|time| time + ttl <= now);
+    match (&existing.id, &existing.e_tag) {
+        (Some(id), Some(tag)) if is_stale => Some((id, tag)),
+        _ => None,
+    }
+}
+
+/// Get the time when the lock file is last refreshed.
+///
+/// Only the time set by the server is used. `fileSystemInfo` can be set by clients.
+fn refreshed_at(item: &DriveItem) -> Option<SystemTime> {
+    parse_timestamp(item.last_modified_date_time.as_deref()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refreshed_at() {
+        let item: DriveItem = serde_json::from_value(serde_json::json!({
+            "lastModifiedDateTime": "2015-01-29T09:21:55Z",
+        }))
+        .unwrap();
+        assert_eq!(refreshed_at(&item), parse_timestamp("2015-01-29T09:21:55Z"));
+
+        let item: DriveItem = serde_json::from_value(serde_json::json!({
+            "lastModifiedDateTime": "2015-01-29T09:21:55Z",
+            "fileSystemInfo": { "lastModifiedDateTime": "2015-01-29T09:30:00Z" },
+        }))
+        .unwrap();
+        assert_eq!(refreshed_at(&item), parse_timestamp("2015-01-29T09:21:55Z"));
+
+        assert_eq!(refreshed_at(&DriveItem::default()), None);
+    }
+
+    #[test]
+    fn test_takeover_target() {
+        let item = |json: serde_json::Value| serde_json::from_value::<DriveItem>(json).unwrap();
+        let lock = item(serde_json::json!({
+            "id": "1",
+            "eTag": "tag",
+            "lastModifiedDateTime": "2015-01-29T09:21:55Z",
+        }));
+        let refreshed = parse_timestamp("2015-01-29T09:21:55Z").unwrap();
+        let ttl = Duration::from_secs(60);
+
+        // Fresh.
+        assert_eq!(takeover_target(&lock, ttl, refreshed), None);
+        assert_eq!(
+            takeover_target(&lock, ttl, refreshed + Duration::from_secs(59)),
+            None,
+        );
+        // Stale.
+        assert_eq!(
+            takeover_target(&lock, ttl, refreshed + ttl),
+            Some((&ItemId("1".to_owned()), &Tag("tag".to_owned()))),
+        );
+
+        // Never taken over without a tag or a refresh time.
+        let now = refreshed + ttl * 2;
+        let no_tag = item(serde_json::json!({
+            "id": "1",
+            "lastModifiedDateTime": "2015-01-29T09:21:55Z",
+        }));
+        assert_eq!(takeover_target(&no_tag, ttl, now), None);
+        let no_time = item(serde_json::json!({ "id": "1", "eTag": "tag" }));
+        assert_eq!(takeover_target(&no_time, ttl, now), None);
+    }
+}
@@ -1,16 +1,25 @@
 use crate::{
+    checksum::{QuickXorHash, UploadVerifier},
+    clock::{Clock, Sleeper, SystemClock, TokioSleeper},
+    coalesce::Coalescer,
     error::{Error, Result},
-    option::{CollectionOption, DriveItemPutOption, ObjectOption},
+    option::{CollectionOption, DriveItemPutOption, ObjectOption, Order},
     resource::*,
+    search::{SearchQuery, SearchResponse},
+    token::SharedToken,
     util::{
-        handle_error_response, ApiPathComponent, DriveLocation, FileName, ItemLocation,
-        RequestBuilderExt as _, ResponseExt as _,
+        handle_error_response, limit_body_size, parse_timestamp, ApiPathComponent, DeltaToken,
+        DriveLocation, FileName, ItemLocation, RequestBuilderExt as _, ResponseExt as _,
     },
-    {ConflictBehavior, ExpectRange},
+    {deserialize_expect_ranges, ConflictBehavior, ExpectRange},
 };
 use bytes::Bytes;
-use reqwest::{header, Client};
-use serde::{Deserialize, Serialize};
+use reqwest::{header, Client, IntoUrl, Method, RequestBuilder, Response, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 use url::Url;
 
 macro_rules! api_url {
@@ -24,6 +33,18 @@ macro_rules! api_url {
     }};
 }
 
+#[cfg(feature = "beta")]
+macro_rules! beta_api_url {
+    ($($seg:expr),* $(,)?) => {{
+        let mut url = Url::parse("https://graph.microsoft.com/beta").unwrap();
+        {
+            let mut buf = url.path_segments_mut().unwrap();
+            $(ApiPathComponent::extend_into($seg, &mut buf);)*
+        } // End borrowing of `url`
+        url
+    }};
+}
+
 /// TODO: More efficient impl.
 macro_rules! api_path {
     ($item:expr) => {{
@@ -36,16 +57,219 @@ macro_rules! api_path {
 }
 
 /// The authorized client to access OneDrive resources in a specified Drive.
-#[derive(Debug)]
+///
+/// Cloning it is cheap. Clones share the underlying `reqwest::Client` and
+/// the [`SharedToken`][shared_token].
+///
+/// [shared_token]: ./struct.SharedToken.html
+#[derive(Clone, Debug)]
 pub struct OneDrive {
     client: Client,
-    token: String,
+    token: SharedToken,
     drive: DriveLocation,
+    timeouts: Timeouts,
+    strict_accepted: bool,
+    default_conflict_behavior: Option<ConflictBehavior>,
+    name_conflict_lookup: bool,
+    clock: Arc<dyn Clock>,
+    sleeper: Arc<dyn Sleeper>,
+    coalescer: Option<Arc<Coalescer>>,
+    response_limits: ResponseLimits,
+}
+
+/// Timeouts for different classes of requests sent by [`OneDrive`][one_drive].
+///
+/// A single timeout on `reqwest::Client` is either too short for transferring large contents,
+/// or too long for metadata requests which are expected to respond quickly.
+/// Timeouts set here are applied per request and override the one of the underlying client,
+/// either shorter or longer. `None` (the default) means to leave it to the underlying client.
+///
+/// # Note
+/// `None` cannot remove the timeout of the underlying client. To transfer contents without
+/// any time limit, the `reqwest::Client` itself should not have a timeout set, while
+/// metadata and monitor requests are limited here.
+///
+/// Upload sessions created by [`OneDrive::new_upload_session`][new_session] carry the
+/// transfer timeout, which is applied to [`UploadSession::upload_part`][upload_part].
+/// See [`UploadSession::with_timeout`][session_timeout].
+///
+/// # See also
+/// [`OneDrive::with_timeouts`][with_timeouts]
+///
+/// [one_drive]: ./struct.OneDrive.html
+/// [new_session]: ./struct.OneDrive.html#method.new_upload_session
+/// [upload_part]: ./struct.UploadSession.html#method.upload_part
+/// [session_timeout]: ./struct.UploadSession.html#method.with_timeout
+/// [with_timeouts]: ./struct.OneDrive.html#method.with_timeouts
+#[derive(Clone, Debug, Default)]
+pub struct Timeouts {
+    metadata: Option<Duration>,
+    transfer: Option<Duration>,
+    monitor: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Create an empty (default) timeout configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the timeout for requests retrieving or modifying metadata of drives and items,
+    /// eg. [`OneDrive::get_item`][get_item] and [`OneDrive::create_folder`][create_folder].
+    ///
+    /// [get_item]: ./struct.OneDrive.html#method.get_item
+    /// [create_folder]: ./struct.OneDrive.html#method.create_folder
+    pub fn metadata(mut self, timeout: Option<Duration>) -> Self {
+        self.metadata = timeout;
+        self
+    }
+
+    /// Set the timeout for requests transferring file contents,
+    /// eg. [`OneDrive::upload_small`][upload_small] and parts uploaded through
+    /// [`UploadSession`][session]s created by this `OneDrive`.
+    ///
+    /// [upload_small]: ./struct.OneDrive.html#method.upload_small
+    /// [session]: ./struct.UploadSession.html
+    pub fn transfer(mut self, timeout: Option<Duration>) -> Self {
+        self.transfer = timeout;
+        self
+    }
+
+    /// Set the timeout for requests checking the status of long-running actions,
+    /// eg. [`CopyProgressMonitor::fetch_progress`][fetch_progress].
+    ///
+    /// [fetch_progress]: ./struct.CopyProgressMonitor.html#method.fetch_progress
+    pub fn monitor(mut self, timeout: Option<Duration>) -> Self {
+        self.monitor = timeout;
+        self
+    }
+
+    /// Get the timeout for metadata requests.
+    pub fn get_metadata(&self) -> Option<Duration> {
+        self.metadata
+    }
+
+    /// Get the timeout for content transfer requests.
+    pub fn get_transfer(&self) -> Option<Duration> {
+        self.transfer
+    }
+
+    /// Get the timeout for monitor requests.
+    pub fn get_monitor(&self) -> Option<Duration> {
+        self.monitor
+    }
+
+    fn get(&self, class: RequestClass) -> Option<Duration> {
+        match class {
+            RequestClass::Metadata => self.metadata,
+            RequestClass::Transfer => self.transfer,
+            RequestClass::Monitor => self.monitor,
+        }
+    }
+}
+
+/// Limits on responses of metadata requests sent by [`OneDrive`][one_drive], to protect
+/// services from running out of memory on unexpectedly huge responses, eg. folders with
+/// hundreds of thousands of children, or a `$expand` blowing up.
+///
+/// Requests exceeding any limit fail with [`Error::is_response_limit_exceeded`][exceeded]
+/// being `true`. `None` (the default) means no limit.
+///
+/// # See also
+/// [`OneDrive::with_response_limits`][with_limits]
+///
+/// [one_drive]: ./struct.OneDrive.html
+/// [exceeded]: ./struct.Error.html#method.is_response_limit_exceeded
+/// [with_limits]: ./struct.OneDrive.html#method.with_response_limits
+#[derive(Clone, Debug, Default)]
+pub struct ResponseLimits {
+    max_response_size: Option<u64>,
+    max_page_items: Option<usize>,
+    max_total_items: Option<usize>,
+}
+
+impl ResponseLimits {
+    /// Create an empty (default) limit configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size in bytes of a (decompressed) response body of requests
+    /// retrieving metadata, eg. [`OneDrive::get_item`][get_item], pages of
+    /// [`OneDrive::list_children`][list_children], thumbnails, activities and
+    /// search results.
+    ///
+    /// Responses are received into memory at most this size before being rejected,
+    /// including responses shared by [coalesced requests][coalescing].
+    ///
+    /// [get_item]: ./struct.OneDrive.html#method.get_item
+    /// [list_children]: ./struct.OneDrive.html#method.list_children
+    /// [coalescing]: ./struct.OneDrive.html#method.with_request_coalescing
+    pub fn max_response_size(mut self, size: Option<u64>) -> Self {
+        self.max_response_size = size;
+        self
+    }
+
+    /// Set the maximum number of items in a single page fetched by
+    /// [`ListChildrenFetcher`][list_fetcher] or [`TrackChangeFetcher`][track_fetcher],
+    /// and the activity fetcher with feature `beta`.
+    ///
+    /// The page is checked after it is received and deserialized, so it does not bound
+    /// the memory used by the page itself. Use [`max_response_size`][max_size] for that.
+    ///
+    /// [list_fetcher]: ./struct.ListChildrenFetcher.html
+    /// [track_fetcher]: ./struct.TrackChangeFetcher.html
+    /// [max_size]: #method.max_response_size
+    pub fn max_page_items(mut self, count: Option<usize>) -> Self {
+        self.max_page_items = count;
+        self
+    }
+
+    /// Set the maximum number of items collected by `fetch_all` of
+    /// [`ListChildrenFetcher`][list_fetcher], [`TrackChangeFetcher`][track_fetcher]
+    /// and the activity fetcher with feature `beta` from all pages, eg. in [`OneDrive::list_children`][list_children].
+    ///
+    /// [list_fetcher]: ./struct.ListChildrenFetcher.html#method.fetch_all
+    /// [track_fetcher]: ./struct.TrackChangeFetcher.html#method.fetch_all
+    /// [list_children]: ./struct.OneDrive.html#method.list_children
+    pub fn max_total_items(mut self, count: Option<usize>) -> Self {
+        self.max_total_items = count;
+        self
+    }
+
+    /// Get the maximum size of a response body.
+    pub fn get_max_response_size(&self) -> Option<u64> {
+        self.max_response_size
+    }
+
+    /// Get the maximum number of items in a single page.
+    pub fn get_max_page_items(&self) -> Option<usize> {
+        self.max_page_items
+    }
+
+    /// Get the maximum number of items collected from all pages.
+    pub fn get_max_total_items(&self) -> Option<usize> {
+        self.max_total_items
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum RequestClass {
+    Metadata,
+    Transfer,
+    #[cfg_attr(not(feature = "beta"), allow(dead_code))]
+    Monitor,
 }
 
 impl OneDrive {
     /// Create a new OneDrive instance with access token given to perform operations in a Drive.
-    pub fn new(access_token: String, drive: impl Into<DriveLocation>) -> Self {
+    ///
+    /// `access_token` can be either a `String` or a [`SharedToken`][shared_token] which
+    /// may be refreshed in background by [`TokenRefresher`][refresher].
+    ///
+    /// [shared_token]: ./struct.SharedToken.html
+    /// [refresher]: ./struct.TokenRefresher.html
+    pub fn new(access_token: impl Into<SharedToken>, drive: impl Into<DriveLocation>) -> Self {
         let client = Client::builder()
             .redirect(reqwest::redirect::Policy::none())
             .gzip(true)
@@ -65,23 +289,310 @@ impl OneDrive {
     /// [get_url]: #method.get_item_download_url_with_option
     pub fn new_with_client(
         client: Client,
-        access_token: String,
+        access_token: impl Into<SharedToken>,
         drive: impl Into<DriveLocation>,
     ) -> Self {
         OneDrive {
             client,
-            token: access_token,
+            token: access_token.into(),
             drive: drive.into(),
+            timeouts: Timeouts::default(),
+            strict_accepted: false,
+            default_conflict_behavior: None,
+            name_conflict_lookup: false,
+            clock: Arc::new(SystemClock),
+            sleeper: Arc::new(TokioSleeper),
+            coalescer: None,
+            response_limits: ResponseLimits::default(),
+        }
+    }
+
+    /// Set timeouts for different classes of requests.
+    ///
+    /// # See also
+    /// [`Timeouts`][timeouts]
+    ///
+    /// [timeouts]: ./struct.Timeouts.html
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Get the timeouts used by this instance.
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
+    /// Set limits on sizes and item counts of metadata responses.
+    ///
+    /// # See also
+    /// [`ResponseLimits`][limits]
+    ///
+    /// [limits]: ./struct.ResponseLimits.html
+    pub fn with_response_limits(mut self, limits: ResponseLimits) -> Self {
+        self.response_limits = limits;
+        self
+    }
+
+    /// Get the response limits used by this instance.
+    pub fn response_limits(&self) -> &ResponseLimits {
+        &self.response_limits
+    }
+
+    /// Set whether to treat unexpected HTTP 202 ACCEPTED without `Location` as errors
+    /// for all APIs of this instance.
+    ///
+    /// An HTTP 202 ACCEPTED with `Location` header always yields the async job monitor url.
+    /// APIs expected to start an async job (eg. [`copy`][copy]) return it as the result.
+    /// Other APIs (eg. [`delete`][delete]) cannot carry it in their results, and return
+    /// an error with [`Error::async_job_monitor_url`][monitor_url] instead.
+    ///
+    /// Without `Location`, the response is handled as a plain success by default.
+    /// If enabled, it is reported as an error with
+    /// [`Error::is_async_job_accepted`][is_accepted] being `true`.
+    ///
+    /// Default to be `false`.
+    ///
+    /// [delete]: #method.delete_with_option
+    /// [copy]: #method.copy
+    /// [monitor_url]: ./struct.Error.html#method.async_job_monitor_url
+    /// [is_accepted]: ./struct.Error.html#method.is_async_job_accepted
+    pub fn with_strict_accepted(mut self, strict: bool) -> Self {
+        self.strict_accepted = strict;
+        self
+    }
+
+    /// Set the default conflict behavior for all APIs creating items, unless
+    /// it is overridden by [`DriveItemPutOption::conflict_behavior`][conflict_behavior]
+    /// per call.
+    ///
+    /// It applies to [`create_folder`][create_folder], [`upload_small`][upload_small],
+    /// [`new_upload_session`][upload_session] and [`move_`][move_] (including their
+    /// `with_option` versions). Eg. set it to [`ConflictBehavior::Fail`][fail] to make sure
+    /// nothing is replaced, including by `upload_small` which replaces by default.
+    ///
+    /// Default to be `None`, which is [`ConflictBehavior::Fail`][fail] for all of them
+    /// except `upload_small`, which leaves it to the server (replacing existing files).
+    ///
+    /// [conflict_behavior]: ./option/struct.DriveItemPutOption.html#method.conflict_behavior
+    /// [create_folder]: #method.create_folder
+    /// [upload_small]: #method.upload_small
+    /// [upload_session]: #method.new_upload_session
+    /// [move_]: #method.move_
+    /// [fail]: ./enum.ConflictBehavior.html#variant.Fail
+    pub fn with_default_conflict_behavior(
+        mut self,
+        conflict_behavior: Option<ConflictBehavior>,
+    ) -> Self {
+        self.default_conflict_behavior = conflict_behavior;
+        self
+    }
+
+    /// Get the default conflict behavior.
+    ///
+    /// # See also
+    /// [`with_default_conflict_behavior`][with_default]
+    ///
+    /// [with_default]: #method.with_default_conflict_behavior
+    pub fn default_conflict_behavior(&self) -> Option<ConflictBehavior> {
+        self.default_conflict_behavior
+    }
+
+    fn conflict_behavior_of(&self, option: &DriveItemPutOption) -> ConflictBehavior {
+        option
+            .get_conflict_behavior()
+            .or(self.default_conflict_behavior)
+            .unwrap_or(ConflictBehavior::Fail)
+    }
+
+    /// Set whether to look up the existing item when creating an item fails due to
+    /// a name conflict.
+    ///
+    /// If enabled, [`create_folder`][create_folder], [`upload_small`][upload_small] and
+    /// [`move_`][move_] with a new name (including their `with_option` versions) will send
+    /// a follow-up request to get the existing item on errors with
+    /// [`Error::is_name_conflict`][is_conflict] being `true`. The item is available through
+    /// [`Error::conflicting_item`][conflicting_item], so callers can decide to merge,
+    /// rename or replace immediately.
+    ///
+    /// Default to be `false`.
+    ///
+    /// [create_folder]: #method.create_folder
+    /// [upload_small]: #method.upload_small
+    /// [move_]: #method.move_
+    /// [is_conflict]: ./struct.Error.html#method.is_name_conflict
+    /// [conflicting_item]: ./struct.Error.html#method.conflicting_item
+    pub fn with_name_conflict_lookup(mut self, lookup: bool) -> Self {
+        self.name_conflict_lookup = lookup;
+        self
+    }
+
+    /// Get whether the existing item is looked up on name conflicts.
+    ///
+    /// # See also
+    /// [`with_name_conflict_lookup`][with_lookup]
+    ///
+    /// [with_lookup]: #method.with_name_conflict_lookup
+    pub fn name_conflict_lookup(&self) -> bool {
+        self.name_conflict_lookup
+    }
+
+    /// Set the clock used by time-dependent logic of this instance.
+    ///
+    /// It decides the staleness of [`RemoteLock`][lock]s, the `obtained_at` of tokens from
+    /// [`get_latest_delta_token`][delta_token], and names of temporary files in
+    /// [`upload_replace_atomic`][replace_atomic]. Standalone types like
+    /// [`UploadSession`][session] and [`DeltaToken`][token] do not hold it, and use the
+    /// system clock in `is_expired` and `age`. Pass [`clock`][clock] to their `_at`
+    /// versions to check them against this clock.
+    ///
+    /// Default to be [`SystemClock`][system_clock].
+    ///
+    /// [lock]: ./struct.RemoteLock.html
+    /// [delta_token]: #method.get_latest_delta_token
+    /// [replace_atomic]: #method.upload_replace_atomic
+    /// [session]: ./struct.UploadSession.html#method.is_expired_at
+    /// [token]: ./struct.DeltaToken.html#method.age_at
+    /// [clock]: #method.clock
+    /// [system_clock]: ./struct.SystemClock.html
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Get the clock used by this instance.
+    pub fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
+    /// Set the timer used to wait before retrying throttled requests and
+    /// between polls of long-running operations.
+    ///
+    /// Default to be [`TokioSleeper`][tokio_sleeper].
+    ///
+    /// [tokio_sleeper]: ./struct.TokioSleeper.html
+    pub fn with_sleeper(mut self, sleeper: impl Sleeper + 'static) -> Self {
+        self.sleeper = Arc::new(sleeper);
+        self
+    }
+
+    /// Get the timer used by this instance.
+    pub fn sleeper(&self) -> &dyn Sleeper {
+        &*self.sleeper
+    }
+
+    /// Set whether to coalesce identical in-flight metadata GET requests.
+    ///
+    /// If enabled, concurrent calls of [`get_drive`][get_drive], [`get_item`][get_item] and
+    /// [`list_children`][list_children] (including their `with_option` and `_in` versions)
+    /// with the same target and options share a single request and its response.
+    /// A request sent after the previous identical one completes is never coalesced.
+    ///
+    /// It is useful for applications where many tasks ask for the same hot folders
+    /// simultaneously.
+    ///
+    /// Default to be `false`.
+    ///
+    /// [get_drive]: #method.get_drive
+    /// [get_item]: #method.get_item
+    /// [list_children]: #method.list_children
+    pub fn with_request_coalescing(mut self, coalescing: bool) -> Self {
+        self.coalescer = if coalescing {
+            Some(Default::default())
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Get whether identical in-flight metadata GET requests are coalesced.
+    ///
+    /// # See also
+    /// [`with_request_coalescing`][with_coalescing]
+    ///
+    /// [with_coalescing]: #method.with_request_coalescing
+    pub fn request_coalescing(&self) -> bool {
+        self.coalescer.is_some()
+    }
+
+    async fn send_metadata_get(&self, req: RequestBuilder) -> Result<Response> {
+        let limit = self.response_limits.max_response_size;
+        match &self.coalescer {
+            Some(coalescer) => coalescer.execute(&self.client, req.build()?, limit).await,
+            None => limit_body_size(req.send_logged().await?, limit).await,
+        }
+    }
+
+    async fn send_limited(&self, req: RequestBuilder) -> Result<Response> {
+        let resp = req.send_logged().await?;
+        limit_body_size(resp, self.response_limits.max_response_size).await
+    }
+
+    async fn lookup_name_conflict(&self, err: Error, target: Url) -> Error {
+        if !self.name_conflict_lookup || !err.is_name_conflict() {
+            return err;
+        }
+        let option = ObjectOption::new().select(&[
+            DriveItemField::id,
+            DriveItemField::name,
+            DriveItemField::e_tag,
+            DriveItemField::c_tag,
+            DriveItemField::size,
+            DriveItemField::file,
+            DriveItemField::folder,
+        ]);
+        let existing = match self
+            .request(Method::GET, target, RequestClass::Metadata)
+            .apply(option)
+            .send_logged()
+            .await
+        {
+            Ok(resp) => resp.parse(self.strict_accepted).await.ok(),
+            Err(_) => None,
+        };
+        Error::name_conflict(err, existing)
+    }
+
+    /// Get whether unexpected HTTP 202 ACCEPTED are treated as errors.
+    ///
+    /// # See also
+    /// [`with_strict_accepted`][with_strict]
+    ///
+    /// [with_strict]: #method.with_strict_accepted
+    pub fn strict_accepted(&self) -> bool {
+        self.strict_accepted
+    }
+
+    fn request_unauthorized(
+        &self,
+        method: Method,
+        url: impl IntoUrl,
+        class: RequestClass,
+    ) -> RequestBuilder {
+        let req = self.client.request(method, url);
+        match self.timeouts.get(class) {
+            Some(timeout) => req.timeout(timeout),
+            None => req,
         }
     }
 
+    fn request(&self, method: Method, url: impl IntoUrl, class: RequestClass) -> RequestBuilder {
+        self.request_unauthorized(method, url, class)
+            .bearer_auth(self.token.get())
+    }
+
     /// Get the `reqwest::Client` used to create the OneDrive instance.
     pub fn client(&self) -> &Client {
         &self.client
     }
 
-    /// Get the access token used to create the OneDrive instance.
-    pub fn access_token(&self) -> &str {
+    /// Get the access token currently used by the OneDrive instance.
+    pub fn access_token(&self) -> String {
+        self.token.get()
+    }
+
+    /// Get the shared token used by the OneDrive instance.
+    pub fn shared_token(&self) -> &SharedToken {
         &self.token
     }
 
@@ -94,14 +605,13 @@ impl OneDrive {
     ///
     /// [drive]: ./resource/struct.Drive.html
     pub async fn get_drive_with_option(&self, option: ObjectOption<DriveField>) -> Result<Drive> {
-        self.client
-            .get(api_url![&self.drive])
-            .apply(option)
-            .bearer_auth(&self.token)
-            .send()
-            .await?
-            .parse()
-            .await
+        self.send_metadata_get(
+            self.request(Method::GET, api_url![&self.drive], RequestClass::Metadata)
+                .apply(option),
+        )
+        .await?
+        .parse(self.strict_accepted)
+        .await
     }
 
     /// Shortcut to `get_drive_with_option` with default parameters.
@@ -114,6 +624,176 @@ impl OneDrive {
         self.get_drive_with_option(Default::default()).await
     }
 
+    /// Get a summary of current `Drive`, including its quota and statistics of the root folder.
+    ///
+    /// It is done in a single request by expanding the root folder.
+    ///
+    /// # See also
+    /// [`DriveSummary`][summary]
+    ///
+    /// [summary]: ./struct.DriveSummary.html
+    pub async fn get_drive_summary(&self) -> Result<DriveSummary> {
+        use std::convert::TryFrom as _;
+
+        let drive = self
+            .get_drive_with_option(
+                ObjectOption::new()
+                    .select(&[DriveField::id, DriveField::drive_type, DriveField::quota])
+                    .expand(DriveField::root, Some(&["id", "folder", "size"])),
+            )
+            .await?;
+        let root = drive.root.unwrap_or_default();
+        Ok(DriveSummary {
+            drive_id: drive.id,
+            drive_type: drive
+                .drive_type
+                .and_then(|ty| ty.as_str().map(|s| s.to_owned())),
+            quota: drive.quota,
+            root_child_count: root
+                .folder
+                .and_then(|folder| folder.get("childCount")?.as_u64()),
+            root_size: root.size.and_then(|size| u64::try_from(size).ok()),
+        })
+    }
+
+    /// Probe what the current token can do on the drive.
+    ///
+    /// It sends a few cheap requests without side effects:
+    /// 1. Get the drive with its owner, to check the read access.
+    /// 2. Update the root folder with an empty patch and a mismatched `If-Match` tag,
+    ///    which fails with HTTP 412 PRECONDITION_FAILED if the write access is granted,
+    ///    or HTTP 403 FORBIDDEN otherwise.
+    /// 3. List permissions of the root folder, to check the share access.
+    /// 4. Get the current user, to check whether the user owns the drive.
+    ///
+    /// # Note
+    /// The result is a best-effort estimation. The server may still reject some operations
+    /// on specific items due to item-level permissions or policies.
+    ///
+    /// App-only tokens (eg. from client credentials) have no current user, and
+    /// getting it fails with HTTP 400 BAD_REQUEST. The owner is then unknown and
+    /// [`Capabilities::admin`][admin] is `false`.
+    ///
+    /// # Errors
+    /// Access denied (HTTP 401, 403 or 404) are reported as missing capabilities.
+    /// Other errors, like network errors or throttling, are returned as `Err`.
+    ///
+    /// [admin]: ./struct.Capabilities.html#structfield.admin
+    pub async fn probe_capabilities(&self) -> Result<Capabilities> {
+        fn denied<T>(ret: Result<T>) -> Result<Option<T>> {
+            match ret {
+                Ok(v) => Ok(Some(v)),
+                Err(err)
+                    if matches!(
+                        err.status_code(),
+                        Some(StatusCode::UNAUTHORIZED)
+                            | Some(StatusCode::FORBIDDEN)
+                            | Some(StatusCode::NOT_FOUND)
+                    ) =>
+                {
+                    Ok(None)
+                }
+                Err(err) => Err(err),
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct Permissions {
+            value: Vec<serde_json::Value>,
+        }
+
+        #[derive(Deserialize)]
+        struct User {
+            id: String,
+        }
+
+        let mut caps = Capabilities::default();
+
+        let drive = match denied(
+            self.get_drive_with_option(
+                ObjectOption::new().select(&[DriveField::id, DriveField::owner]),
+            )
+            .await,
+        )? {
+            Some(drive) => drive,
+            None => return Ok(caps),
+        };
+        caps.read = true;
+
+        let root = ItemLocation::root();
+        let ret = self
+            .request(
+                Method::PATCH,
+                api_url![&self.drive, &root],
+                RequestClass::Metadata,
+            )
+            .header(header::IF_MATCH, "\"probe-capabilities\"")
+            .json(&serde_json::json!({}))
+            .send_logged()
+            .await?
+            .parse_no_content(self.strict_accepted)
+            .await;
+        caps.write = match ret {
+            Err(err) if err.status_code() == Some(StatusCode::PRECONDITION_FAILED) => true,
+            ret => denied(ret)?.is_some(),
+        };
+
+        let req = self.request(
+            Method::GET,
+            api_url![&self.drive, &root, "permissions"],
+            RequestClass::Metadata,
+        );
+        let permissions = denied(
+            self.send_limited(req)
+                .await?
+                .parse::<Permissions>(self.strict_accepted)
+                .await,
+        )?;
+        caps.share = caps.write && permissions.is_some();
+
+        let me = self
+            .request(Method::GET, api_url!["me"], RequestClass::Metadata)
+            .query(&[("$select", "id")])
+            .send_logged()
+            .await?
+            .parse::<User>(self.strict_accepted)
+            .await;
+        let me = match me {
+            // No current user for app-only tokens.
+            Err(err) if err.status_code() == Some(StatusCode::BAD_REQUEST) => None,
+            me => denied(me)?,
+        };
+        if let Some(me) = me {
+            let is_me = |identity_set: &serde_json::Value| {
+                ["user", "siteUser"].iter().any(|key| {
+                    identity_set
+                        .get(key)
+                        .and_then(|user| user.get("id")?.as_str())
+                        .is_some_and(|id| id.eq_ignore_ascii_case(&me.id))
+                })
+            };
+            let owns_drive = drive.owner.iter().any(|owner| {
+                [&owner.user, &owner.site_user]
+                    .iter()
+                    .filter_map(|user| user.as_ref()?.id.as_deref())
+                    .any(|id| id.eq_ignore_ascii_case(&me.id))
+            });
+            let owns_root = permissions.iter().flat_map(|p| &p.value).any(|perm| {
+                let is_owner = perm
+                    .get("roles")
+                    .and_then(|roles| roles.as_array())
+                    .is_some_and(|roles| roles.iter().any(|role| role == "owner"));
+                is_owner
+                    && ["grantedToV2", "grantedTo"]
+                        .iter()
+                        .any(|key| perm.get(key).is_some_and(is_me))
+            });
+            caps.admin = caps.write && (owns_drive || owns_root);
+        }
+
+        Ok(caps)
+    }
+
     /// List children of a `DriveItem`.
     ///
     /// Retrieve a collection of [`resource::DriveItem`][drive_item]s in the children relationship
@@ -137,13 +817,16 @@ impl OneDrive {
         option: CollectionOption<DriveItemField>,
     ) -> Result<Option<ListChildrenFetcher>> {
         let opt_resp = self
-            .client
-            .get(api_url![&self.drive, &item.into(), "children"])
-            .apply(option)
-            .bearer_auth(&self.token)
-            .send()
+            .send_metadata_get(
+                self.request(
+                    Method::GET,
+                    api_url![&self.drive, &item.into(), "children"],
+                    RequestClass::Metadata,
+                )
+                .apply(option),
+            )
             .await?
-            .parse_optional()
+            .parse_optional(self.strict_accepted)
             .await?;
 
         Ok(opt_resp.map(ListChildrenFetcher::new))
@@ -167,6 +850,170 @@ impl OneDrive {
             .await
     }
 
+    /// List all children of the root folder, with [`DriveItemField::CORE`][core]
+    /// fields selected.
+    ///
+    /// # See also
+    /// [`list_children_with_option`][with_opt]
+    ///
+    /// [core]: ./resource/enum.DriveItemField.html#associatedconstant.CORE
+    /// [with_opt]: #method.list_children_with_option
+    pub async fn list_root_children(&self) -> Result<Vec<DriveItem>> {
+        self.list_children_with_option(ItemLocation::root(), CollectionOption::new().select_core())
+            .await?
+            .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))?
+            .fetch_all(self)
+            .await
+    }
+
+    /// Start building a query for children of a `DriveItem`.
+    ///
+    /// It is a fluent alternative to [`list_children_with_option`][with_opt].
+    ///
+    /// # Example
+    /// ```
+    /// use onedrive_api::{OneDrive, ItemLocation, option::Order, resource::DriveItemField};
+    ///
+    /// # async fn run(onedrive: &OneDrive) -> onedrive_api::Result<()> {
+    /// let items = onedrive
+    ///     .children(ItemLocation::root())
+    ///     .select(&[DriveItemField::id, DriveItemField::name])
+    ///     .order_by(DriveItemField::name, Order::Ascending)
+    ///     .page_size(100)
+    ///     .fetch()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [with_opt]: #method.list_children_with_option
+    pub fn children<'a>(&'a self, item: impl Into<ItemLocation<'a>>) -> ChildrenQuery<'a> {
+        ChildrenQuery {
+            onedrive: self,
+            item: item.into(),
+            option: CollectionOption::new(),
+        }
+    }
+
+    /// List all children of a folder as a consistent snapshot, even when the folder
+    /// is modified concurrently.
+    ///
+    /// Paged listing by [`list_children`][list_children] may miss or duplicate items
+    /// if the folder is modified between fetching pages. Instead, this uses
+    /// [Track Changes API][delta] scoped to the folder, whose pages together always
+    /// form a snapshot. Duplicated items are merged, and deleted or moved-out
+    /// items are removed.
+    ///
+    /// # Note
+    /// Track Changes API on non-root folders is only supported on personal OneDrive.
+    /// If it is rejected with HTTP 400 BAD_REQUEST or 501 NOT_IMPLEMENTED, this falls
+    /// back to paged listing with duplicated items merged.
+    ///
+    /// Track Changes API enumerates all descendants of the folder, so it costs more
+    /// requests than paged listing on deeply nested folders.
+    ///
+    /// # Response
+    /// Children of the folder in no particular order.
+    ///
+    /// [list_children]: #method.list_children
+    /// [delta]: #method.track_changes_from_initial_with_option
+    pub async fn list_children_consistent<'a>(
+        &self,
+        folder: impl Into<ItemLocation<'a>>,
+    ) -> Result<Vec<DriveItem>> {
+        let folder_id = self
+            .get_item_with_option(folder, ObjectOption::new().select(&[DriveItemField::id]))
+            .await?
+            .and_then(|item| item.id)
+            .ok_or_else(|| Error::unexpected_response("Missing `id`"))?;
+        let items = match self.track_changes_from_initial(&folder_id).await {
+            Ok(fetcher) => fetcher.fetch_all(self).await?.0,
+            Err(err)
+                if matches!(
+                    err.status_code(),
+                    Some(StatusCode::BAD_REQUEST) | Some(StatusCode::NOT_IMPLEMENTED)
+                ) =>
+            {
+                self.list_children(&folder_id).await?
+            }
+            Err(err) => return Err(err),
+        };
+        Ok(merge_children_snapshot(items, &folder_id))
+    }
+
+    /// Watch changes of the metadata of a single `DriveItem` by polling every `interval`.
+    ///
+    /// Each poll is a conditional GET with `If-None-Match` of the last `eTag`, so unchanged
+    /// polls are cheap. It is a lightweight alternative to [`track_changes_from_initial`][delta]
+    /// for watching only a few items, like a shared spreadsheet.
+    ///
+    /// # Example
+    /// ```
+    /// use onedrive_api::{ItemEvent, ItemLocation, OneDrive};
+    /// use std::time::Duration;
+    ///
+    /// # async fn run(onedrive: &OneDrive) -> onedrive_api::Result<()> {
+    /// let item = ItemLocation::from_path("/report.xlsx").unwrap();
+    /// let mut watcher = onedrive.watch_item(item, Duration::from_secs(30));
+    /// loop {
+    ///     match watcher.next_event().await? {
+    ///         ItemEvent::Initial(item) | ItemEvent::Modified(item) => println!("{:?}", item.e_tag),
+    ///         ItemEvent::Deleted => break,
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`ItemWatcher`][watcher]
+    ///
+    /// [delta]: #method.track_changes_from_initial
+    /// [watcher]: ./struct.ItemWatcher.html
+    pub fn watch_item<'a>(
+        &'a self,
+        item: impl Into<ItemLocation<'a>>,
+        interval: Duration,
+    ) -> ItemWatcher<'a> {
+        ItemWatcher {
+            onedrive: self,
+            item: item.into(),
+            interval,
+            last_tag: None,
+            state: WatchState::Initial,
+        }
+    }
+
+    /// List recent activities on the whole drive. (Beta)
+    ///
+    /// Activities include creating, editing, moving, deleting and sharing items,
+    /// which are typed in [`ItemActionSet`][action_set].
+    ///
+    /// # Note
+    /// Activities are only available on OneDrive for Business and SharePoint.
+    ///
+    /// # See also
+    /// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/activities-list?view=graph-rest-beta)
+    ///
+    /// [action_set]: ./resource/struct.ItemActionSet.html
+    #[cfg(feature = "beta")]
+    pub async fn list_drive_activities(&self) -> Result<ActivityFetcher> {
+        let req = self.request(
+            Method::GET,
+            beta_api_url![&self.drive, "activities"],
+            RequestClass::Metadata,
+        );
+        let resp = self
+            .send_limited(req)
+            .await?
+            .parse(self.strict_accepted)
+            .await?;
+        Ok(ActivityFetcher {
+            fetcher: PageFetcher::new(resp),
+        })
+    }
+
     /// Get a `DriveItem` resource.
     ///
     /// Retrieve the metadata for a [`resource::DriveItem`][drive_item] by file system path or ID.
@@ -184,13 +1031,7 @@ impl OneDrive {
         item: impl Into<ItemLocation<'a>>,
         option: ObjectOption<DriveItemField>,
     ) -> Result<Option<DriveItem>> {
-        self.client
-            .get(api_url![&self.drive, &item.into()])
-            .apply(option)
-            .bearer_auth(&self.token)
-            .send()
-            .await?
-            .parse_optional()
+        self.get_item_in_with_option(&self.drive, item, option)
             .await
     }
 
@@ -206,9 +1047,64 @@ impl OneDrive {
             .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))
     }
 
-    /// Get a pre-authorized download URL for a file.
-    ///
-    /// The URL returned is only valid for a short period of time (a few minutes).
+    /// Get the root folder of the drive, with [`DriveItemField::CORE`][core] fields selected.
+    ///
+    /// # See also
+    /// [`get_item_with_option`][with_opt]
+    ///
+    /// [core]: ./resource/enum.DriveItemField.html#associatedconstant.CORE
+    /// [with_opt]: #method.get_item_with_option
+    pub async fn get_root(&self) -> Result<DriveItem> {
+        self.get_item_with_option(ItemLocation::root(), ObjectOption::new().select_core())
+            .await?
+            .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))
+    }
+
+    /// Get a `DriveItem` resource in another drive `drive`.
+    ///
+    /// Same as [`get_item_with_option`][get_item], but `item` is located in `drive`
+    /// instead of the drive of this `OneDrive`. It is useful to access several drives
+    /// accessible by the same token without creating a `OneDrive` for each of them.
+    ///
+    /// [get_item]: #method.get_item_with_option
+    pub async fn get_item_in_with_option<'a>(
+        &self,
+        drive: &DriveLocation,
+        item: impl Into<ItemLocation<'a>>,
+        option: ObjectOption<DriveItemField>,
+    ) -> Result<Option<DriveItem>> {
+        self.send_metadata_get(
+            self.request(
+                Method::GET,
+                api_url![drive, &item.into()],
+                RequestClass::Metadata,
+            )
+            .apply(option),
+        )
+        .await?
+        .parse_optional(self.strict_accepted)
+        .await
+    }
+
+    /// Shortcut to `get_item_in_with_option` with default parameters.
+    ///
+    /// # See also
+    /// [`get_item_in_with_option`][with_opt]
+    ///
+    /// [with_opt]: #method.get_item_in_with_option
+    pub async fn get_item_in<'a>(
+        &self,
+        drive: &DriveLocation,
+        item: impl Into<ItemLocation<'a>>,
+    ) -> Result<DriveItem> {
+        self.get_item_in_with_option(drive, item, Default::default())
+            .await?
+            .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))
+    }
+
+    /// Get a pre-authorized download URL for a file.
+    ///
+    /// The URL returned is only valid for a short period of time (a few minutes).
     ///
     /// # Note
     /// This API only works with reqwest redirection disabled, which is the default option set by
@@ -229,11 +1125,13 @@ impl OneDrive {
         option: ObjectOption<DriveItemField>,
     ) -> Result<String> {
         let raw_resp = self
-            .client
-            .get(api_url![&self.drive, &item.into(), "content"])
+            .request(
+                Method::GET,
+                api_url![&self.drive, &item.into(), "content"],
+                RequestClass::Metadata,
+            )
             .apply(option)
-            .bearer_auth(&self.token)
-            .send()
+            .send_logged()
             .await?;
         let url = handle_error_response(raw_resp)
             .await?
@@ -264,10 +1162,55 @@ impl OneDrive {
             .await
     }
 
+    /// List the thumbnails of a `DriveItem`.
+    ///
+    /// Retrieve a collection of [`ThumbnailSet`][thumbnail_set] resources for a
+    /// [`DriveItem`][drive_item]. Each set contains thumbnails in different sizes.
+    ///
+    /// Thumbnails can also be fetched along with items by expanding
+    /// [`DriveItemField::thumbnails`][thumbnails_field].
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-list-thumbnails?view=graph-rest-1.0)
+    ///
+    /// [thumbnail_set]: ./resource/struct.ThumbnailSet.html
+    /// [drive_item]: ./resource/struct.DriveItem.html
+    /// [thumbnails_field]: ./resource/enum.DriveItemField.html#variant.thumbnails
+    pub async fn list_thumbnails<'a>(
+        &self,
+        item: impl Into<ItemLocation<'a>>,
+    ) -> Result<Vec<ThumbnailSet>> {
+        #[derive(Deserialize)]
+        struct Resp {
+            value: Vec<ThumbnailSet>,
+        }
+
+        let req = self.request(
+            Method::GET,
+            api_url![&self.drive, &item.into(), "thumbnails"],
+            RequestClass::Metadata,
+        );
+        let resp: Resp = self
+            .send_limited(req)
+            .await?
+            .parse(self.strict_accepted)
+            .await?;
+        Ok(resp.value)
+    }
+
     /// Create a new folder under an DriveItem
     ///
     /// Create a new folder [`DriveItem`][drive_item] with a specified parent item or path.
     ///
+    /// # Response
+    /// The newly created item. Its `e_tag` is taken from the `ETag` response header if
+    /// the body omits it, so it can usually be used in [`if_match`][if_match] directly
+    /// without another request. It is `None` only if both of them lack it.
+    ///
+    /// If [`conflict_behavior`][conflict_behavior] is set to [`Rename`][conflict_rename],
+    /// the server may choose another name, which is reported by
+    /// [`CreatedItem::was_renamed`][was_renamed].
+    ///
     /// # Errors
     /// Will result in `Err` with HTTP 409 CONFLICT if [`conflict_behavior`][conflict_behavior]
     /// is set to [`Fail`][conflict_fail] and the target already exists.
@@ -278,12 +1221,15 @@ impl OneDrive {
     /// [drive_item]: ./resource/struct.DriveItem.html
     /// [conflict_behavior]: ./option/struct.DriveItemPutOption.html#method.conflict_behavior
     /// [conflict_fail]: ./enum.ConflictBehavior.html#variant.Fail
+    /// [conflict_rename]: ./enum.ConflictBehavior.html#variant.Rename
+    /// [if_match]: ./option/struct.DriveItemPutOption.html#method.if_match
+    /// [was_renamed]: ./struct.CreatedItem.html#structfield.was_renamed
     pub async fn create_folder_with_option<'a>(
         &self,
         parent_item: impl Into<ItemLocation<'a>>,
         name: &FileName,
         option: DriveItemPutOption,
-    ) -> Result<DriveItem> {
+    ) -> Result<CreatedItem> {
         #[derive(Serialize)]
         struct Folder {}
 
@@ -296,22 +1242,31 @@ impl OneDrive {
             conflict_behavior: ConflictBehavior,
         }
 
-        let conflict_behavior = option
-            .get_conflict_behavior()
-            .unwrap_or(ConflictBehavior::Fail);
-        self.client
-            .post(api_url![&self.drive, &parent_item.into(), "children"])
-            .bearer_auth(&self.token)
+        let conflict_behavior = self.conflict_behavior_of(&option);
+        let parent_item = parent_item.into();
+        let ret = self
+            .request(
+                Method::POST,
+                api_url![&self.drive, &parent_item, "children"],
+                RequestClass::Metadata,
+            )
             .apply(option)
             .json(&Req {
                 name: name.as_str(),
                 folder: Folder {},
                 conflict_behavior,
             })
-            .send()
+            .send_logged()
             .await?
-            .parse()
-            .await
+            .parse_drive_item(self.strict_accepted)
+            .await;
+        match ret {
+            Ok(item) => Ok(CreatedItem::new(item, Some(name.as_str()))),
+            Err(err) => {
+                let target = api_url![&self.drive, &parent_item, "children", name.as_str()];
+                Err(self.lookup_name_conflict(err, target).await)
+            }
+        }
     }
 
     /// Shortcut to `create_folder_with_option` with default options.
@@ -327,6 +1282,7 @@ impl OneDrive {
     ) -> Result<DriveItem> {
         self.create_folder_with_option(parent_item, name, Default::default())
             .await
+            .map(|created| created.item)
     }
 
     /// Update DriveItem properties
@@ -337,27 +1293,34 @@ impl OneDrive {
     /// you should use [`move_`][move_] (or [`move_with_option`][move_with_opt]) instead of this, which is a wrapper
     /// to this API endpoint to make things easier.
     ///
+    /// # Response
+    /// The updated item, with `e_tag` filled from the response header if the body omits it
+    /// (see [`create_folder_with_option`][create_folder]).
+    ///
     /// # See also
     /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-update?view=graph-rest-1.0)
     ///
     /// [drive_item]: ./resource/struct.DriveItem.html
     /// [move_]: #method.move_
     /// [move_with_opt]: #method.move_with_option
+    /// [create_folder]: #method.create_folder_with_option
     pub async fn update_item_with_option<'a>(
         &self,
         item: impl Into<ItemLocation<'a>>,
         patch: &DriveItem,
         option: ObjectOption<DriveItemField>,
     ) -> Result<DriveItem> {
-        self.client
-            .patch(api_url![&self.drive, &item.into()])
-            .bearer_auth(&self.token)
-            .apply(option)
-            .json(patch)
-            .send()
-            .await?
-            .parse()
-            .await
+        self.request(
+            Method::PATCH,
+            api_url![&self.drive, &item.into()],
+            RequestClass::Metadata,
+        )
+        .apply(option)
+        .json(patch)
+        .send_logged()
+        .await?
+        .parse_drive_item(self.strict_accepted)
+        .await
     }
 
     /// Shortcut to `update_item_with_option` with default options.
@@ -390,6 +1353,22 @@ impl OneDrive {
     /// update the contents of an existing file in a single API call. This method
     /// only supports files up to 4MB in size.
     ///
+    /// Unless [`conflict_behavior`][conflict_behavior] is set in `option`, the
+    /// [default conflict behavior][default_conflict] is used, or it is left to the server
+    /// (replacing existing files) if that is also `None`.
+    ///
+    /// # Response
+    /// The new or updated item, with `e_tag` filled from the response header if the body
+    /// omits it, and `c_tag` reflecting the new content.
+    ///
+    /// If the conflict behavior is [`Rename`][conflict_rename], the server may choose
+    /// another name, which is reported by [`CreatedItem::was_renamed`][was_renamed].
+    ///
+    /// # Errors
+    /// Will result in `Err` with HTTP 409 CONFLICT if the conflict behavior is
+    /// [`Fail`][conflict_fail] and the target already exists, or HTTP 412
+    /// PRECONDITION_FAILED if [`if_match`][if_match] is set but does not match the target.
+    ///
     /// # Panic
     /// Panic if `data` is larger than 4 MB (4,000,000 bytes).
     ///
@@ -397,11 +1376,18 @@ impl OneDrive {
     /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-put-content?view=graph-rest-1.0)
     ///
     /// [drive_item]: ./resource/struct.DriveItem.html
-    pub async fn upload_small<'a>(
+    /// [conflict_behavior]: ./option/struct.DriveItemPutOption.html#method.conflict_behavior
+    /// [default_conflict]: #method.with_default_conflict_behavior
+    /// [conflict_fail]: ./enum.ConflictBehavior.html#variant.Fail
+    /// [conflict_rename]: ./enum.ConflictBehavior.html#variant.Rename
+    /// [if_match]: ./option/struct.DriveItemPutOption.html#method.if_match
+    /// [was_renamed]: ./struct.CreatedItem.html#structfield.was_renamed
+    pub async fn upload_small_with_option<'a>(
         &self,
         item: impl Into<ItemLocation<'a>>,
         data: impl Into<Bytes>,
-    ) -> Result<DriveItem> {
+        option: DriveItemPutOption,
+    ) -> Result<CreatedItem> {
         let data = data.into();
         assert!(
             data.len() <= Self::UPLOAD_SMALL_MAX_SIZE,
@@ -410,16 +1396,369 @@ impl OneDrive {
             Self::UPLOAD_SMALL_MAX_SIZE,
         );
 
-        self.client
-            .put(api_url![&self.drive, &item.into(), "content"])
-            .bearer_auth(&self.token)
+        let item = item.into();
+        let mut req = self.request(
+            Method::PUT,
+            api_url![&self.drive, &item, "content"],
+            RequestClass::Transfer,
+        );
+        let conflict_behavior = option
+            .get_conflict_behavior()
+            .or(self.default_conflict_behavior);
+        if let Some(conflict_behavior) = conflict_behavior {
+            req = req.query(&[("@microsoft.graph.conflictBehavior", conflict_behavior)]);
+        }
+        let ret = req
+            .apply(option)
             .header(header::CONTENT_TYPE, "application/octet-stream")
             .header(header::CONTENT_LENGTH, data.len().to_string())
             .body(data)
-            .send()
+            .send_logged()
+            .await?
+            .parse_drive_item(self.strict_accepted)
+            .await;
+        match ret {
+            Ok(created) => Ok(CreatedItem::new(created, item.name())),
+            Err(err) => Err(self
+                .lookup_name_conflict(err, api_url![&self.drive, &item])
+                .await),
+        }
+    }
+
+    /// Shortcut to `upload_small_with_option` with default options.
+    ///
+    /// # See also
+    /// [`upload_small_with_option`][with_opt]
+    ///
+    /// [with_opt]: #method.upload_small_with_option
+    pub async fn upload_small<'a>(
+        &self,
+        item: impl Into<ItemLocation<'a>>,
+        data: impl Into<Bytes>,
+    ) -> Result<DriveItem> {
+        self.upload_small_with_option(item, data, Default::default())
+            .await
+            .map(|created| created.item)
+    }
+
+    /// Replace the content of an existing file without exposing partial content to readers.
+    ///
+    /// The file is replaced by a new item, so its `id` changes and the old item is sent to
+    /// the recycle bin. Sharing links, permissions and the version history of the old item
+    /// are lost. Use [`upload_small`][upload_small] instead if they should be kept.
+    ///
+    /// Microsoft Graph has no atomic replacement. It is emulated by uploading `data` to a
+    /// temporary file named `.{name}.{timestamp}.tmp` next to `item` through
+    /// [`upload_small`][upload_small], and then moving it over `item` with
+    /// [`ConflictBehavior::Replace`][conflict_replace]. Readers see either the old or
+    /// the new content, but never a partially written one.
+    ///
+    /// Concurrent modifications are only detected best-effort. The move is a request on
+    /// the temporary file, so it cannot be conditional on the `e_tag` of `item`. Instead,
+    /// `item` is checked against the `e_tag` fetched at the beginning right before the move,
+    /// and a modification between the check and the move is silently overwritten.
+    ///
+    /// The temporary file is deleted if replacing fails after it is uploaded.
+    ///
+    /// # Response
+    /// The new item, with a different `id` from the replaced one.
+    ///
+    /// # Errors
+    /// Will return `Err` with HTTP 404 NOT_FOUND if `item` does not exist, or
+    /// HTTP 412 PRECONDITION_FAILED if it is detected to be modified during the replacement.
+    ///
+    /// # Panic
+    /// Panic if `data` is larger than 4 MB (4,000,000 bytes).
+    ///
+    /// [upload_small]: #method.upload_small
+    /// [conflict_replace]: ./enum.ConflictBehavior.html#variant.Replace
+    pub async fn upload_replace_atomic<'a>(
+        &self,
+        item: impl Into<ItemLocation<'a>>,
+        data: impl Into<Bytes>,
+    ) -> Result<DriveItem> {
+        let old = self
+            .get_item_with_option(
+                item,
+                ObjectOption::new().select(&[
+                    DriveItemField::id,
+                    DriveItemField::name,
+                    DriveItemField::e_tag,
+                    DriveItemField::parent_reference,
+                ]),
+            )
             .await?
-            .parse()
+            .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))?;
+        let old_id = old
+            .id
+            .ok_or_else(|| Error::unexpected_response("Missing field `id`"))?;
+        let old_tag = old
+            .e_tag
+            .ok_or_else(|| Error::unexpected_response("Missing field `eTag`"))?;
+        let name = old
+            .name
+            .ok_or_else(|| Error::unexpected_response("Missing field `name`"))?;
+        let parent_id = old
+            .parent_reference
+            .and_then(|parent| parent.id)
+            .ok_or_else(|| Error::unexpected_response("Missing field `parentReference.id`"))?;
+
+        let nanos = self
+            .clock
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let temp_name = format!(".{}.{}.tmp", name, nanos);
+        // Derived from a valid name.
+        let temp_name = FileName::new(&temp_name).unwrap();
+        let temp = self
+            .clone()
+            .with_default_conflict_behavior(Some(ConflictBehavior::Fail))
+            .with_name_conflict_lookup(false)
+            .upload_small(ItemLocation::child_of_id(&parent_id, temp_name), data)
+            .await?;
+        let temp_id = temp
+            .id
+            .ok_or_else(|| Error::unexpected_response("Missing field `id`"))?;
+
+        let ret = async {
+            let modified = self
+                .get_item_with_option(
+                    &old_id,
+                    ObjectOption::new()
+                        .select(&[DriveItemField::id])
+                        .if_none_match(&old_tag),
+                )
+                .await?;
+            if modified.is_some() {
+                return Err(Error::precondition_failed(
+                    "The item is modified during the replacement",
+                ));
+            }
+            self.move_with_option(
+                &temp_id,
+                &parent_id,
+                FileName::new(&name),
+                DriveItemPutOption::new().conflict_behavior(ConflictBehavior::Replace),
+            )
             .await
+        }
+        .await;
+        match ret {
+            Ok(created) => Ok(created.item),
+            Err(err) => {
+                let _ = self.delete_if_exists(&temp_id).await;
+                Err(err)
+            }
+        }
+    }
+
+    /// The maximum number of files packed into a single `$batch` request by [`upload_many`].
+    ///
+    /// The value is the limit of JSON batching from
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/json-batching).
+    ///
+    /// [`upload_many`]: #method.upload_many
+    pub const UPLOAD_MANY_BATCH_SIZE: usize = 20;
+
+    /// The maximum total size of base64-encoded contents in a single `$batch` request
+    /// by [`upload_many`].
+    ///
+    /// [`upload_many`]: #method.upload_many
+    pub const UPLOAD_MANY_BATCH_MAX_BYTES: usize = 4_000_000; // 4 MB
+
+    /// The number of files uploaded concurrently by [`upload_many`] when batching is not used.
+    ///
+    /// [`upload_many`]: #method.upload_many
+    pub const UPLOAD_MANY_CONCURRENCY: usize = 4;
+
+    /// Upload or replace the contents of many small files.
+    ///
+    /// Files are packed into [`$batch`][batching] requests of at most
+    /// [`UPLOAD_MANY_BATCH_SIZE`][batch_size] files and
+    /// [`UPLOAD_MANY_BATCH_MAX_BYTES`][batch_bytes] bytes of base64-encoded contents.
+    /// Files too large to be packed, and files in batches rejected as unsupported
+    /// (HTTP 404, 405, 413 or 501), are uploaded individually through
+    /// [`upload_small`][upload_small] with at most [`UPLOAD_MANY_CONCURRENCY`][concurrency]
+    /// requests at the same time. If a batch fails for other reasons, eg. network errors
+    /// or throttling, all files in it get the error and are not retried, since the batch
+    /// may have been processed.
+    ///
+    /// # Response
+    /// Results of each file, in the same order as `files`. Like
+    /// [`upload_small_with_option`][upload_small_opt], each item is returned with `e_tag`
+    /// filled from the response header if the body omits it, and
+    /// [`CreatedItem::was_renamed`][was_renamed] reports whether the server chose another
+    /// name if the [default conflict behavior][default_conflict] is
+    /// [`Rename`][conflict_rename].
+    ///
+    /// # Panic
+    /// Panic if any of the contents is larger than 4 MB (4,000,000 bytes).
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/json-batching)
+    ///
+    /// [batching]: https://docs.microsoft.com/en-us/graph/json-batching
+    /// [batch_size]: #associatedconstant.UPLOAD_MANY_BATCH_SIZE
+    /// [batch_bytes]: #associatedconstant.UPLOAD_MANY_BATCH_MAX_BYTES
+    /// [concurrency]: #associatedconstant.UPLOAD_MANY_CONCURRENCY
+    /// [upload_small]: #method.upload_small
+    /// [upload_small_opt]: #method.upload_small_with_option
+    /// [was_renamed]: ./struct.CreatedItem.html#structfield.was_renamed
+    /// [default_conflict]: #method.with_default_conflict_behavior
+    /// [conflict_rename]: ./enum.ConflictBehavior.html#variant.Rename
+    pub async fn upload_many<'a, D: Into<Bytes>>(
+        &self,
+        files: impl IntoIterator<Item = (ItemLocation<'a>, D)>,
+    ) -> Vec<Result<CreatedItem>> {
+        use futures_util::stream::{self, StreamExt as _};
+
+        let files = files
+            .into_iter()
+            .map(|(item, data)| (item, data.into()))
+            .collect::<Vec<_>>();
+        for (_, data) in &files {
+            assert!(
+                data.len() <= Self::UPLOAD_SMALL_MAX_SIZE,
+                "Data too large for upload_many ({} B > {} B)",
+                data.len(),
+                Self::UPLOAD_SMALL_MAX_SIZE,
+            );
+        }
+
+        let mut results = files.iter().map(|_| None).collect::<Vec<_>>();
+        let (batches, mut singles) = pack_upload_batches(files.iter().map(|(_, data)| data.len()));
+        for batch in batches {
+            match self.upload_batch(&files, &batch).await {
+                Ok(rets) => {
+                    for (&idx, ret) in batch.iter().zip(rets) {
+                        results[idx] = Some(ret);
+                    }
+                }
+                // Only fall back if the batch is not processed at all for sure, or files
+                // may be uploaded twice.
+                Err(err) if is_batch_unsupported(&err) => singles.extend(batch),
+                Err(err) => {
+                    for (&idx, err) in batch.iter().zip(err.split(batch.len())) {
+                        results[idx] = Some(Err(err));
+                    }
+                }
+            }
+        }
+
+        let mut uploads = stream::iter(singles)
+            .map(|idx| {
+                let (item, data) = &files[idx];
+                let option = DriveItemPutOption::new();
+                async move {
+                    let ret = self
+                        .upload_small_with_option(*item, data.clone(), option)
+                        .await;
+                    (idx, ret)
+                }
+            })
+            .buffer_unordered(Self::UPLOAD_MANY_CONCURRENCY);
+        while let Some((idx, ret)) = uploads.next().await {
+            results[idx] = Some(ret);
+        }
+
+        results
+            .into_iter()
+            .map(|ret| ret.expect("All files are uploaded"))
+            .collect()
+    }
+
+    async fn upload_batch(
+        &self,
+        files: &[(ItemLocation<'_>, Bytes)],
+        batch: &[usize],
+    ) -> Result<Vec<Result<CreatedItem>>> {
+        let requests = batch
+            .iter()
+            .map(|&idx| {
+                let (item, data) = &files[idx];
+                let mut url = api_url![&self.drive, item, "content"];
+                if let Some(conflict_behavior) = self.default_conflict_behavior {
+                    let value = serde_json::to_value(conflict_behavior).unwrap();
+                    url.query_pairs_mut()
+                        .append_pair("@microsoft.graph.conflictBehavior", value.as_str().unwrap());
+                }
+                BatchRequestItem {
+                    id: idx.to_string(),
+                    method: "PUT",
+                    url: batch_url(&url),
+                    headers: Some(
+                        serde_json::json!({ "Content-Type": "application/octet-stream" }),
+                    ),
+                    body: Some(base64::encode(data)),
+                }
+            })
+            .collect();
+
+        let responses = self.send_batch(requests, RequestClass::Transfer).await?;
+        let mut rets = Vec::with_capacity(batch.len());
+        for (&idx, resp) in batch.iter().zip(responses) {
+            let ret = match resp {
+                Ok(resp) => resp
+                    .parse_drive_item(self.strict_accepted)
+                    .await
+                    .map(|item| CreatedItem::new(item, files[idx].0.name())),
+                Err(err) => Err(err),
+            };
+            rets.push(ret);
+        }
+        Ok(rets)
+    }
+
+    /// Send sub-requests in a single [`$batch`][batching] request.
+    ///
+    /// The response of each sub-request is returned as a `Response` in the same order,
+    /// so it can be parsed by `ResponseExt` like any other response.
+    ///
+    /// [batching]: https://docs.microsoft.com/en-us/graph/json-batching
+    async fn send_batch(
+        &self,
+        requests: Vec<BatchRequestItem>,
+        class: RequestClass,
+    ) -> Result<Vec<Result<Response>>> {
+        #[derive(Serialize)]
+        struct BatchRequest<'a> {
+            requests: &'a [BatchRequestItem],
+        }
+
+        #[derive(Deserialize)]
+        struct BatchResponse {
+            responses: Vec<BatchResponseItem>,
+        }
+
+        let req = self
+            .request(Method::POST, api_url!["$batch"], class)
+            .json(&BatchRequest {
+                requests: &requests,
+            });
+        let resp: BatchResponse = self
+            .send_limited(req)
+            .await?
+            .parse(self.strict_accepted)
+            .await?;
+
+        let mut responses = resp
+            .responses
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect::<std::collections::HashMap<_, _>>();
+        Ok(requests
+            .iter()
+            .map(|req| {
+                responses
+                    .remove(&req.id)
+                    .ok_or_else(|| {
+                        Error::unexpected_response("Missing response in batch response")
+                    })?
+                    .into_response()
+            })
+            .collect())
     }
 
     /// Create an upload session.
@@ -471,28 +1810,30 @@ impl OneDrive {
             meta: UploadSessionMeta,
         }
 
-        let conflict_behavior = option
-            .get_conflict_behavior()
-            .unwrap_or(ConflictBehavior::Fail);
+        let conflict_behavior = self.conflict_behavior_of(&option);
         let resp: Resp = self
-            .client
-            .post(api_url![&self.drive, &item.into(), "createUploadSession"])
+            .request(
+                Method::POST,
+                api_url![&self.drive, &item.into(), "createUploadSession"],
+                RequestClass::Metadata,
+            )
             .apply(option)
-            .bearer_auth(&self.token)
             .json(&Req {
                 item: Item {
                     conflict_behavior,
                     initial,
                 },
             })
-            .send()
+            .send_logged()
             .await?
-            .parse()
+            .parse(self.strict_accepted)
             .await?;
 
         Ok((
             UploadSession {
                 upload_url: resp.upload_url,
+                expiration_date_time: Some(resp.meta.expiration_date_time.clone()),
+                timeout: self.timeouts.transfer,
             },
             resp.meta,
         ))
@@ -511,7 +1852,7 @@ impl OneDrive {
             .await
     }
 
-    /// Shortcut to [`new_upload_session_with_option`] with `ConflictBehavior::Fail`.
+    /// Shortcut to [`new_upload_session_with_option`] with the default conflict behavior.
     ///
     /// [`new_upload_session_with_option`]: #method.new_upload_session_with_option
     pub async fn new_upload_session<'a>(
@@ -547,35 +1888,30 @@ impl OneDrive {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct Req<'a> {
-            parent_reference: ItemReference<'a>,
+            parent_reference: ParentReference<'a>,
             name: &'a str,
         }
 
         let raw_resp = self
-            .client
-            .post(api_url![&self.drive, &source_item.into(), "copy"])
-            .bearer_auth(&self.token)
+            .request(
+                Method::POST,
+                api_url![&self.drive, &source_item.into(), "copy"],
+                RequestClass::Metadata,
+            )
             .json(&Req {
-                parent_reference: ItemReference {
+                parent_reference: ParentReference {
                     path: api_path!(&dest_folder.into()),
                 },
                 name: dest_name.as_str(),
             })
-            .send()
+            .send_logged()
             .await?;
 
-        let url = handle_error_response(raw_resp)
-            .await?
-            .headers()
-            .get(header::LOCATION)
-            .ok_or_else(|| {
-                Error::unexpected_response("Header `Location` not exists in response of `copy`")
-            })?
-            .to_str()
-            .map_err(|_| Error::unexpected_response("Invalid string header `Location`"))?
-            .to_owned();
-
-        Ok(CopyProgressMonitor::from_monitor_url(url))
+        let url = raw_resp.parse_async_job(self.strict_accepted).await?;
+        Ok(CopyProgressMonitor {
+            monitor_url: url,
+            dest_name: Some(dest_name.as_str().to_owned()),
+        })
     }
 
     /// Move a DriveItem to a new folder.
@@ -589,6 +1925,15 @@ impl OneDrive {
     /// # Note
     /// [`conflict_behavior`][conflict_behavior] is supported.
     ///
+    /// # Response
+    /// The moved item, with `e_tag` filled from the response header if the body omits it.
+    ///
+    /// If [`conflict_behavior`][conflict_behavior] is set to [`Rename`][conflict_rename],
+    /// the server may choose another name, which is reported by
+    /// [`CreatedItem::was_renamed`][was_renamed]. If `dest_name` is not given, it is
+    /// compared with the name of `source_item`, so it is always `false` if `source_item`
+    /// is located by id.
+    ///
     /// # Errors
     /// Will return `Err` with HTTP 412 PRECONDITION_FAILED if [`if_match`][if_match] is set
     /// but it does not match the item.
@@ -597,44 +1942,64 @@ impl OneDrive {
     /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-move?view=graph-rest-1.0)
     ///
     /// [conflict_behavior]: ./option/struct.DriveItemPutOption.html#method.conflict_behavior
+    /// [conflict_rename]: ./enum.ConflictBehavior.html#variant.Rename
     /// [if_match]: ./option/struct.CollectionOption.html#method.if_match
+    /// [was_renamed]: ./struct.CreatedItem.html#structfield.was_renamed
     pub async fn move_with_option<'a, 'b>(
         &self,
         source_item: impl Into<ItemLocation<'a>>,
         dest_folder: impl Into<ItemLocation<'b>>,
         dest_name: Option<&FileName>,
         option: DriveItemPutOption,
-    ) -> Result<DriveItem> {
+    ) -> Result<CreatedItem> {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct Req<'a> {
-            parent_reference: ItemReference<'a>,
+            parent_reference: ParentReference<'a>,
             name: Option<&'a str>,
             #[serde(rename = "@microsoft.graph.conflictBehavior")]
             conflict_behavior: ConflictBehavior,
         }
 
-        let conflict_behavior = option
-            .get_conflict_behavior()
-            .unwrap_or(ConflictBehavior::Fail);
-        self.client
-            .patch(api_url![&self.drive, &source_item.into()])
-            .bearer_auth(&self.token)
+        let conflict_behavior = self.conflict_behavior_of(&option);
+        let source_item = source_item.into();
+        let dest_folder = dest_folder.into();
+        let ret = self
+            .request(
+                Method::PATCH,
+                api_url![&self.drive, &source_item],
+                RequestClass::Metadata,
+            )
             .apply(option)
             .json(&Req {
-                parent_reference: ItemReference {
-                    path: api_path!(&dest_folder.into()),
+                parent_reference: ParentReference {
+                    path: api_path!(&dest_folder),
                 },
                 name: dest_name.map(FileName::as_str),
                 conflict_behavior,
             })
-            .send()
+            .send_logged()
             .await?
-            .parse()
-            .await
+            .parse_drive_item(self.strict_accepted)
+            .await;
+        match (ret, dest_name) {
+            // The source name is kept if `dest_name` is not given.
+            (Ok(item), _) => Ok(CreatedItem::new(
+                item,
+                dest_name
+                    .map(FileName::as_str)
+                    .or_else(|| source_item.name()),
+            )),
+            // The name is unknown without an extra request if not renamed.
+            (Err(err), None) => Err(err),
+            (Err(err), Some(name)) => {
+                let target = api_url![&self.drive, &dest_folder, "children", name.as_str()];
+                Err(self.lookup_name_conflict(err, target).await)
+            }
+        }
     }
 
-    /// Shortcut to `move_with_option` with `ConflictBehavior::Fail`.
+    /// Shortcut to `move_with_option` with the default conflict behavior.
     ///
     /// # See also
     /// [`move_with_option`][with_opt]
@@ -648,6 +2013,7 @@ impl OneDrive {
     ) -> Result<DriveItem> {
         self.move_with_option(source_item, dest_folder, dest_name, Default::default())
             .await
+            .map(|created| created.item)
     }
 
     /// Delete a `DriveItem`.
@@ -679,14 +2045,16 @@ impl OneDrive {
             "`conflict_behavior` is not supported by `delete[_with_option]`",
         );
 
-        self.client
-            .delete(api_url![&self.drive, &item.into()])
-            .bearer_auth(&self.token)
+        let resp = self
+            .request(
+                Method::DELETE,
+                api_url![&self.drive, &item.into()],
+                RequestClass::Metadata,
+            )
             .apply(option)
-            .send()
-            .await?
-            .parse_no_content()
-            .await
+            .send_logged()
+            .await?;
+        resp.parse_no_content(self.strict_accepted).await
     }
 
     /// Shortcut to `delete_with_option`.
@@ -699,15 +2067,161 @@ impl OneDrive {
         self.delete_with_option(item, Default::default()).await
     }
 
-    /// Track changes for root folder from initial state (empty state) to snapshot of current states.
+    /// Delete a `DriveItem` if it exists.
+    ///
+    /// Same as [`delete_with_option`][delete] but treat HTTP 404 NOT_FOUND as success.
+    /// It is useful for retrying and cleanup jobs, where a previous attempt may
+    /// have already deleted the item.
+    ///
+    /// # Response
+    /// Return `true` if the item is deleted by this request, or `false` if it does not exist.
+    ///
+    /// # Panic
+    /// Same as [`delete_with_option`][delete].
+    ///
+    /// [delete]: #method.delete_with_option
+    pub async fn delete_if_exists_with_option<'a>(
+        &self,
+        item: impl Into<ItemLocation<'a>>,
+        option: DriveItemPutOption,
+    ) -> Result<bool> {
+        deleted_if_exists(self.delete_with_option(item, option).await)
+    }
+
+    /// Shortcut to `delete_if_exists_with_option`.
+    ///
+    /// # See also
+    /// [`delete_if_exists_with_option`][with_opt]
+    ///
+    /// [with_opt]: #method.delete_if_exists_with_option
+    pub async fn delete_if_exists<'a>(&self, item: impl Into<ItemLocation<'a>>) -> Result<bool> {
+        self.delete_if_exists_with_option(item, Default::default())
+            .await
+    }
+
+    /// The maximum number of items packed into a single `$batch` request by
+    /// [`delete_many_if_exists`].
+    ///
+    /// [`delete_many_if_exists`]: #method.delete_many_if_exists
+    pub const DELETE_MANY_BATCH_SIZE: usize = 20;
+
+    /// The number of items deleted concurrently by [`delete_many_if_exists`] when batching
+    /// is not used.
+    ///
+    /// [`delete_many_if_exists`]: #method.delete_many_if_exists
+    pub const DELETE_MANY_CONCURRENCY: usize = 4;
+
+    /// Delete many `DriveItem`s if they exist.
+    ///
+    /// Items are packed into [`$batch`][batching] requests of at most
+    /// [`DELETE_MANY_BATCH_SIZE`][batch_size] items. Items in batches rejected as unsupported
+    /// (HTTP 404, 405, 413 or 501) are deleted individually through
+    /// [`delete_if_exists`][delete_if_exists] with at most
+    /// [`DELETE_MANY_CONCURRENCY`][concurrency] requests at the same time. If a batch fails
+    /// for other reasons, eg. network errors or throttling, all items in it get the error.
+    /// Like [`delete_if_exists`][delete_if_exists], it is safe to retry failed items.
+    ///
+    /// # Response
+    /// Results of each item, in the same order as `items`. Same as
+    /// [`delete_if_exists`][delete_if_exists], each is `true` if the item is deleted by
+    /// this request, or `false` if it does not exist.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/json-batching)
+    ///
+    /// [batching]: https://docs.microsoft.com/en-us/graph/json-batching
+    /// [batch_size]: #associatedconstant.DELETE_MANY_BATCH_SIZE
+    /// [concurrency]: #associatedconstant.DELETE_MANY_CONCURRENCY
+    /// [delete_if_exists]: #method.delete_if_exists
+    pub async fn delete_many_if_exists<'a>(
+        &self,
+        items: impl IntoIterator<Item = ItemLocation<'a>>,
+    ) -> Vec<Result<bool>> {
+        use futures_util::stream::{self, StreamExt as _};
+
+        let items = items.into_iter().collect::<Vec<_>>();
+        let mut results = items.iter().map(|_| None).collect::<Vec<_>>();
+        let mut singles = vec![];
+        let indices = (0..items.len()).collect::<Vec<_>>();
+        for batch in indices.chunks(Self::DELETE_MANY_BATCH_SIZE) {
+            // No need to batch a single item.
+            if batch.len() == 1 {
+                singles.extend_from_slice(batch);
+                continue;
+            }
+            match self.delete_batch(&items, batch).await {
+                Ok(rets) => {
+                    for (&idx, ret) in batch.iter().zip(rets) {
+                        results[idx] = Some(ret);
+                    }
+                }
+                Err(err) if is_batch_unsupported(&err) => singles.extend_from_slice(batch),
+                Err(err) => {
+                    for (&idx, err) in batch.iter().zip(err.split(batch.len())) {
+                        results[idx] = Some(Err(err));
+                    }
+                }
+            }
+        }
+
+        let mut deletes = stream::iter(singles)
+            .map(|idx| {
+                let item = items[idx];
+                async move { (idx, self.delete_if_exists(item).await) }
+            })
+            .buffer_unordered(Self::DELETE_MANY_CONCURRENCY);
+        while let Some((idx, ret)) = deletes.next().await {
+            results[idx] = Some(ret);
+        }
+
+        results
+            .into_iter()
+            .map(|ret| ret.expect("All items are deleted"))
+            .collect()
+    }
+
+    async fn delete_batch(
+        &self,
+        items: &[ItemLocation<'_>],
+        batch: &[usize],
+    ) -> Result<Vec<Result<bool>>> {
+        let requests = batch
+            .iter()
+            .map(|&idx| BatchRequestItem {
+                id: idx.to_string(),
+                method: "DELETE",
+                url: batch_url(&api_url![&self.drive, &items[idx]]),
+                headers: None,
+                body: None,
+            })
+            .collect();
+
+        let responses = self.send_batch(requests, RequestClass::Metadata).await?;
+        let mut rets = Vec::with_capacity(batch.len());
+        for resp in responses {
+            let ret = match resp {
+                Ok(resp) => resp.parse_no_content(self.strict_accepted).await,
+                Err(err) => Err(err),
+            };
+            rets.push(deleted_if_exists(ret));
+        }
+        Ok(rets)
+    }
+
+    /// Track changes for a folder from initial state (empty state) to snapshot of current states.
     ///
-    /// This method allows your app to track changes to a drive and its children over time.
+    /// This method allows your app to track changes to a folder and its children over time.
     /// Deleted items are returned with the deleted facet. Items with this property set
     /// should be removed from your local state.
     ///
     /// Note: you should only delete a folder locally if it is empty after
     /// syncing all the changes.
     ///
+    /// # Note
+    /// Tracking changes of non-root folders is only supported on personal OneDrive.
+    /// OneDrive for Business and SharePoint only support tracking the root folder,
+    /// and will respond an error for other folders.
+    ///
     /// # Panic
     /// Track Changes API does not support [`$count=true` query parameter][dollar_count].
     /// If [`CollectionOption::get_count`][opt_get_count] is set in option, it will panic.
@@ -722,66 +2236,77 @@ impl OneDrive {
     /// [fetcher]: ./struct.TrackChangeFetcher.html
     /// [dollar_count]: https://docs.microsoft.com/en-us/graph/query-parameters#count-parameter
     /// [opt_get_count]: ./option/struct.CollectionOption.html#method.get_count
-    pub async fn track_root_changes_from_initial_with_option<'a>(
+    pub async fn track_changes_from_initial_with_option<'a>(
         &self,
+        folder: impl Into<ItemLocation<'a>>,
         option: CollectionOption<DriveItemField>,
     ) -> Result<TrackChangeFetcher> {
         assert!(
             !option.has_get_count(),
             "`get_count` is not supported by Track Changes API",
         );
+        let req = self
+            .request(
+                Method::GET,
+                api_url![&self.drive, &folder.into(), "delta"],
+                RequestClass::Metadata,
+            )
+            .apply(option);
         let resp = self
-            .client
-            .get(api_url![&self.drive, "root", "delta"])
-            .apply(option)
-            .bearer_auth(&self.token)
-            .send()
+            .send_limited(req)
             .await?
-            .parse()
+            .parse(self.strict_accepted)
             .await?;
         Ok(TrackChangeFetcher::new(resp))
     }
 
-    /// Shortcut to `track_root_changes_from_initial_with_option` with default parameters.
+    /// Shortcut to `track_changes_from_initial_with_option` with default parameters.
     ///
     /// # See also
-    /// [`track_root_changes_from_initial_with_option`][with_opt]
+    /// [`track_changes_from_initial_with_option`][with_opt]
     ///
-    /// [with_opt]: #method.track_root_changes_from_initial_with_option
-    pub async fn track_root_changes_from_initial<'a>(&self) -> Result<TrackChangeFetcher> {
-        self.track_root_changes_from_initial_with_option(Default::default())
+    /// [with_opt]: #method.track_changes_from_initial_with_option
+    pub async fn track_changes_from_initial<'a>(
+        &self,
+        folder: impl Into<ItemLocation<'a>>,
+    ) -> Result<TrackChangeFetcher> {
+        self.track_changes_from_initial_with_option(folder, Default::default())
             .await
     }
 
-    /// Track changes for root folder from snapshot (delta url) to snapshot of current states.
+    /// Track changes for a folder from snapshot (delta url) to snapshot of current states.
+    ///
+    /// The folder is determined by the delta url.
     ///
     /// # Note
     /// There is no `with_option` version of this function. Since delta URL already carries
     /// query parameters when you get it. The initial parameters will be automatically used
     /// in all following requests through delta URL.
-    pub async fn track_root_changes_from_delta_url(
+    pub async fn track_changes_from_delta_url(
         &self,
         delta_url: &str,
     ) -> Result<TrackChangeFetcher> {
+        let req = self.request(Method::GET, delta_url, RequestClass::Metadata);
         let resp: DriveItemCollectionResponse = self
-            .client
-            .get(delta_url)
-            .bearer_auth(&self.token)
-            .send()
+            .send_limited(req)
             .await?
-            .parse()
+            .parse(self.strict_accepted)
             .await?;
         Ok(TrackChangeFetcher::new(resp))
     }
 
-    /// Get a delta url representing the snapshot of current states of root folder.
+    /// Get a delta url representing the snapshot of current states of a folder.
     ///
-    /// The delta url can be used in [`track_root_changes_from_delta_url`][track_from_delta] later
+    /// The delta url can be used in [`track_changes_from_delta_url`][track_from_delta] later
     /// to get diffs between two snapshots of states.
     ///
     /// Note that options (query parameters) are saved in delta url, so they are applied to all later
     /// requests by `track_changes_from_delta_url` without need for specifying them every time.
     ///
+    /// # Note
+    /// Like [`track_changes_from_initial_with_option`][track_initial], non-root folders are
+    /// only supported on personal OneDrive.
+    ///
     /// # Panic
     /// Track Changes API does not support [`$count=true` query parameter][dollar_count].
     /// If [`CollectionOption::get_count`][opt_get_count] is set in option, it will panic.
@@ -789,25 +2314,30 @@ impl OneDrive {
     /// # See also
     /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-delta?view=graph-rest-1.0#retrieving-the-current-deltalink)
     ///
-    /// [track_from_delta]: #method.track_root_changes_from_delta_url
+    /// [track_from_delta]: #method.track_changes_from_delta_url
+    /// [track_initial]: #method.track_changes_from_initial_with_option
     /// [dollar_count]: https://docs.microsoft.com/en-us/graph/query-parameters#count-parameter
     /// [opt_get_count]: ./option/struct.CollectionOption.html#method.get_count
-    pub async fn get_root_latest_delta_url_with_option<'a>(
+    pub async fn get_latest_delta_url_with_option<'a>(
         &self,
+        folder: impl Into<ItemLocation<'a>>,
         option: CollectionOption<DriveItemField>,
     ) -> Result<String> {
         assert!(
             !option.has_get_count(),
             "`get_count` is not supported by Track Changes API",
         );
-        self.client
-            .get(api_url![&self.drive, "root", "delta"])
+        let req = self
+            .request(
+                Method::GET,
+                api_url![&self.drive, &folder.into(), "delta"],
+                RequestClass::Metadata,
+            )
             .query(&[("token", "latest")])
-            .apply(option)
-            .bearer_auth(&self.token)
-            .send()
+            .apply(option);
+        self.send_limited(req)
             .await?
-            .parse::<DriveItemCollectionResponse>()
+            .parse::<DriveItemCollectionResponse>(self.strict_accepted)
             .await?
             .delta_url
             .ok_or_else(|| {
@@ -817,20 +2347,610 @@ impl OneDrive {
             })
     }
 
+    /// Shortcut to `get_latest_delta_url_with_option` with default parameters.
+    ///
+    /// # See also
+    /// [`get_latest_delta_url_with_option`][with_opt]
+    ///
+    /// [with_opt]: #method.get_latest_delta_url_with_option
+    pub async fn get_latest_delta_url<'a>(
+        &self,
+        folder: impl Into<ItemLocation<'a>>,
+    ) -> Result<String> {
+        self.get_latest_delta_url_with_option(folder, Default::default())
+            .await
+    }
+
+    /// Track changes for a folder from snapshot (delta token) to snapshot of current states.
+    ///
+    /// Same as [`track_changes_from_delta_url`][track_delta], but check that the token
+    /// targets the drive of this `OneDrive` first.
+    ///
+    /// # Errors
+    /// Will return `Err` with [`Error::is_invalid_argument`][invalid] being `true` if
+    /// [`DeltaToken::targets_drive`][targets_drive] returns `false` on the drive
+    /// of this `OneDrive`, without sending any request.
+    ///
+    /// [invalid]: ./struct.Error.html#method.is_invalid_argument
+    ///
+    /// [track_delta]: #method.track_changes_from_delta_url
+    /// [targets_drive]: ./struct.DeltaToken.html#method.targets_drive
+    pub async fn track_changes_from_delta_token(
+        &self,
+        delta_token: &DeltaToken,
+    ) -> Result<TrackChangeFetcher> {
+        if !delta_token.targets_drive(&self.drive) {
+            return Err(Error::invalid_argument(
+                "Delta token does not target the drive of this OneDrive",
+            ));
+        }
+        self.track_changes_from_delta_url(delta_token.delta_url())
+            .await
+    }
+
+    /// Get a delta token representing the snapshot of current states of a folder.
+    ///
+    /// Same as [`get_latest_delta_url`][get_latest], but wrap the result into a `DeltaToken`
+    /// obtained at the time of [`clock`][clock].
+    ///
+    /// [get_latest]: #method.get_latest_delta_url
+    /// [clock]: #method.clock
+    pub async fn get_latest_delta_token<'a>(
+        &self,
+        folder: impl Into<ItemLocation<'a>>,
+    ) -> Result<DeltaToken> {
+        let delta_url = self.get_latest_delta_url(folder).await?;
+        Ok(DeltaToken::new_at(delta_url, self.clock.now()))
+    }
+
+    /// Track changes for root folder from initial state (empty state) to snapshot of current states.
+    ///
+    /// Same as [`track_changes_from_initial_with_option`][track_initial] on
+    /// [`ItemLocation::root`][root].
+    ///
+    /// [track_initial]: #method.track_changes_from_initial_with_option
+    /// [root]: ./struct.ItemLocation.html#method.root
+    pub async fn track_root_changes_from_initial_with_option(
+        &self,
+        option: CollectionOption<DriveItemField>,
+    ) -> Result<TrackChangeFetcher> {
+        self.track_changes_from_initial_with_option(ItemLocation::root(), option)
+            .await
+    }
+
+    /// Shortcut to `track_root_changes_from_initial_with_option` with default parameters.
+    ///
+    /// # See also
+    /// [`track_root_changes_from_initial_with_option`][with_opt]
+    ///
+    /// [with_opt]: #method.track_root_changes_from_initial_with_option
+    pub async fn track_root_changes_from_initial(&self) -> Result<TrackChangeFetcher> {
+        self.track_root_changes_from_initial_with_option(Default::default())
+            .await
+    }
+
+    /// Track changes for root folder from initial state, with [`DriveItemField::CORE`][core]
+    /// fields and the `deleted` facet selected.
+    ///
+    /// It is the common entry point to sync the whole drive. Continue with
+    /// [`track_root_changes_from_delta_url`][track_delta] using the delta url got at the end.
+    ///
+    /// # See also
+    /// [`track_root_changes_from_initial_with_option`][with_opt]
+    ///
+    /// [core]: ./resource/enum.DriveItemField.html#associatedconstant.CORE
+    /// [track_delta]: #method.track_root_changes_from_delta_url
+    /// [with_opt]: #method.track_root_changes_from_initial_with_option
+    pub async fn root_delta(&self) -> Result<TrackChangeFetcher> {
+        self.track_root_changes_from_initial_with_option(
+            CollectionOption::new()
+                .select_core()
+                .select(&[DriveItemField::deleted]),
+        )
+        .await
+    }
+
+    /// Track changes for root folder from snapshot (delta url) to snapshot of current states.
+    ///
+    /// Same as [`track_changes_from_delta_url`][track_delta].
+    ///
+    /// [track_delta]: #method.track_changes_from_delta_url
+    pub async fn track_root_changes_from_delta_url(
+        &self,
+        delta_url: &str,
+    ) -> Result<TrackChangeFetcher> {
+        self.track_changes_from_delta_url(delta_url).await
+    }
+
+    /// Get a delta url representing the snapshot of current states of root folder.
+    ///
+    /// Same as [`get_latest_delta_url_with_option`][get_latest] on
+    /// [`ItemLocation::root`][root].
+    ///
+    /// [get_latest]: #method.get_latest_delta_url_with_option
+    /// [root]: ./struct.ItemLocation.html#method.root
+    pub async fn get_root_latest_delta_url_with_option(
+        &self,
+        option: CollectionOption<DriveItemField>,
+    ) -> Result<String> {
+        self.get_latest_delta_url_with_option(ItemLocation::root(), option)
+            .await
+    }
+
     /// Shortcut to `get_root_latest_delta_url_with_option` with default parameters.
     ///
     /// # See also
     /// [`get_root_latest_delta_url_with_option`][with_opt]
     ///
     /// [with_opt]: #method.get_root_latest_delta_url_with_option
-    pub async fn get_root_latest_delta_url<'a>(&self) -> Result<String> {
+    pub async fn get_root_latest_delta_url(&self) -> Result<String> {
         self.get_root_latest_delta_url_with_option(Default::default())
             .await
     }
+
+    /// Search drive items through the Microsoft Search API.
+    ///
+    /// Unlike other methods, it searches all drives accessible by the user,
+    /// not only the drive of this instance. Types of queries and results are in
+    /// module [`search`][search].
+    ///
+    /// # Note
+    /// It is only available for work or school accounts.
+    /// Use [`next_page`][next_page] to get the query of the following page.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/search-query?view=graph-rest-1.0)
+    ///
+    /// [search]: ./search/index.html
+    /// [next_page]: ./search/struct.SearchQuery.html#method.next_page
+    pub async fn search_query(&self, query: &SearchQuery) -> Result<SearchResponse> {
+        #[derive(Serialize)]
+        struct Req<'a> {
+            requests: [&'a SearchQuery; 1],
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ResponseValue {
+            #[serde(default)]
+            hits_containers: Vec<SearchResponse>,
+        }
+
+        #[derive(Deserialize)]
+        struct Resp {
+            value: Vec<ResponseValue>,
+        }
+
+        let req = self
+            .request(
+                Method::POST,
+                api_url!["search", "query"],
+                RequestClass::Metadata,
+            )
+            .json(&Req { requests: [query] });
+        let resp: Resp = self
+            .send_limited(req)
+            .await?
+            .parse(self.strict_accepted)
+            .await?;
+        Ok(resp
+            .value
+            .into_iter()
+            .flat_map(|v| v.hits_containers)
+            .next()
+            .unwrap_or_default())
+    }
+
+    /// Get a Socket.IO endpoint to receive real-time change notifications of the drive.
+    ///
+    /// Notifications are sent when there are changes in the root folder (recursively) of the
+    /// drive, which can be used to trigger [`track_root_changes_from_delta_url`][track_delta]
+    /// instead of polling on a timer.
+    ///
+    /// # Note
+    /// A Socket.IO client is needed to connect to
+    /// [`SocketIoEndpoint::notification_url`][url]. This crate doesn't provide one.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/subscriptions-socketio?view=graph-rest-1.0)
+    ///
+    /// [track_delta]: #method.track_root_changes_from_delta_url
+    /// [url]: ./resource/struct.SocketIoEndpoint.html#structfield.notification_url
+    pub async fn get_socket_io_endpoint(&self) -> Result<SocketIoEndpoint> {
+        self.request(
+            Method::GET,
+            api_url![&self.drive, "root", "subscriptions", "socketIo"],
+            RequestClass::Metadata,
+        )
+        .send_logged()
+        .await?
+        .parse(self.strict_accepted)
+        .await
+    }
+
+    /// Get the path of a `DriveItem` relative to the drive root, like `/dir/file`.
+    ///
+    /// The path is built from `name` and [`ItemReference::path`][path] of the parent. If the
+    /// path of the parent is not provided by the server (eg. for some drive types),
+    /// parents are fetched one by one until one with a known path, or the root, is reached.
+    ///
+    /// It is useful to map items from [`track_root_changes_from_initial`][track], which are
+    /// identified by ids, back to paths.
+    ///
+    /// # Note
+    /// The path segments are returned as-is from the server.
+    ///
+    /// # See also
+    /// [`ItemReference::root_relative_path`][root_relative_path]
+    ///
+    /// [path]: ./resource/struct.ItemReference.html#structfield.path
+    /// [root_relative_path]: ./resource/struct.ItemReference.html#method.root_relative_path
+    /// [track]: #method.track_root_changes_from_initial
+    pub async fn get_item_path(&self, item: &ItemId) -> Result<String> {
+        let mut names = vec![];
+        let mut current = item.clone();
+        let prefix = loop {
+            let item = self
+                .get_item_with_option(
+                    &current,
+                    ObjectOption::new().select(&[
+                        DriveItemField::name,
+                        DriveItemField::parent_reference,
+                        DriveItemField::root,
+                    ]),
+                )
+                .await?
+                .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))?;
+            if item.root.is_some() {
+                break String::new();
+            }
+            names.push(
+                item.name
+                    .ok_or_else(|| Error::unexpected_response("Missing field `name`"))?,
+            );
+            let parent = item
+                .parent_reference
+                .ok_or_else(|| Error::unexpected_response("Missing field `parentReference`"))?;
+            match parent.root_relative_path() {
+                Some("/") => break String::new(),
+                Some(path) => break path.to_owned(),
+                None => {
+                    current = parent.id.ok_or_else(|| {
+                        Error::unexpected_response("Missing field `parentReference.id`")
+                    })?;
+                }
+            }
+        };
+
+        if names.is_empty() {
+            return Ok("/".to_owned());
+        }
+        let mut path = prefix;
+        for name in names.iter().rev() {
+            path.push('/');
+            path.push_str(name);
+        }
+        Ok(path)
+    }
+
+    /// The default number of folders listed concurrently in [`folder_size`].
+    ///
+    /// [`folder_size`]: #method.folder_size
+    pub const FOLDER_SIZE_CONCURRENCY: usize = 4;
+
+    /// The maximum number of folders listed in a single [`$batch`][batching] request
+    /// in [`folder_size`].
+    ///
+    /// [batching]: https://docs.microsoft.com/en-us/graph/json-batching
+    /// [`folder_size`]: #method.folder_size
+    pub const FOLDER_SIZE_BATCH_SIZE: usize = 20;
+
+    /// Calculate the total size and item count beneath a folder.
+    ///
+    /// The `size` property of a folder is not always available or up to date for some drive types.
+    /// This method walks through all descendants instead. Children of pending folders are
+    /// listed in [`$batch`][batching] requests of at most
+    /// [`FOLDER_SIZE_BATCH_SIZE`][batch_size] folders, and at most `concurrency` requests
+    /// are sent at the same time. Further pages of a large folder are fetched separately.
+    /// If batching is not available, folders are listed one by one instead.
+    ///
+    /// Requests or sub-requests failed with [transient errors][is_transient], like being
+    /// throttled by the server (HTTP 429 or 503), are retried after the time indicated by
+    /// [`Error::retry_after`][retry_after]. Only throttled folders of a batch are retried.
+    ///
+    /// # Note
+    /// Items shared from other drives (with `remote_item` facet) are counted but not descended.
+    ///
+    /// The operation is not atomic. Concurrent modifications in the folder may or may not
+    /// be counted.
+    ///
+    /// # Panic
+    /// Panic if `concurrency` is zero.
+    ///
+    /// [batching]: https://docs.microsoft.com/en-us/graph/json-batching
+    /// [batch_size]: #associatedconstant.FOLDER_SIZE_BATCH_SIZE
+    /// [is_transient]: ./struct.Error.html#method.is_transient
+    /// [retry_after]: ./struct.Error.html#method.retry_after
+    pub async fn folder_size_with_concurrency<'a>(
+        &self,
+        folder: impl Into<ItemLocation<'a>>,
+        concurrency: usize,
+    ) -> Result<FolderSize> {
+        use futures_util::stream::{FuturesUnordered, StreamExt as _};
+
+        assert!(concurrency > 0, "`concurrency` should be positive");
+
+        let mut ret = FolderSize::default();
+        let mut pending_folders = vec![];
+        let mut count_children = |children: Vec<DriveItem>, pending_folders: &mut Vec<ItemId>| {
+            for child in children {
+                if child.folder.is_some() && child.remote_item.is_none() {
+                    ret.folder_count += 1;
+                    pending_folders.extend(child.id);
+                } else {
+                    ret.file_count += 1;
+                    ret.total_bytes += child.size.map_or(0, |size| size.max(0) as u64);
+                }
+            }
+        };
+
+        let children = self.list_children_for_size(folder.into()).await?;
+        count_children(children, &mut pending_folders);
+
+        let mut running = FuturesUnordered::new();
+        loop {
+            while running.len() < concurrency && !pending_folders.is_empty() {
+                // Spread pending folders over free slots, so small levels are still
+                // listed concurrently.
+                let free = concurrency - running.len();
+                let size = pending_folders
+                    .len()
+                    .div_ceil(free)
+                    .min(Self::FOLDER_SIZE_BATCH_SIZE);
+                let ids = pending_folders.split_off(pending_folders.len() - size);
+                running.push(self.list_children_batch_for_size(ids));
+            }
+            match running.next().await {
+                Some(children) => count_children(children?, &mut pending_folders),
+                None => break,
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Shortcut to `folder_size_with_concurrency` with
+    /// [`FOLDER_SIZE_CONCURRENCY`][concurrency].
+    ///
+    /// # See also
+    /// [`folder_size_with_concurrency`][with_concurrency]
+    ///
+    /// [concurrency]: #associatedconstant.FOLDER_SIZE_CONCURRENCY
+    /// [with_concurrency]: #method.folder_size_with_concurrency
+    pub async fn folder_size<'a>(&self, folder: impl Into<ItemLocation<'a>>) -> Result<FolderSize> {
+        self.folder_size_with_concurrency(folder, Self::FOLDER_SIZE_CONCURRENCY)
+            .await
+    }
+
+    async fn list_children_for_size<'a>(
+        &self,
+        folder: impl Into<ItemLocation<'a>>,
+    ) -> Result<Vec<DriveItem>> {
+        let folder = folder.into();
+        self.retry_transient(|| async move {
+            let option = CollectionOption::new().select(&FOLDER_SIZE_FIELDS);
+            self.list_children_with_option(folder, option)
+                .await?
+                .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))?
+                .fetch_all(self)
+                .await
+        })
+        .await
+    }
+
+    /// List children of all `folders`, in `$batch` requests if there are more than one.
+    async fn list_children_batch_for_size(&self, folders: Vec<ItemId>) -> Result<Vec<DriveItem>> {
+        if let [folder] = &folders[..] {
+            return self.list_children_for_size(folder).await;
+        }
+
+        let mut children = vec![];
+        let mut pending = folders.iter().collect::<Vec<_>>();
+        let mut retry = 0;
+        while !pending.is_empty() {
+            let requests = pending
+                .iter()
+                .enumerate()
+                .map(|(idx, folder)| self.children_for_size_batch_request(idx, folder))
+                .collect();
+            let responses = match self.send_batch(requests, RequestClass::Metadata).await {
+                Ok(responses) => responses,
+                Err(err) if is_batch_unsupported(&err) => {
+                    for folder in pending {
+                        children.extend(self.list_children_for_size(folder).await?);
+                    }
+                    break;
+                }
+                // Listing is idempotent, so it is safe to resend the whole batch.
+                Err(err) if retry < TRANSIENT_MAX_RETRY && err.is_transient() => {
+                    retry += 1;
+                    let delay = err.retry_after().unwrap_or(TRANSIENT_DEFAULT_RETRY_AFTER);
+                    self.sleeper.sleep(delay).await;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let mut throttled = vec![];
+            let mut retry_after = None;
+            for (folder, resp) in pending.into_iter().zip(responses) {
+                match self.fetch_children_for_size(resp).await {
+                    Ok(items) => children.extend(items),
+                    Err(err) if retry < TRANSIENT_MAX_RETRY && err.is_transient() => {
+                        retry_after = retry_after.max(err.retry_after());
+                        throttled.push(folder);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            if !throttled.is_empty() {
+                retry += 1;
+                let delay = retry_after.unwrap_or(TRANSIENT_DEFAULT_RETRY_AFTER);
+                self.sleeper.sleep(delay).await;
+            }
+            pending = throttled;
+        }
+        Ok(children)
+    }
+
+    fn children_for_size_batch_request(&self, idx: usize, folder: &ItemId) -> BatchRequestItem {
+        let mut url = api_url![&self.drive, &ItemLocation::from_id(folder), "children"];
+        let select = FOLDER_SIZE_FIELDS
+            .iter()
+            .map(|field| field.__raw_name())
+            .collect::<Vec<_>>()
+            .join(",");
+        url.query_pairs_mut().append_pair("$select", &select);
+        BatchRequestItem {
+            id: idx.to_string(),
+            method: "GET",
+            url: batch_url(&url),
+            headers: None,
+            body: None,
+        }
+    }
+
+    /// Collect children from the first page in a batch response, and fetch the rest pages.
+    async fn fetch_children_for_size(&self, resp: Result<Response>) -> Result<Vec<DriveItem>> {
+        let resp: DriveItemCollectionResponse = resp?.parse(self.strict_accepted).await?;
+        let mut fetcher = DriveItemFetcher::new(resp);
+        let mut children = fetcher.fetch_next_page(self).await?.unwrap_or_default();
+        if let Some(next_url) = fetcher.next_url().map(str::to_owned) {
+            let (rest, _) = self
+                .retry_transient(|| DriveItemFetcher::resume_from(next_url.clone()).fetch_all(self))
+                .await?;
+            children.extend(rest);
+        }
+        Ok(children)
+    }
+
+    async fn retry_transient<T, Fut>(&self, mut f: impl FnMut() -> Fut) -> Result<T>
+    where
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            match f().await {
+                Err(err) if retry < TRANSIENT_MAX_RETRY && err.is_transient() => {
+                    retry += 1;
+                    let delay = err.retry_after().unwrap_or(TRANSIENT_DEFAULT_RETRY_AFTER);
+                    self.sleeper.sleep(delay).await;
+                }
+                ret => return ret,
+            }
+        }
+    }
+}
+
+/// The total size and item count beneath a folder.
+///
+/// # See also
+/// [`OneDrive::folder_size`][folder_size]
+///
+/// [folder_size]: ./struct.OneDrive.html#method.folder_size
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FolderSize {
+    /// The total bytes of all files beneath the folder.
+    pub total_bytes: u64,
+    /// The number of files (non-folder items) beneath the folder.
+    pub file_count: u64,
+    /// The number of folders beneath the folder, not including itself.
+    pub folder_count: u64,
+}
+
+/// An item created (or moved) by APIs supporting [`ConflictBehavior::Rename`][conflict_rename].
+///
+/// It is returned by [`OneDrive::create_folder_with_option`][create_folder],
+/// [`OneDrive::upload_small_with_option`][upload_small],
+/// [`OneDrive::move_with_option`][move_] and
+/// `CopyProgressMonitor::fetch_created_item` (with feature `beta`).
+///
+/// [conflict_rename]: ./enum.ConflictBehavior.html#variant.Rename
+/// [create_folder]: ./struct.OneDrive.html#method.create_folder_with_option
+/// [upload_small]: ./struct.OneDrive.html#method.upload_small_with_option
+/// [move_]: ./struct.OneDrive.html#method.move_with_option
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct CreatedItem {
+    /// The created item.
+    pub item: DriveItem,
+    /// Whether the server chose a name different from the requested one,
+    /// due to a name conflict.
+    ///
+    /// The final name is in [`DriveItem::name`][name] of [`item`][item].
+    ///
+    /// [name]: ./resource/struct.DriveItem.html#structfield.name
+    /// [item]: #structfield.item
+    pub was_renamed: bool,
+}
+
+impl CreatedItem {
+    fn new(item: DriveItem, requested_name: Option<&str>) -> Self {
+        let was_renamed = match (requested_name, &item.name) {
+            (Some(requested), Some(name)) => requested != name,
+            _ => false,
+        };
+        Self { item, was_renamed }
+    }
+}
+
+/// Capabilities of the current token on a drive.
+///
+/// # See also
+/// [`OneDrive::probe_capabilities`][probe]
+///
+/// [probe]: ./struct.OneDrive.html#method.probe_capabilities
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Whether items can be read.
+    pub read: bool,
+    /// Whether items can be created, updated or deleted.
+    pub write: bool,
+    /// Whether items can be shared, that is, permissions can be listed and created.
+    pub share: bool,
+    /// Whether the current user owns the drive or its root folder.
+    ///
+    /// It is `false` if the current user is unknown, eg. for app-only tokens.
+    pub admin: bool,
+}
+
+/// An overview of a drive.
+///
+/// # See also
+/// [`OneDrive::get_drive_summary`][get_summary]
+///
+/// [get_summary]: ./struct.OneDrive.html#method.get_drive_summary
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct DriveSummary {
+    /// The id of the drive.
+    pub drive_id: Option<DriveId>,
+    /// The type of the drive, like `personal`, `business` or `documentLibrary`.
+    pub drive_type: Option<String>,
+    /// The storage quota of the drive.
+    pub quota: Option<Quota>,
+    /// The number of direct children of the root folder.
+    pub root_child_count: Option<u64>,
+    /// The total size of the root folder in bytes.
+    pub root_size: Option<u64>,
 }
 
 /// The monitor for checking the progress of a asynchronous `copy` operation.
 ///
+/// It holds no client or token, and can be cloned or serialized to be continued
+/// later by any `OneDrive` instance, eg. in another process.
+///
 /// # Notes
 /// This struct is always present. But since retrieving copy progress requires beta API,
 /// it is useless due to the lack of method `fetch_progress` if feature `beta` is not enabled.
@@ -841,9 +2961,12 @@ impl OneDrive {
 /// [Microsoft docs](https://docs.microsoft.com/en-us/graph/long-running-actions-overview)
 ///
 /// [copy]: ./struct.OneDrive.html#method.copy
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CopyProgressMonitor {
     monitor_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dest_name: Option<String>,
 }
 
 /// The progress of a asynchronous `copy` operation. (Beta)
@@ -851,17 +2974,91 @@ pub struct CopyProgressMonitor {
 /// # See also
 /// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/asyncjobstatus?view=graph-rest-beta)
 #[cfg(feature = "beta")]
-#[allow(missing_docs)]
 #[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(from = "RawCopyProgress")]
+#[non_exhaustive]
 pub struct CopyProgress {
+    /// The percentage complete, from 0 to 100.
     pub percentage_complete: f64,
-    pub status: CopyStatus,
+    /// The status of the operation.
+    pub status: AsyncOperationStatus,
+    /// A human-readable description of the current status, if provided.
+    pub status_description: Option<String>,
+    /// The id of the newly created item, only available when the copy is completed.
+    pub resource_id: Option<ItemId>,
+}
+
+#[cfg(feature = "beta")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCopyProgress {
+    #[serde(default)]
+    percentage_complete: f64,
+    status: String,
+    #[serde(default)]
+    status_description: Option<String>,
     #[serde(default)]
-    _private: (),
+    resource_id: Option<ItemId>,
+    #[serde(default)]
+    error_code: Option<String>,
+    #[serde(default)]
+    error: Option<ErrorResponse>,
+}
+
+#[cfg(feature = "beta")]
+impl From<RawCopyProgress> for CopyProgress {
+    fn from(raw: RawCopyProgress) -> Self {
+        use AsyncOperationStatus::*;
+
+        let RawCopyProgress {
+            percentage_complete,
+            status,
+            status_description,
+            resource_id,
+            error_code,
+            error,
+        } = raw;
+        let status = match &*status {
+            "notStarted" => NotStarted,
+            "inProgress" | "running" => InProgress,
+            "completed" | "succeeded" => Completed,
+            "failed" => Failed(error.or_else(|| {
+                error_code.map(|code| ErrorResponse {
+                    code,
+                    message: String::new(),
+                    inner_error: None,
+                })
+            })),
+            "waiting" => Waiting,
+            "cancelled" => Cancelled,
+            "updating" => Updating,
+            "deletePending" => DeletePending,
+            "deleteFailed" => DeleteFailed,
+            _ => Unknown(status),
+        };
+        Self {
+            percentage_complete,
+            status,
+            status_description,
+            resource_id,
+        }
+    }
+}
+
+#[cfg(feature = "beta")]
+impl CopyProgress {
+    /// Check if the `copy` operation is finished, either completed, failed or cancelled.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            AsyncOperationStatus::Completed
+                | AsyncOperationStatus::Failed(_)
+                | AsyncOperationStatus::Cancelled
+        )
+    }
 }
 
-/// The status of a `copy` operation. (Beta)
+/// The status of an asynchronous operation, like `copy`. (Beta)
 ///
 /// # See also
 /// [`CopyProgress`][copy_progress]
@@ -870,19 +3067,29 @@ pub struct CopyProgress {
 ///
 /// [copy_progress]: ./struct.CopyProgress.html
 #[cfg(feature = "beta")]
-#[allow(missing_docs)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
-pub enum CopyStatus {
+pub enum AsyncOperationStatus {
+    /// The operation is not started yet.
     NotStarted,
+    /// The operation is in progress.
     InProgress,
+    /// The operation is completed successfully.
     Completed,
+    /// The operation failed, with the error if provided.
+    Failed(Option<ErrorResponse>),
+    /// The operation is waiting for other operations.
+    Waiting,
+    /// The operation is cancelled.
+    Cancelled,
+    /// The operation is updating.
     Updating,
-    Failed,
+    /// The item is pending deletion.
     DeletePending,
+    /// The deletion failed.
     DeleteFailed,
-    Waiting,
+    /// Status not known by this crate, with its raw value.
+    Unknown(String),
 }
 
 impl CopyProgressMonitor {
@@ -892,7 +3099,10 @@ impl CopyProgressMonitor {
     ///
     /// [monitor_url]: #method.monitor_url
     pub fn from_monitor_url(monitor_url: String) -> Self {
-        Self { monitor_url }
+        Self {
+            monitor_url,
+            dest_name: None,
+        }
     }
 
     /// Get the monitor url.
@@ -909,42 +3119,284 @@ impl CopyProgressMonitor {
     #[cfg(feature = "beta")]
     pub async fn fetch_progress(&self, onedrive: &OneDrive) -> Result<CopyProgress> {
         // No bearer auth.
-        onedrive
-            .client
-            .get(&self.monitor_url)
-            .send()
-            .await?
-            .parse()
-            .await
+        let resp = onedrive
+            .request_unauthorized(Method::GET, &self.monitor_url, RequestClass::Monitor)
+            .send_logged()
+            .await?;
+        // The monitor responds HTTP 202 ACCEPTED with the progress when it is in progress.
+        Ok(handle_error_response(resp).await?.json().await?)
     }
-}
-
-#[derive(Debug, Deserialize)]
-struct DriveItemCollectionResponse {
-    value: Option<Vec<DriveItem>>,
-    #[serde(rename = "@odata.nextLink")]
-    next_url: Option<String>,
-    #[serde(rename = "@odata.deltaLink")]
-    delta_url: Option<String>,
-}
-
-#[derive(Debug)]
-struct DriveItemFetcher {
-    last_response: DriveItemCollectionResponse,
-}
 
-impl DriveItemFetcher {
-    fn new(first_response: DriveItemCollectionResponse) -> Self {
-        Self {
+    /// Poll the `copy` progress every `interval` until it is finished. (Beta)
+    ///
+    /// `on_progress` is called with every progress fetched, including the final one,
+    /// which is useful to show live progress in UIs.
+    ///
+    /// # Response
+    /// The final progress, either completed or failed.
+    /// See [`CopyProgress::is_finished`][is_finished].
+    ///
+    /// # Errors
+    /// Any error occurs when fetching will stop the polling.
+    ///
+    /// [is_finished]: ./struct.CopyProgress.html#method.is_finished
+    #[cfg(feature = "beta")]
+    pub async fn wait_with_progress(
+        &self,
+        onedrive: &OneDrive,
+        interval: Duration,
+        mut on_progress: impl FnMut(&CopyProgress),
+    ) -> Result<CopyProgress> {
+        loop {
+            let progress = self.fetch_progress(onedrive).await?;
+            on_progress(&progress);
+            if progress.is_finished() {
+                return Ok(progress);
+            }
+            onedrive.sleeper.sleep(interval).await;
+        }
+    }
+
+    /// Fetch the item created by a completed `copy` operation. (Beta)
+    ///
+    /// Return `None` if `progress` is not completed or has no resource id.
+    ///
+    /// Since the server may copy to another name, eg. when copying into the current
+    /// parent folder, [`CreatedItem::was_renamed`][was_renamed] reports whether the final
+    /// name differs from the requested one. It is always `false` if the monitor is made by
+    /// [`from_monitor_url`][from_monitor_url], which does not know the requested name.
+    ///
+    /// [was_renamed]: ./struct.CreatedItem.html#structfield.was_renamed
+    /// [from_monitor_url]: #method.from_monitor_url
+    #[cfg(feature = "beta")]
+    pub async fn fetch_created_item(
+        &self,
+        onedrive: &OneDrive,
+        progress: &CopyProgress,
+    ) -> Result<Option<CreatedItem>> {
+        let id = match (&progress.status, &progress.resource_id) {
+            (AsyncOperationStatus::Completed, Some(id)) => id,
+            _ => return Ok(None),
+        };
+        let item = onedrive.get_item(id).await?;
+        Ok(Some(CreatedItem::new(item, self.dest_name.as_deref())))
+    }
+}
+
+/// Merge items listed in pages into children of folder `folder_id`, for
+/// `OneDrive::list_children_consistent`.
+///
+/// Later occurrences of an item replace earlier ones in place. Items deleted or not
+/// directly under the folder are removed.
+fn merge_children_snapshot(
+    items: impl IntoIterator<Item = DriveItem>,
+    folder_id: &ItemId,
+) -> Vec<DriveItem> {
+    let mut slots: Vec<Option<DriveItem>> = Vec::new();
+    let mut indices = std::collections::HashMap::new();
+    for item in items {
+        let id = match &item.id {
+            Some(id) if id != folder_id => id.clone(),
+            _ => continue,
+        };
+        let in_folder = item.deleted.is_none()
+            && item
+                .parent_reference
+                .as_ref()
+                .and_then(|parent| parent.id.as_ref())
+                .is_none_or(|parent_id| parent_id == folder_id);
+        let item = if in_folder { Some(item) } else { None };
+        match indices.get(&id) {
+            Some(&idx) => slots[idx] = item,
+            None => {
+                indices.insert(id, slots.len());
+                slots.push(item);
+            }
+        }
+    }
+    slots.into_iter().flatten().collect()
+}
+
+/// Pack files with sizes `sizes` into batches for `OneDrive::upload_many`.
+///
+/// Return indices of files in each batch, and indices of files to be uploaded individually.
+fn pack_upload_batches(sizes: impl IntoIterator<Item = usize>) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let (mut batches, mut singles) = (Vec::new(), Vec::new());
+    let (mut cur, mut cur_bytes) = (Vec::new(), 0);
+    for (idx, size) in sizes.into_iter().enumerate() {
+        // Contents are base64-encoded in batch requests.
+        let encoded = size.div_ceil(3) * 4;
+        if encoded > OneDrive::UPLOAD_MANY_BATCH_MAX_BYTES {
+            singles.push(idx);
+            continue;
+        }
+        if cur.len() == OneDrive::UPLOAD_MANY_BATCH_SIZE
+            || cur_bytes + encoded > OneDrive::UPLOAD_MANY_BATCH_MAX_BYTES
+        {
+            batches.push(std::mem::take(&mut cur));
+            cur_bytes = 0;
+        }
+        cur.push(idx);
+        cur_bytes += encoded;
+    }
+    match cur.len() {
+        0 => {}
+        // No need to batch a single file.
+        1 => singles.extend(cur),
+        _ => batches.push(cur),
+    }
+    (batches, singles)
+}
+
+/// The maximum number of retries on transient errors.
+const TRANSIENT_MAX_RETRY: usize = 5;
+
+/// The delay before retrying on transient errors without `Retry-After`.
+const TRANSIENT_DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Fields needed to calculate folder size.
+const FOLDER_SIZE_FIELDS: [DriveItemField; 4] = [
+    DriveItemField::id,
+    DriveItemField::size,
+    DriveItemField::folder,
+    DriveItemField::remote_item,
+];
+
+/// A sub-request of a `$batch` request.
+#[derive(Debug, Serialize)]
+struct BatchRequestItem {
+    id: String,
+    method: &'static str,
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    headers: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+}
+
+/// The response of a sub-request of `$batch`.
+#[derive(Debug, Deserialize)]
+struct BatchResponseItem {
+    id: String,
+    status: u16,
+    #[serde(default)]
+    headers: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    body: serde_json::Value,
+}
+
+impl BatchResponseItem {
+    fn into_response(self) -> Result<Response> {
+        let body = match self.body {
+            serde_json::Value::Null => Vec::new(),
+            body => serde_json::to_vec(&body).unwrap(),
+        };
+        let mut resp = http::Response::new(body);
+        *resp.status_mut() = StatusCode::from_u16(self.status)
+            .map_err(|_| Error::unexpected_response("Invalid status in batch response"))?;
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                header::HeaderName::from_bytes(name.as_bytes()),
+                header::HeaderValue::from_str(value),
+            ) {
+                resp.headers_mut().insert(name, value);
+            }
+        }
+        Ok(resp.into())
+    }
+}
+
+/// Get the url of a sub-request of `$batch`, which is relative to the API version.
+fn batch_url(url: &Url) -> String {
+    url[url::Position::BeforePath..]
+        .trim_start_matches("/v1.0")
+        .to_owned()
+}
+
+/// Treat HTTP 404 NOT_FOUND of a deletion as success, returning whether the item is deleted.
+fn deleted_if_exists(ret: Result<()>) -> Result<bool> {
+    match ret {
+        Ok(()) => Ok(true),
+        Err(err) if err.status_code() == Some(StatusCode::NOT_FOUND) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether a `$batch` request is rejected as a whole without processing any of its
+/// sub-requests, eg. batching is not available or the request is too large.
+fn is_batch_unsupported(err: &Error) -> bool {
+    matches!(
+        err.status_code(),
+        Some(
+            StatusCode::NOT_FOUND
+                | StatusCode::METHOD_NOT_ALLOWED
+                | StatusCode::PAYLOAD_TOO_LARGE
+                | StatusCode::NOT_IMPLEMENTED
+        )
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionResponse<T> {
+    value: Option<Vec<T>>,
+    #[serde(rename = "@odata.nextLink")]
+    next_url: Option<String>,
+    #[serde(rename = "@odata.deltaLink")]
+    delta_url: Option<String>,
+    #[serde(rename = "@odata.context")]
+    odata_context: Option<ODataContext>,
+}
+
+type DriveItemCollectionResponse = CollectionResponse<DriveItem>;
+
+/// A page of items fetched by [`ListChildrenFetcher`][list_fetcher] or
+/// [`TrackChangeFetcher`][track_fetcher], with its metadata.
+///
+/// It is useful to checkpoint after each page or to log the progress of crawling.
+///
+/// [list_fetcher]: ./struct.ListChildrenFetcher.html
+/// [track_fetcher]: ./struct.TrackChangeFetcher.html
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Page {
+    /// Items in this page.
+    pub items: Vec<DriveItem>,
+    /// The url to the next page (`@odata.nextLink`), or `None` if this is the last page.
+    pub next_link: Option<String>,
+    /// The delta url (`@odata.deltaLink`), only available in the last page of tracking changes.
+    pub delta_link: Option<String>,
+    /// The `@odata.context` of the response.
+    pub odata_context: Option<ODataContext>,
+}
+
+impl Page {
+    /// Get the count of items in this page.
+    pub fn items_in_page(&self) -> usize {
+        self.items.len()
+    }
+}
+
+/// The common page fetcher of collections, with items of type `T`.
+#[derive(Debug)]
+struct PageFetcher<T> {
+    last_response: CollectionResponse<T>,
+}
+
+type DriveItemFetcher = PageFetcher<DriveItem>;
+
+impl<T: DeserializeOwned> PageFetcher<T> {
+    fn new(first_response: CollectionResponse<T>) -> Self {
+        Self {
             last_response: first_response,
         }
     }
 
     fn resume_from(next_url: String) -> Self {
-        Self::new(DriveItemCollectionResponse {
+        Self::new(CollectionResponse {
             value: None,
             next_url: Some(next_url),
             delta_url: None,
+            odata_context: None,
         })
     }
 
@@ -952,7 +3404,7 @@ impl DriveItemFetcher {
         // Return `None` for the first page, or it will
         // lost items of the first page when resumed.
         match &self.last_response {
-            DriveItemCollectionResponse {
+            CollectionResponse {
                 value: None,
                 next_url: Some(next_url),
                 ..
@@ -965,34 +3417,284 @@ impl DriveItemFetcher {
         self.last_response.delta_url.as_deref()
     }
 
-    async fn fetch_next_page(&mut self, onedrive: &OneDrive) -> Result<Option<Vec<DriveItem>>> {
-        if let Some(items) = self.last_response.value.take() {
-            return Ok(Some(items));
+    async fn fetch_next_response(
+        &mut self,
+        onedrive: &OneDrive,
+    ) -> Result<Option<CollectionResponse<T>>> {
+        if self.last_response.value.is_none() {
+            let url = match self.last_response.next_url.as_ref() {
+                None => return Ok(None),
+                Some(url) => url,
+            };
+            let req = onedrive.request(Method::GET, url, RequestClass::Metadata);
+            self.last_response = onedrive
+                .send_limited(req)
+                .await?
+                .parse(onedrive.strict_accepted)
+                .await?;
         }
-        let url = match self.last_response.next_url.as_ref() {
-            None => return Ok(None),
-            Some(url) => url,
-        };
-        self.last_response = onedrive
-            .client
-            .get(url)
-            .bearer_auth(&onedrive.token)
-            .send()
+        let resp = &mut self.last_response;
+        if let Some(limit) = onedrive.response_limits.max_page_items {
+            if resp.value.as_ref().is_some_and(|items| items.len() > limit) {
+                return Err(Error::too_many_items(limit));
+            }
+        }
+        Ok(Some(CollectionResponse {
+            value: resp.value.take(),
+            next_url: resp.next_url.clone(),
+            delta_url: resp.delta_url.clone(),
+            odata_context: resp.odata_context.clone(),
+        }))
+    }
+
+    async fn fetch_next_page(&mut self, onedrive: &OneDrive) -> Result<Option<Vec<T>>> {
+        Ok(self
+            .fetch_next_response(onedrive)
             .await?
-            .parse()
-            .await?;
-        Ok(Some(self.last_response.value.take().unwrap_or_default()))
+            .map(|resp| resp.value.unwrap_or_default()))
     }
 
-    async fn fetch_all(mut self, onedrive: &OneDrive) -> Result<(Vec<DriveItem>, Option<String>)> {
+    async fn fetch_all(mut self, onedrive: &OneDrive) -> Result<(Vec<T>, Option<String>)> {
         let mut buf = vec![];
         while let Some(items) = self.fetch_next_page(onedrive).await? {
             buf.extend(items);
+            if let Some(limit) = onedrive.response_limits.max_total_items {
+                if buf.len() > limit {
+                    return Err(Error::too_many_items(limit));
+                }
+            }
         }
         Ok((buf, self.delta_url().map(|s| s.to_owned())))
     }
 }
 
+impl DriveItemFetcher {
+    async fn fetch_next_page_with_meta(&mut self, onedrive: &OneDrive) -> Result<Option<Page>> {
+        Ok(self.fetch_next_response(onedrive).await?.map(|resp| Page {
+            items: resp.value.unwrap_or_default(),
+            next_link: resp.next_url,
+            delta_link: resp.delta_url,
+            odata_context: resp.odata_context,
+        }))
+    }
+}
+
+/// A builder of listing children query.
+///
+/// # See also
+/// [`OneDrive::children`][children]
+///
+/// [children]: ./struct.OneDrive.html#method.children
+#[derive(Debug)]
+#[must_use = "Query does nothing until fetched"]
+pub struct ChildrenQuery<'a> {
+    onedrive: &'a OneDrive,
+    item: ItemLocation<'a>,
+    option: CollectionOption<DriveItemField>,
+}
+
+impl<'a> ChildrenQuery<'a> {
+    /// Select only some fields of children.
+    ///
+    /// # See also
+    /// [`CollectionOption::select`][select]
+    ///
+    /// [select]: ./option/struct.CollectionOption.html#method.select
+    pub fn select(mut self, fields: &[DriveItemField]) -> Self {
+        self.option = self.option.select(fields);
+        self
+    }
+
+    /// Select well-known fields of children needed to sync them.
+    ///
+    /// # See also
+    /// [`CollectionOption::select_core`][select_core]
+    ///
+    /// [select_core]: ./option/struct.CollectionOption.html#method.select_core
+    pub fn select_core(mut self) -> Self {
+        self.option = self.option.select_core();
+        self
+    }
+
+    /// Expand a field of children.
+    ///
+    /// # See also
+    /// [`CollectionOption::expand`][expand]
+    ///
+    /// [expand]: ./option/struct.CollectionOption.html#method.expand
+    pub fn expand(mut self, field: DriveItemField, select_children: Option<&[&str]>) -> Self {
+        self.option = self.option.expand(field, select_children);
+        self
+    }
+
+    /// Filter children by an OData expression.
+    ///
+    /// # See also
+    /// [`CollectionOption::filter`][filter]
+    ///
+    /// [filter]: ./option/struct.CollectionOption.html#method.filter
+    pub fn filter(mut self, expr: &str) -> Self {
+        self.option = self.option.filter(expr);
+        self
+    }
+
+    /// Specify the sort order of children.
+    ///
+    /// # See also
+    /// [`CollectionOption::order_by`][order_by]
+    ///
+    /// [order_by]: ./option/struct.CollectionOption.html#method.order_by
+    pub fn order_by(mut self, field: DriveItemField, order: Order) -> Self {
+        self.option = self.option.order_by(field, order);
+        self
+    }
+
+    /// Specify the number of children per page.
+    ///
+    /// # See also
+    /// [`CollectionOption::page_size`][page_size]
+    ///
+    /// [page_size]: ./option/struct.CollectionOption.html#method.page_size
+    pub fn page_size(mut self, size: usize) -> Self {
+        self.option = self.option.page_size(size);
+        self
+    }
+
+    /// Add a raw query parameter to the request.
+    ///
+    /// # See also
+    /// [`CollectionOption::raw_param`][raw_param]
+    ///
+    /// [raw_param]: ./option/struct.CollectionOption.html#method.raw_param
+    pub fn raw_param(mut self, key: &str, value: &str) -> Self {
+        self.option = self.option.raw_param(key, value);
+        self
+    }
+
+    /// Send the first request and get the fetcher for all pages.
+    ///
+    /// # See also
+    /// [`OneDrive::list_children_with_option`][with_opt]
+    ///
+    /// [with_opt]: ./struct.OneDrive.html#method.list_children_with_option
+    pub async fn fetcher(self) -> Result<ListChildrenFetcher> {
+        self.onedrive
+            .list_children_with_option(self.item, self.option)
+            .await?
+            .ok_or_else(|| Error::unexpected_response("Unexpected empty response"))
+    }
+
+    /// Fetch and collect all children.
+    pub async fn fetch(self) -> Result<Vec<DriveItem>> {
+        let onedrive = self.onedrive;
+        self.fetcher().await?.fetch_all(onedrive).await
+    }
+}
+
+/// A change event of a watched `DriveItem`.
+///
+/// # See also
+/// [`OneDrive::watch_item`][watch_item]
+///
+/// [watch_item]: ./struct.OneDrive.html#method.watch_item
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ItemEvent {
+    /// The item when watching starts, or when it appears after being deleted.
+    Initial(DriveItem),
+    /// The item is modified. It contains the latest metadata.
+    Modified(DriveItem),
+    /// The item is deleted, or becomes inaccessible (HTTP 404 NOT_FOUND).
+    Deleted,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WatchState {
+    Initial,
+    Watching,
+    Deleted,
+}
+
+/// The poller of changes of a single `DriveItem`.
+///
+/// # See also
+/// [`OneDrive::watch_item`][watch_item]
+///
+/// [watch_item]: ./struct.OneDrive.html#method.watch_item
+#[derive(Debug)]
+pub struct ItemWatcher<'a> {
+    onedrive: &'a OneDrive,
+    item: ItemLocation<'a>,
+    interval: Duration,
+    last_tag: Option<Tag>,
+    state: WatchState,
+}
+
+impl<'a> ItemWatcher<'a> {
+    /// Get the `eTag` of the item in the last event, if any.
+    pub fn last_tag(&self) -> Option<&Tag> {
+        self.last_tag.as_ref()
+    }
+
+    /// Wait for the next change event.
+    ///
+    /// The first call returns [`ItemEvent::Initial`][initial] (or [`ItemEvent::Deleted`][deleted]
+    /// if the item does not exist) immediately. Later calls poll every `interval` until
+    /// a change is detected.
+    ///
+    /// # Errors
+    /// Any error except HTTP 404 NOT_FOUND is returned immediately. The watcher can
+    /// still be used after errors.
+    ///
+    /// [initial]: ./enum.ItemEvent.html#variant.Initial
+    /// [deleted]: ./enum.ItemEvent.html#variant.Deleted
+    pub async fn next_event(&mut self) -> Result<ItemEvent> {
+        loop {
+            if self.state != WatchState::Initial {
+                self.onedrive.sleeper.sleep(self.interval).await;
+            }
+            let mut option = ObjectOption::new();
+            if let Some(tag) = &self.last_tag {
+                option = option.if_none_match(tag);
+            }
+            let event = match self.onedrive.get_item_with_option(self.item, option).await {
+                Ok(None) => continue,
+                Ok(Some(item)) => {
+                    self.last_tag = item.e_tag.clone();
+                    match self.state {
+                        WatchState::Watching => ItemEvent::Modified(item),
+                        _ => ItemEvent::Initial(item),
+                    }
+                }
+                Err(err) if err.status_code() == Some(StatusCode::NOT_FOUND) => {
+                    if self.state == WatchState::Deleted {
+                        continue;
+                    }
+                    self.last_tag = None;
+                    self.state = WatchState::Deleted;
+                    return Ok(ItemEvent::Deleted);
+                }
+                Err(err) => return Err(err),
+            };
+            self.state = WatchState::Watching;
+            return Ok(event);
+        }
+    }
+
+    /// Convert into an endless stream of change events.
+    ///
+    /// # See also
+    /// [`next_event`][next_event]
+    ///
+    /// [next_event]: #method.next_event
+    pub fn into_stream(self) -> impl futures_util::stream::Stream<Item = Result<ItemEvent>> + 'a {
+        futures_util::stream::unfold(self, |mut watcher| async move {
+            let event = watcher.next_event().await;
+            Some((event, watcher))
+        })
+    }
+}
+
 /// The page fetcher for listing children
 ///
 /// # See also
@@ -1026,7 +3728,7 @@ impl ListChildrenFetcher {
     /// Used for resuming the fetching progress.
     ///
     /// # Error
-    /// Will success only if there are more pages and the first page is already readed.
+    /// Will success only if there are more pages and the first page is already read.
     ///
     /// # Note
     /// The first page data from [`OneDrive::list_children_with_option`][list_children_with_opt]
@@ -1042,6 +3744,16 @@ impl ListChildrenFetcher {
         self.fetcher.fetch_next_page(onedrive).await
     }
 
+    /// Fetch the next page with its metadata, or `None` if reaches the end.
+    ///
+    /// # See also
+    /// [`Page`][page]
+    ///
+    /// [page]: ./struct.Page.html
+    pub async fn fetch_next_page_with_meta(&mut self, onedrive: &OneDrive) -> Result<Option<Page>> {
+        self.fetcher.fetch_next_page_with_meta(onedrive).await
+    }
+
     /// Fetch all rest pages and collect all items.
     ///
     /// # Errors
@@ -1056,6 +3768,62 @@ impl ListChildrenFetcher {
     }
 }
 
+/// The page fetcher for listing activities. (Beta)
+///
+/// # See also
+/// [`OneDrive::list_drive_activities`][list_activities]
+///
+/// [list_activities]: ./struct.OneDrive.html#method.list_drive_activities
+#[cfg(feature = "beta")]
+#[derive(Debug)]
+pub struct ActivityFetcher {
+    fetcher: PageFetcher<ItemActivity>,
+}
+
+#[cfg(feature = "beta")]
+impl ActivityFetcher {
+    /// Resume a fetching process from url from
+    /// [`ActivityFetcher::next_url`][next_url].
+    ///
+    /// [next_url]: #method.next_url
+    pub fn resume_from(next_url: String) -> Self {
+        Self {
+            fetcher: PageFetcher::resume_from(next_url),
+        }
+    }
+
+    /// Try to get the url to the next page.
+    ///
+    /// Used for resuming the fetching progress.
+    ///
+    /// # Error
+    /// Will success only if there are more pages and the first page is already read.
+    pub fn next_url(&self) -> Option<&str> {
+        self.fetcher.next_url()
+    }
+
+    /// Fetch the next page, or `None` if reaches the end.
+    pub async fn fetch_next_page(
+        &mut self,
+        onedrive: &OneDrive,
+    ) -> Result<Option<Vec<ItemActivity>>> {
+        self.fetcher.fetch_next_page(onedrive).await
+    }
+
+    /// Fetch all rest pages and collect all activities.
+    ///
+    /// # Errors
+    ///
+    /// Any error occurs when fetching will lead to an failure, and
+    /// all progress will be lost.
+    pub async fn fetch_all(self, onedrive: &OneDrive) -> Result<Vec<ItemActivity>> {
+        self.fetcher
+            .fetch_all(onedrive)
+            .await
+            .map(|(activities, _)| activities)
+    }
+}
+
 /// The page fetcher for tracking operations with `Iterator` interface.
 ///
 /// # See also
@@ -1093,7 +3861,7 @@ impl TrackChangeFetcher {
     /// Used for resuming the fetching progress.
     ///
     /// # Error
-    /// Will success only if there are more pages and the first page is already readed.
+    /// Will success only if there are more pages and the first page is already read.
     ///
     /// # Note
     /// The first page data from
@@ -1123,11 +3891,29 @@ impl TrackChangeFetcher {
         self.fetcher.delta_url()
     }
 
+    /// Same as [`delta_url`][delta_url], but wrapped into a [`DeltaToken`][delta_token].
+    ///
+    /// [delta_url]: #method.delta_url
+    /// [delta_token]: ./struct.DeltaToken.html
+    pub fn delta_token(&self) -> Option<DeltaToken> {
+        self.delta_url().map(|url| DeltaToken::new(url.to_owned()))
+    }
+
     /// Fetch the next page, or `None` if reaches the end.
     pub async fn fetch_next_page(&mut self, onedrive: &OneDrive) -> Result<Option<Vec<DriveItem>>> {
         self.fetcher.fetch_next_page(onedrive).await
     }
 
+    /// Fetch the next page with its metadata, or `None` if reaches the end.
+    ///
+    /// # See also
+    /// [`Page`][page]
+    ///
+    /// [page]: ./struct.Page.html
+    pub async fn fetch_next_page_with_meta(&mut self, onedrive: &OneDrive) -> Result<Option<Page>> {
+        self.fetcher.fetch_next_page_with_meta(onedrive).await
+    }
+
     /// Fetch all rest pages, collect all items, and also return `delta_url`.
     ///
     /// # Errors
@@ -1144,21 +3930,30 @@ impl TrackChangeFetcher {
 }
 
 #[derive(Serialize)]
-struct ItemReference<'a> {
+struct ParentReference<'a> {
     path: &'a str,
 }
 
 /// An upload session for resumable file uploading process.
 ///
+/// It holds no client or token, and can be cloned or serialized to be continued
+/// later by any `reqwest::Client`, eg. in another process. The serialized form is
+/// compatible with the `uploadSession` resource returned by the API.
+///
 /// # See also
 /// [`OneDrive::new_upload_session`][get_session]
 ///
 /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/uploadsession?view=graph-rest-1.0)
 ///
 /// [get_session]: ./struct.OneDrive.html#method.new_upload_session
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct UploadSession {
     upload_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expiration_date_time: Option<TimestampString>,
+    #[serde(skip)]
+    timeout: Option<Duration>,
 }
 
 /// Metadata of an in-progress upload session
@@ -1172,6 +3967,8 @@ pub struct UploadSessionMeta {
     /// Get a collection of byte ranges that the server is missing for the file.
     ///
     /// Used for determine what to upload when resuming a session.
+    /// Ranges are sorted and overlapping or adjacent ones are merged.
+    #[serde(deserialize_with = "deserialize_expect_ranges")]
     pub next_expected_ranges: Vec<ExpectRange>,
     /// Get the date and time in UTC that the upload session will expire.
     ///
@@ -1190,39 +3987,124 @@ impl UploadSession {
     pub const MAX_PART_SIZE: usize = 60 << 20; // 60 MiB
 
     /// Construct back the upload session from upload URL.
+    ///
+    /// The expiration time is unknown until set by
+    /// [`with_expiration_date_time`][with_expiration].
+    ///
+    /// [with_expiration]: #method.with_expiration_date_time
     pub fn from_upload_url(upload_url: String) -> Self {
-        Self { upload_url }
+        Self {
+            upload_url,
+            expiration_date_time: None,
+            timeout: None,
+        }
     }
 
-    /// Query the metadata of the upload to find out which byte ranges
-    /// have been received previously.
-    ///
-    /// # See also
-    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-createuploadsession?view=graph-rest-1.0#resuming-an-in-progress-upload)
-    pub async fn get_meta(&self, client: &Client) -> Result<UploadSessionMeta> {
-        // No bearer auth.
-        Ok(client
-            .get(&self.upload_url)
-            .send()
-            .await?
-            .parse::<UploadSessionMeta>()
-            .await?)
+    /// Set the timeout of each [`upload_part`][upload_part] request, overriding the one
+    /// of the `reqwest::Client` passed in.
+    ///
+    /// Sessions created by [`OneDrive::new_upload_session`][new_session] use the transfer
+    /// timeout of [`OneDrive::timeouts`][timeouts]. It is not serialized, and is `None`
+    /// for sessions constructed back from upload urls or checkpoints.
+    ///
+    /// [upload_part]: #method.upload_part
+    /// [new_session]: ./struct.OneDrive.html#method.new_upload_session
+    /// [timeouts]: ./struct.OneDrive.html#method.timeouts
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
     }
 
-    /// The URL endpoint accepting PUT requests.
+    /// Get the timeout of each [`upload_part`][upload_part] request.
     ///
-    /// It is exactly what you passed in [`UploadSession::from_upload_url`].
+    /// [upload_part]: #method.upload_part
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Set the expiration time of the session, usually from
+    /// [`UploadSessionMeta::expiration_date_time`][meta_expiration].
     ///
-    /// [`UploadSession::from_upload_url`]: #method.new
-    pub fn upload_url(&self) -> &str {
-        &self.upload_url
+    /// [meta_expiration]: ./struct.UploadSessionMeta.html#structfield.expiration_date_time
+    pub fn with_expiration_date_time(mut self, expiration_date_time: TimestampString) -> Self {
+        self.expiration_date_time = Some(expiration_date_time);
+        self
     }
 
-    /// Cancel the upload session
+    /// Get the raw expiration time of the session, if known.
     ///
-    /// This cleans up the temporary file holding the data previously uploaded.
-    /// This should be used in scenarios where the upload is aborted, for example,
-    /// if the user cancels the transfer.
+    /// Note that it is only updated when the session is created or
+    /// [`with_expiration_date_time`][with_expiration] is called.
+    /// Every successful [`upload_part`][upload_part] may extend the expiration on the server side.
+    ///
+    /// [with_expiration]: #method.with_expiration_date_time
+    /// [upload_part]: #method.upload_part
+    pub fn expiration_date_time(&self) -> Option<&str> {
+        self.expiration_date_time.as_deref()
+    }
+
+    /// Get the expiration time of the session.
+    ///
+    /// Return `None` if it is unknown or fails to be parsed.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        parse_timestamp(self.expiration_date_time.as_deref()?)
+    }
+
+    /// Check if the session is already expired according to the system clock.
+    ///
+    /// Return `false` if the expiration time is unknown.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemTime::now())
+    }
+
+    /// Check if the session is already expired at time `now`, eg.
+    /// from [`OneDrive::clock`][clock].
+    ///
+    /// Return `false` if the expiration time is unknown.
+    ///
+    /// [clock]: ./struct.OneDrive.html#method.clock
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        self.expires_at().is_some_and(|time| time <= now)
+    }
+
+    fn map_expired(&self, err: Error) -> Error {
+        if err.status_code() == Some(StatusCode::NOT_FOUND) {
+            Error::session_expired(err, self.expiration_date_time.clone())
+        } else {
+            err
+        }
+    }
+
+    /// Query the metadata of the upload to find out which byte ranges
+    /// have been received previously.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-createuploadsession?view=graph-rest-1.0#resuming-an-in-progress-upload)
+    pub async fn get_meta(&self, client: &Client) -> Result<UploadSessionMeta> {
+        // No bearer auth. Not bound to a `OneDrive`, so HTTP 202 ACCEPTED is not strict.
+        client
+            .get(&self.upload_url)
+            .send_logged()
+            .await?
+            .parse::<UploadSessionMeta>(false)
+            .await
+            .map_err(|err| self.map_expired(err))
+    }
+
+    /// The URL endpoint accepting PUT requests.
+    ///
+    /// It is exactly what you passed in [`UploadSession::from_upload_url`].
+    ///
+    /// [`UploadSession::from_upload_url`]: #method.new
+    pub fn upload_url(&self) -> &str {
+        &self.upload_url
+    }
+
+    /// Cancel the upload session
+    ///
+    /// This cleans up the temporary file holding the data previously uploaded.
+    /// This should be used in scenarios where the upload is aborted, for example,
+    /// if the user cancels the transfer.
     ///
     /// Temporary files and their accompanying upload session are automatically
     /// cleaned up after the expirationDateTime has passed. Temporary files may
@@ -1231,13 +4113,14 @@ impl UploadSession {
     /// # See also
     /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-createuploadsession?view=graph-rest-1.0#cancel-the-upload-session)
     pub async fn delete(&self, client: &Client) -> Result<()> {
-        // No bearer auth.
+        // No bearer auth. Not bound to a `OneDrive`, so HTTP 202 ACCEPTED is not strict.
         client
             .delete(&self.upload_url)
-            .send()
+            .send_logged()
             .await?
-            .parse_no_content()
+            .parse_no_content(false)
             .await
+            .map_err(|err| self.map_expired(err))
     }
 
     /// Upload bytes to an upload session
@@ -1266,12 +4149,17 @@ impl UploadSession {
     /// during uploading, the last `upload_to_session` call will return `Err` with
     /// HTTP 409 CONFLICT.
     ///
+    /// If the session is expired, it will return `Err` with
+    /// [`Error::is_session_expired`][is_expired] being `true`.
+    ///
     /// # Panic
     /// Panic if `remote_range` is invalid, not match the length of `data`, or
     /// `data` is larger than 60 MiB (62,914,560 bytes).
     ///
     /// # See also
     /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-createuploadsession?view=graph-rest-1.0#upload-bytes-to-the-upload-session)
+    ///
+    /// [is_expired]: ./struct.Error.html#method.is_session_expired
     pub async fn upload_part(
         &self,
         data: impl Into<Bytes>,
@@ -1297,25 +4185,267 @@ impl UploadSession {
         );
 
         // No bearer auth.
-        client
-            .put(&self.upload_url)
-            .header(
-                header::CONTENT_RANGE,
-                format!(
-                    "bytes {}-{}/{}",
-                    remote_range.start,
-                    // Inclusive.
-                    // We checked `remote_range.start < remote_range.end`,
-                    // so this never overflows.
-                    remote_range.end - 1,
-                    file_size,
-                ),
-            )
-            .body(data)
-            .send()
-            .await?
-            .parse_optional()
-            .await
+        let mut req = client.put(&self.upload_url);
+        if let Some(timeout) = self.timeout {
+            req = req.timeout(timeout);
+        }
+        req.header(
+            header::CONTENT_RANGE,
+            format!(
+                "bytes {}-{}/{}",
+                remote_range.start,
+                // Inclusive.
+                // We checked `remote_range.start < remote_range.end`,
+                // so this never overflows.
+                remote_range.end - 1,
+                file_size,
+            ),
+        )
+        .body(data)
+        .send_logged()
+        .await?
+        // Uploading a non-last part responds HTTP 202 ACCEPTED without `Location`.
+        .parse_optional(false)
+        .await
+        .map_err(|err| self.map_expired(err))
+    }
+
+    /// Upload bytes to an upload session, recording checksums in `verifier`.
+    ///
+    /// This behaves the same as [`upload_part`][upload_part], and additionally hashes each
+    /// successfully uploaded chunk into `verifier`. When the last part is uploaded, the
+    /// QuickXorHash of the whole file is validated against the one of the created item,
+    /// if the server provides it. Per-chunk checksums are available in
+    /// [`UploadVerifier::chunks`][chunks].
+    ///
+    /// # Error
+    /// Besides errors of [`upload_part`][upload_part], it will return `Err` with
+    /// [`Error::is_checksum_mismatch`][mismatch] being `true` if the final checksum mismatches,
+    /// or with [`Error::is_invalid_argument`][invalid] being `true` without sending any
+    /// request if parts are not uploaded sequentially from the beginning of the file,
+    /// that is, `remote_range.start` differs from [`UploadVerifier::uploaded_len`][len].
+    ///
+    /// # Panic
+    /// Same as [`upload_part`][upload_part].
+    ///
+    /// [upload_part]: #method.upload_part
+    /// [chunks]: ./struct.UploadVerifier.html#method.chunks
+    /// [mismatch]: ./struct.Error.html#method.is_checksum_mismatch
+    /// [invalid]: ./struct.Error.html#method.is_invalid_argument
+    /// [len]: ./struct.UploadVerifier.html#method.uploaded_len
+    pub async fn upload_part_with_checksum(
+        &self,
+        data: impl Into<Bytes>,
+        remote_range: std::ops::Range<u64>,
+        file_size: u64,
+        client: &Client,
+        verifier: &mut UploadVerifier,
+    ) -> Result<Option<DriveItem>> {
+        if remote_range.start != verifier.uploaded_len() {
+            return Err(Error::invalid_argument(
+                "Parts must be uploaded sequentially from the beginning for checksum",
+            ));
+        }
+        let data = data.into();
+        let ret = self
+            .upload_part(data.clone(), remote_range.clone(), file_size, client)
+            .await?;
+        verifier.record(&data, remote_range);
+        if let Some(item) = &ret {
+            verifier.verify(item)?;
+        }
+        Ok(ret)
+    }
+}
+
+/// A serializable checkpoint of an upload through [`UploadSession`][session],
+/// used to resume the upload later, even after the process restarts.
+///
+/// Besides the session, it records the size of the file and the uploaded prefix,
+/// with an optional QuickXorHash of the prefix, to detect local changes when resuming.
+///
+/// # See also
+/// [`UploadCheckpoint::resume`][resume]
+///
+/// [session]: ./struct.UploadSession.html
+/// [resume]: #method.resume
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadCheckpoint {
+    upload_url: String,
+    expiration_date_time: Option<TimestampString>,
+    file_size: u64,
+    uploaded: u64,
+    quick_xor_hash: Option<String>,
+}
+
+/// The result of [`UploadCheckpoint::resume`][resume].
+///
+/// [resume]: ./struct.UploadCheckpoint.html#method.resume
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ResumedUpload {
+    /// The upload session to continue with.
+    pub session: UploadSession,
+    /// The latest metadata of the session.
+    pub meta: UploadSessionMeta,
+    /// The verifier with the already uploaded prefix recorded, to be used in
+    /// [`UploadSession::upload_part_with_checksum`][upload_part].
+    ///
+    /// [upload_part]: ./struct.UploadSession.html#method.upload_part_with_checksum
+    pub verifier: UploadVerifier,
+    /// The offset in the file to continue uploading from.
+    pub offset: u64,
+}
+
+impl UploadCheckpoint {
+    /// Create a checkpoint with nothing uploaded for a file of size `file_size`.
+    pub fn new(session: &UploadSession, file_size: u64) -> Self {
+        Self {
+            upload_url: session.upload_url.clone(),
+            expiration_date_time: session.expiration_date_time.clone(),
+            file_size,
+            uploaded: 0,
+            quick_xor_hash: None,
+        }
+    }
+
+    /// Record the progress in `verifier`, with the checksum of the uploaded prefix.
+    pub fn update(&mut self, verifier: &UploadVerifier) {
+        self.uploaded = verifier.uploaded_len();
+        self.quick_xor_hash = Some(verifier.quick_xor_hash());
+    }
+
+    /// Record the progress that the first `uploaded` bytes are uploaded, without checksum.
+    ///
+    /// Only the size of the file is validated when resuming.
+    pub fn update_uploaded(&mut self, uploaded: u64) {
+        self.uploaded = uploaded;
+        self.quick_xor_hash = None;
+    }
+
+    /// Get the size of the file.
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Get the length of the uploaded prefix.
+    pub fn uploaded(&self) -> u64 {
+        self.uploaded
+    }
+
+    /// Get the base64-encoded QuickXorHash of the uploaded prefix, if recorded.
+    pub fn quick_xor_hash(&self) -> Option<&str> {
+        self.quick_xor_hash.as_deref()
+    }
+
+    /// Construct back the upload session.
+    pub fn session(&self) -> UploadSession {
+        UploadSession {
+            upload_url: self.upload_url.clone(),
+            expiration_date_time: self.expiration_date_time.clone(),
+            timeout: None,
+        }
+    }
+
+    /// Validate the local file and prepare to continue the upload.
+    ///
+    /// `local` should read the content of the local file from the beginning, eg. an opened
+    /// `std::fs::File`, and `local_file_size` is its current size. The uploaded prefix is read
+    /// and rehashed through `spawn_blocking` if it is called inside a tokio runtime, so
+    /// the async executor is not blocked. Otherwise, it is read synchronously.
+    ///
+    /// # Response
+    /// Return `None` if the local file is changed since the checkpoint, that is, its size or
+    /// the QuickXorHash of the uploaded prefix mismatches. The upload should be restarted with
+    /// a new session in this case.
+    ///
+    /// Otherwise, the upload can be continued from [`ResumedUpload::offset`][offset], which is
+    /// the start of the first range expected by the server.
+    ///
+    /// # Errors
+    /// Will return `Err` with [`Error::is_session_expired`][is_expired] being `true` if the
+    /// session is expired, or `Err` if it fails to read `local`.
+    ///
+    /// [offset]: ./struct.ResumedUpload.html#structfield.offset
+    /// [is_expired]: ./struct.Error.html#method.is_session_expired
+    pub async fn resume(
+        &self,
+        local: impl std::io::Read + Send + 'static,
+        local_file_size: u64,
+        client: &Client,
+    ) -> Result<Option<ResumedUpload>> {
+        if local_file_size != self.file_size {
+            return Ok(None);
+        }
+
+        let session = self.session();
+        let meta = session.get_meta(client).await?;
+        let offset = meta
+            .next_expected_ranges
+            .first()
+            .map_or(self.file_size, |range| range.start);
+
+        let (uploaded, expected_hash) = (self.uploaded, self.quick_xor_hash.clone());
+        let hash = move || hash_local_prefix(local, offset, uploaded, expected_hash.as_deref());
+        let ret = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle
+                .spawn_blocking(hash)
+                .await
+                .unwrap_or_else(|err| Err(std::io::Error::other(err))),
+            Err(_) => hash(),
+        };
+        let prefix_hasher = match ret.map_err(Error::from_io)? {
+            Some(hasher) => hasher,
+            None => return Ok(None),
+        };
+
+        Ok(Some(ResumedUpload {
+            session: session.with_expiration_date_time(meta.expiration_date_time.clone()),
+            meta,
+            verifier: UploadVerifier::from_prefix(prefix_hasher),
+            offset,
+        }))
+    }
+}
+
+/// Hash the first `offset` bytes of `local`, checking the hash of the first `uploaded` bytes
+/// against `expected_hash` if any.
+///
+/// Return `None` if the hash mismatches or `local` is too short.
+fn hash_local_prefix(
+    mut local: impl std::io::Read,
+    offset: u64,
+    uploaded: u64,
+    expected_hash: Option<&str>,
+) -> std::io::Result<Option<QuickXorHash>> {
+    let read_len = offset.max(uploaded);
+    let mut hasher = QuickXorHash::new();
+    let mut prefix_hasher = None;
+    let mut buf = vec![0u8; 64 << 10];
+    loop {
+        if hasher.len() == offset {
+            prefix_hasher = Some(hasher.clone());
+        }
+        if hasher.len() == uploaded
+            && expected_hash.is_some_and(|expected| expected != hasher.digest_base64())
+        {
+            return Ok(None);
+        }
+        if hasher.len() == read_len {
+            return Ok(prefix_hasher);
+        }
+        let mut limit = buf.len() as u64;
+        for &boundary in &[offset, uploaded, read_len] {
+            if hasher.len() < boundary {
+                limit = limit.min(boundary - hasher.len());
+            }
+        }
+        let n = local.read(&mut buf[..limit as usize])?;
+        if n == 0 {
+            return Ok(None);
+        }
+        hasher.update(&buf[..n]);
     }
 }
 
@@ -1336,6 +4466,605 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_merge_children_snapshot() {
+        let item = |id: &str, parent_id: &str, name: &str| -> DriveItem {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "name": name,
+                "parentReference": { "id": parent_id },
+            }))
+            .unwrap()
+        };
+        let folder_id = ItemId("folder".to_owned());
+        let mut deleted = item("c", "folder", "c");
+        deleted.deleted = Some(Box::new(serde_json::json!({})));
+
+        let merged = merge_children_snapshot(
+            vec![
+                item("folder", "root", "folder"),
+                item("a", "folder", "a"),
+                item("b", "folder", "b"),
+                item("c", "folder", "c"),
+                item("x", "b", "nested"),
+                item("a", "folder", "a2"),
+                item("b", "other", "b"),
+                deleted,
+                item("d", "folder", "d"),
+            ],
+            &folder_id,
+        );
+        assert_eq!(
+            merged
+                .iter()
+                .map(|item| item.name.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            ["a2", "d"],
+        );
+    }
+
+    #[test]
+    fn test_pack_upload_batches() {
+        let (batches, singles) = pack_upload_batches(vec![1; 45]);
+        assert_eq!(
+            batches.iter().map(|b| b.len()).collect::<Vec<_>>(),
+            [20, 20, 5],
+        );
+        assert!(singles.is_empty());
+
+        let big = OneDrive::UPLOAD_SMALL_MAX_SIZE;
+        let half = OneDrive::UPLOAD_MANY_BATCH_MAX_BYTES / 4 * 3 / 2;
+        let (batches, singles) = pack_upload_batches(vec![1, big, half, half, 1, 1]);
+        assert_eq!(batches, [vec![0, 2], vec![3, 4, 5]]);
+        assert_eq!(singles, [1]);
+
+        let (batches, singles) = pack_upload_batches(vec![1]);
+        assert!(batches.is_empty());
+        assert_eq!(singles, [0]);
+    }
+
+    #[test]
+    fn test_batch_response() {
+        use futures_util::FutureExt as _;
+
+        let item = |json| serde_json::from_value::<BatchResponseItem>(json).unwrap();
+        let resp = item(serde_json::json!({
+            "id": "0",
+            "status": 201,
+            "headers": { "ETag": "\"tag\"" },
+            "body": { "id": "1", "name": "a.txt" },
+        }))
+        .into_response()
+        .unwrap();
+        let item_ = resp
+            .parse_drive_item(false)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(item_.name.as_deref(), Some("a.txt"));
+        assert_eq!(item_.e_tag, Some(Tag("\"tag\"".to_owned())));
+
+        let resp = item(serde_json::json!({
+            "id": "1",
+            "status": 429,
+            "headers": { "Retry-After": "3" },
+            "body": { "error": { "code": "activityLimitReached", "message": "" } },
+        }))
+        .into_response()
+        .unwrap();
+        let err = resp
+            .parse_drive_item(false)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.status_code(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(3)));
+
+        let url =
+            Url::parse("https://graph.microsoft.com/v1.0/me/drive/root:/a:/content?x=1").unwrap();
+        assert_eq!(batch_url(&url), "/me/drive/root:/a:/content?x=1");
+    }
+
+    #[test]
+    fn test_batch_error() {
+        let api_error = |status| {
+            let response = ErrorResponse {
+                code: "code".to_owned(),
+                message: String::new(),
+                inner_error: None,
+            };
+            Error::from_error_response(status, response, Some(Duration::from_secs(5)))
+        };
+        assert!(is_batch_unsupported(&api_error(StatusCode::NOT_FOUND)));
+        assert!(is_batch_unsupported(&api_error(
+            StatusCode::NOT_IMPLEMENTED
+        )));
+        assert!(!is_batch_unsupported(&api_error(
+            StatusCode::TOO_MANY_REQUESTS
+        )));
+        assert!(!is_batch_unsupported(&Error::unexpected_response("")));
+
+        let errors = api_error(StatusCode::TOO_MANY_REQUESTS).split(3);
+        assert_eq!(errors.len(), 3);
+        for err in &errors {
+            assert_eq!(err.status_code(), Some(StatusCode::TOO_MANY_REQUESTS));
+            assert_eq!(err.retry_after(), Some(Duration::from_secs(5)));
+        }
+        let errors = Error::from_io(std::io::ErrorKind::Other.into()).split(2);
+        assert!(errors[0].to_string().starts_with("I/O error"));
+        assert!(errors[1].to_string().starts_with("Unexpected response"));
+    }
+
+    #[test]
+    fn test_deleted_if_exists() {
+        let api_error = |status| {
+            let response = ErrorResponse {
+                code: "code".to_owned(),
+                message: String::new(),
+                inner_error: None,
+            };
+            Error::from_error_response(status, response, None)
+        };
+        assert!(deleted_if_exists(Ok(())).unwrap());
+        assert!(!deleted_if_exists(Err(api_error(StatusCode::NOT_FOUND))).unwrap());
+        assert_eq!(
+            deleted_if_exists(Err(api_error(StatusCode::PRECONDITION_FAILED)))
+                .unwrap_err()
+                .status_code(),
+            Some(StatusCode::PRECONDITION_FAILED),
+        );
+    }
+
+    #[test]
+    fn test_children_for_size_batch_request() {
+        let drive = OneDrive::new(String::new(), DriveLocation::me());
+        let req = drive.children_for_size_batch_request(3, &ItemId("1234".to_owned()));
+        assert_eq!(req.id, "3");
+        assert_eq!(req.method, "GET");
+        assert_eq!(
+            req.url,
+            "/me/drive/items/1234/children?%24select=id%2Csize%2Cfolder%2CremoteItem",
+        );
+        assert!(req.headers.is_none() && req.body.is_none());
+    }
+
+    #[test]
+    fn test_hash_local_prefix() {
+        let digest_of = |data: &[u8]| {
+            let mut hasher = QuickXorHash::new();
+            hasher.update(data);
+            hasher.digest_base64()
+        };
+        let content = b"hello, world";
+        let expected = digest_of(&content[..5]);
+
+        for &offset in &[0, 3, 5, 8] {
+            let hasher = hash_local_prefix(&content[..], offset, 5, Some(&expected))
+                .unwrap()
+                .unwrap();
+            assert_eq!(hasher.len(), offset);
+            assert_eq!(
+                hasher.digest_base64(),
+                digest_of(&content[..offset as usize])
+            );
+        }
+        assert!(
+            hash_local_prefix(&b"jello, world"[..], 5, 5, Some(&expected))
+                .unwrap()
+                .is_none()
+        );
+        assert!(hash_local_prefix(&b"jello, world"[..], 5, 5, None)
+            .unwrap()
+            .is_some());
+        assert!(hash_local_prefix(&content[..3], 5, 5, None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_upload_session_serde() {
+        let sess: UploadSession = serde_json::from_str(
+            r#"{
+                "uploadUrl": "https://sn3302.up.1drv.com/up/fe6987415ace7X4e1eF866337",
+                "expirationDateTime": "2015-01-29T09:21:55.523Z",
+                "nextExpectedRanges": ["0-"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sess.upload_url(),
+            "https://sn3302.up.1drv.com/up/fe6987415ace7X4e1eF866337",
+        );
+        assert_eq!(
+            sess.expiration_date_time(),
+            Some("2015-01-29T09:21:55.523Z")
+        );
+        let json = serde_json::to_string(&sess).unwrap();
+        assert_eq!(serde_json::from_str::<UploadSession>(&json).unwrap(), sess);
+        // Timeouts are local settings and not serialized.
+        let with_timeout = sess.clone().with_timeout(Some(Duration::from_secs(1)));
+        assert_eq!(with_timeout.timeout(), Some(Duration::from_secs(1)));
+        assert_eq!(serde_json::to_string(&with_timeout).unwrap(), json);
+
+        let monitor = CopyProgressMonitor::from_monitor_url("https://example.com/m".to_owned());
+        let json = serde_json::to_string(&monitor).unwrap();
+        assert_eq!(json, r#"{"monitorUrl":"https://example.com/m"}"#);
+        assert_eq!(
+            serde_json::from_str::<CopyProgressMonitor>(&json).unwrap(),
+            monitor,
+        );
+        // The requested name of `copy` is kept to check renaming later.
+        let monitor = CopyProgressMonitor {
+            dest_name: Some("a.txt".to_owned()),
+            ..monitor
+        };
+        let json = serde_json::to_string(&monitor).unwrap();
+        assert_eq!(
+            json,
+            r#"{"monitorUrl":"https://example.com/m","destName":"a.txt"}"#,
+        );
+        assert_eq!(
+            serde_json::from_str::<CopyProgressMonitor>(&json).unwrap(),
+            monitor,
+        );
+    }
+
+    #[test]
+    fn test_created_item() {
+        let item = |name: &str| DriveItem {
+            name: Some(name.to_owned()),
+            ..Default::default()
+        };
+        assert!(!CreatedItem::new(item("a.txt"), Some("a.txt")).was_renamed);
+        assert!(CreatedItem::new(item("a 1.txt"), Some("a.txt")).was_renamed);
+        assert!(!CreatedItem::new(item("a 1.txt"), None).was_renamed);
+
+        let id = ItemId("id".to_owned());
+        let name = FileName::new("c.txt").unwrap();
+        let name_of = |path| ItemLocation::from_path(path).unwrap().name();
+        assert_eq!(name_of("/a/b.txt"), Some("b.txt"));
+        assert_eq!(name_of("/a/b/"), Some("b"));
+        assert_eq!(name_of("/"), None);
+        assert_eq!(ItemLocation::from_id(&id).name(), None);
+        assert_eq!(ItemLocation::child_of_id(&id, name).name(), Some("c.txt"));
+    }
+
+    #[test]
+    fn test_delta_token() {
+        let drive_id = DriveId("b!abc".to_owned());
+        let token = DeltaToken::new(
+            "https://graph.microsoft.com/v1.0/drives/b!abc/root/delta?token=a%3Db".to_owned(),
+        );
+        assert_eq!(token.token().as_deref(), Some("a=b"));
+        assert!(token.age().is_some());
+        let obtained_at = std::time::UNIX_EPOCH + Duration::from_secs(100);
+        let token_at = DeltaToken::new_at(token.delta_url().to_owned(), obtained_at);
+        assert_eq!(
+            token_at.age_at(obtained_at + Duration::from_secs(5)),
+            Some(Duration::from_secs(5)),
+        );
+        assert_eq!(token_at.age_at(std::time::UNIX_EPOCH), Some(Duration::ZERO));
+        assert!(token.targets_drive(&DriveLocation::from_id(drive_id)));
+        assert!(!token.targets_drive(&DriveLocation::from_id(DriveId("b!abd".to_owned()))));
+        assert!(!token.targets_drive(&DriveLocation::me()));
+
+        let token = DeltaToken::from_delta_url(
+            "https://graph.microsoft.com/v1.0/me/drive/items/123/delta?token=xyz".to_owned(),
+        );
+        assert_eq!(token.age(), None);
+        assert!(token.targets_drive(&DriveLocation::me()));
+        assert!(!token.targets_drive(&DriveLocation::from_user("me".to_owned())));
+
+        let json = serde_json::to_string(&token).unwrap();
+        assert_eq!(serde_json::from_str::<DeltaToken>(&json).unwrap(), token);
+
+        use futures_util::FutureExt as _;
+        let onedrive = OneDrive::new(
+            String::new(),
+            DriveLocation::from_id(DriveId("b!abc".to_owned())),
+        );
+        let err = onedrive
+            .track_changes_from_delta_token(&token)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert!(err.is_invalid_argument());
+    }
+
+    #[cfg(feature = "beta")]
+    #[test]
+    fn test_copy_progress_parsing() {
+        let progress: CopyProgress = serde_json::from_str(
+            r#"{ "percentageComplete": 42.5, "status": "inProgress", "statusDescription": "Copying" }"#,
+        )
+        .unwrap();
+        assert_eq!(progress.percentage_complete, 42.5);
+        assert_eq!(progress.status, AsyncOperationStatus::InProgress);
+        assert_eq!(progress.status_description.as_deref(), Some("Copying"));
+        assert!(!progress.is_finished());
+
+        let progress: CopyProgress =
+            serde_json::from_str(r#"{ "status": "failed", "errorCode": "nameAlreadyExists" }"#)
+                .unwrap();
+        assert_eq!(
+            progress.status,
+            AsyncOperationStatus::Failed(Some(ErrorResponse {
+                code: "nameAlreadyExists".to_owned(),
+                message: String::new(),
+                inner_error: None,
+            })),
+        );
+        assert!(progress.is_finished());
+
+        let progress: CopyProgress = serde_json::from_str(r#"{ "status": "whatever" }"#).unwrap();
+        assert_eq!(
+            progress.status,
+            AsyncOperationStatus::Unknown("whatever".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_upload_session_expiration() {
+        let session = UploadSession::from_upload_url("https://example.com".to_owned());
+        assert_eq!(session.expires_at(), None);
+        assert!(!session.is_expired());
+
+        let session = session.with_expiration_date_time("2015-01-29T09:21:55.523Z".to_owned());
+        assert_eq!(
+            session.expires_at(),
+            Some(std::time::UNIX_EPOCH + Duration::new(1_422_523_315, 523_000_000)),
+        );
+        assert!(session.is_expired());
+
+        let session = session.with_expiration_date_time("2015-01-29T17:21:55+08:00".to_owned());
+        assert_eq!(
+            session.expires_at(),
+            Some(std::time::UNIX_EPOCH + Duration::from_secs(1_422_523_315)),
+        );
+        let expires_at = session.expires_at().unwrap();
+        assert!(!session.is_expired_at(expires_at - Duration::from_secs(1)));
+        assert!(session.is_expired_at(expires_at));
+
+        let session = session.with_expiration_date_time("9999-12-31T23:59:59Z".to_owned());
+        assert!(!session.is_expired());
+
+        for invalid in &[
+            "",
+            "2015-01-29",
+            "2015-13-29T09:21:55Z",
+            "2015-01-29T09:21Z",
+        ] {
+            let session = UploadSession::from_upload_url("https://example.com".to_owned())
+                .with_expiration_date_time((*invalid).to_owned());
+            assert_eq!(session.expires_at(), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_upload_part_with_checksum_out_of_order() {
+        use futures_util::FutureExt as _;
+
+        let session = UploadSession::from_upload_url("https://example.com".to_owned());
+        let mut verifier = UploadVerifier::new();
+        let err = session
+            .upload_part_with_checksum(vec![0u8; 10], 10..20, 100, &Client::new(), &mut verifier)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert!(err.is_invalid_argument());
+        assert_eq!(verifier.uploaded_len(), 0);
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingSleeper(std::sync::Mutex<Vec<Duration>>);
+
+    impl Sleeper for RecordingSleeper {
+        fn sleep(&self, duration: Duration) -> crate::SleepFuture {
+            self.0.lock().unwrap().push(duration);
+            Box::pin(futures_util::future::ready(()))
+        }
+    }
+
+    #[test]
+    fn test_retry_transient() {
+        use futures_util::FutureExt as _;
+
+        let throttled = |retry_after| {
+            let response =
+                serde_json::from_str(r#"{ "code": "tooManyRequests", "message": "" }"#).unwrap();
+            Error::from_error_response(StatusCode::TOO_MANY_REQUESTS, response, retry_after)
+        };
+
+        let sleeper = Arc::new(RecordingSleeper::default());
+        let drive = OneDrive::new(String::new(), DriveLocation::me()).with_sleeper(sleeper.clone());
+
+        let mut results = vec![
+            Err(throttled(Some(Duration::from_secs(2)))),
+            Err(throttled(None)),
+            Ok(42),
+        ]
+        .into_iter();
+        let ret = drive
+            .retry_transient(|| futures_util::future::ready(results.next().unwrap()))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(ret.unwrap(), 42);
+        assert_eq!(
+            *sleeper.0.lock().unwrap(),
+            [Duration::from_secs(2), Duration::from_secs(5)],
+        );
+
+        sleeper.0.lock().unwrap().clear();
+        let not_found = || {
+            let response =
+                serde_json::from_str(r#"{ "code": "itemNotFound", "message": "" }"#).unwrap();
+            Error::from_error_response(StatusCode::NOT_FOUND, response, None)
+        };
+        assert!(not_found().is_client_error());
+        assert!(!not_found().is_server_error());
+        assert!(!not_found().is_transient());
+        assert!(throttled(None).is_transient());
+
+        let ret = drive
+            .retry_transient(|| futures_util::future::ready(Err::<(), _>(not_found())))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(ret.unwrap_err().status_code(), Some(StatusCode::NOT_FOUND));
+        assert!(sleeper.0.lock().unwrap().is_empty());
+
+        let ret = drive
+            .retry_transient(|| futures_util::future::ready(Err::<(), _>(throttled(None))))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(
+            ret.unwrap_err().status_code(),
+            Some(StatusCode::TOO_MANY_REQUESTS)
+        );
+        assert_eq!(sleeper.0.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_redact_url() {
+        use crate::util::redact_url;
+
+        let redact = |url: &str| redact_url(&Url::parse(url).unwrap());
+        assert_eq!(
+            redact("https://graph.microsoft.com/v1.0/me/drive/root/children?$top=2"),
+            "https://graph.microsoft.com/v1.0/me/drive/root/children?%24top=2",
+        );
+        assert_eq!(
+            redact("https://graph.microsoft.com/v1.0/me/drive?access_token=secret&a=b"),
+            "https://graph.microsoft.com/v1.0/me/drive?access_token=REDACTED&a=b",
+        );
+        assert_eq!(
+            redact("https://public.db.files.1drv.com/y4mABC/file.txt?tempauth=secret"),
+            "https://public.db.files.1drv.com/REDACTED",
+        );
+        assert_eq!(
+            redact("https://sn3302.up.1drv.com/up/fe6987415ace7X4e1eF866337"),
+            "https://sn3302.up.1drv.com/REDACTED",
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        for &(secs, s) in &[
+            (0, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_422_523_315, "2015-01-29T09:21:55Z"),
+            (253_402_300_799, "9999-12-31T23:59:59Z"),
+        ] {
+            let time = std::time::UNIX_EPOCH + Duration::from_secs(secs);
+            assert_eq!(parse_timestamp(s), Some(time));
+        }
+
+        let epoch = std::time::UNIX_EPOCH;
+        assert_eq!(
+            parse_timestamp("1970-01-01T00:00:01.25Z"),
+            Some(epoch + Duration::from_millis(1250)),
+        );
+        assert_eq!(
+            parse_timestamp("1969-12-31T23:59:59.25Z"),
+            Some(epoch - Duration::from_millis(750)),
+        );
+        assert_eq!(
+            parse_timestamp("1970-01-01T00:59:59.5+01:00"),
+            Some(epoch - Duration::from_millis(500)),
+        );
+    }
+
+    #[test]
+    fn test_accepted_job() {
+        use crate::util::accepted_job;
+
+        let resp = |status: StatusCode, location: Option<&str>| -> Response {
+            let mut builder = http::Response::builder().status(status);
+            if let Some(location) = location {
+                builder = builder.header(header::LOCATION, location);
+            }
+            builder.body("").unwrap().into()
+        };
+        let monitor = Some("https://example.com/monitor");
+        for &strict in &[false, true] {
+            assert_eq!(
+                accepted_job(&resp(StatusCode::ACCEPTED, monitor), strict).unwrap(),
+                monitor.map(|s| s.to_owned()),
+            );
+            assert_eq!(
+                accepted_job(&resp(StatusCode::OK, monitor), strict).unwrap(),
+                None
+            );
+        }
+        assert_eq!(
+            accepted_job(&resp(StatusCode::ACCEPTED, None), false).unwrap(),
+            None
+        );
+        assert!(accepted_job(&resp(StatusCode::ACCEPTED, None), true)
+            .unwrap_err()
+            .is_async_job_accepted());
+
+        use futures_util::FutureExt as _;
+        let err = resp(StatusCode::ACCEPTED, monitor)
+            .parse_no_content(false)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.async_job_monitor_url(), monitor);
+        let url = resp(StatusCode::ACCEPTED, monitor)
+            .parse_async_job(true)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(Some(&*url), monitor);
+        assert!(resp(StatusCode::ACCEPTED, None)
+            .parse_optional::<DriveItem>(false)
+            .now_or_never()
+            .unwrap()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_response_limits() {
+        use futures_util::FutureExt as _;
+
+        let resp = |body: &'static str| -> Response { http::Response::new(body).into() };
+        let limited = |body, limit| limit_body_size(resp(body), limit).now_or_never().unwrap();
+        let item: DriveItem = limited(r#"{ "id": "1" }"#, Some(13))
+            .unwrap()
+            .parse(false)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.id, Some(ItemId("1".to_owned())));
+        assert!(limited(r#"{ "id": "1" }"#, None).is_ok());
+        let err = limited(r#"{ "id": "1" }"#, Some(12)).unwrap_err();
+        assert!(err.is_response_limit_exceeded());
+        assert_eq!(err.status_code(), None);
+
+        let page = || {
+            DriveItemFetcher::new(DriveItemCollectionResponse {
+                value: Some(vec![DriveItem::default(), DriveItem::default()]),
+                next_url: None,
+                delta_url: None,
+                odata_context: None,
+            })
+        };
+        let fetch_all = |limits| {
+            let onedrive =
+                OneDrive::new("token".to_owned(), DriveLocation::me()).with_response_limits(limits);
+            page().fetch_all(&onedrive).now_or_never().unwrap()
+        };
+        assert_eq!(fetch_all(ResponseLimits::new()).unwrap().0.len(), 2);
+        assert!(fetch_all(ResponseLimits::new().max_page_items(Some(2))).is_ok());
+        assert!(fetch_all(ResponseLimits::new().max_page_items(Some(1)))
+            .unwrap_err()
+            .is_response_limit_exceeded());
+        assert!(fetch_all(ResponseLimits::new().max_total_items(Some(1)))
+            .unwrap_err()
+            .is_response_limit_exceeded());
+    }
+
     #[test]
     fn test_path_name_check() {
         let invalid_names = ["", ".*?", "a|b", "a<b>b", ":run", "/", "\\"];
@@ -10,17 +10,18 @@
 //! # See also
 //! [Microsoft Docs](https://docs.microsoft.com/en-us/graph/query-parameters)
 use crate::{
-    resource::{ResourceField, Tag},
+    resource::{DriveField, DriveItemField, ResourceField, Tag},
     util::RequestBuilderTransformer,
     ConflictBehavior,
 };
 use reqwest::{header, RequestBuilder};
 use std::{fmt::Write, marker::PhantomData};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 struct AccessOption {
     if_match: Option<String>,
     if_none_match: Option<String>,
+    prefer_return: Option<ReturnPreference>,
 }
 
 impl AccessOption {
@@ -33,6 +34,19 @@ impl AccessOption {
         self.if_none_match = Some(tag.0.clone());
         self
     }
+
+    fn prefer_return(mut self, preference: ReturnPreference) -> Self {
+        self.prefer_return = Some(preference);
+        self
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            if_match: other.if_match.or(self.if_match),
+            if_none_match: other.if_none_match.or(self.if_none_match),
+            prefer_return: other.prefer_return.or(self.prefer_return),
+        }
+    }
 }
 
 impl RequestBuilderTransformer for AccessOption {
@@ -43,16 +57,40 @@ impl RequestBuilderTransformer for AccessOption {
         if let Some(v) = self.if_none_match {
             req = req.header(header::IF_NONE_MATCH, v);
         }
+        if let Some(v) = self.prefer_return {
+            let v = match v {
+                ReturnPreference::Minimal => "return=minimal",
+                ReturnPreference::Representation => "return=representation",
+            };
+            req = req.header("Prefer", v);
+        }
         req
     }
 }
 
 /// Option for GET-like requests to one resource object.
-#[derive(Debug)]
+///
+/// It can be cloned and [merged][merge] to compose options from reusable fragments.
+///
+/// # Example
+/// ```
+/// use onedrive_api::{option::ObjectOption, resource::DriveItemField};
+///
+/// let base: ObjectOption<DriveItemField> =
+///     vec![DriveItemField::id, DriveItemField::name].into_iter().collect();
+/// let option = base
+///     .clone()
+///     .merge(ObjectOption::new().select(&[DriveItemField::size]));
+/// ```
+///
+/// [merge]: #method.merge
+#[derive(Clone, Debug)]
 pub struct ObjectOption<Field> {
     access_opt: AccessOption,
     select_buf: String,
-    expand_buf: String,
+    // Each entry is a field name, optionally followed by its nested options.
+    expand_buf: Vec<String>,
+    raw_params: Vec<(String, String)>,
     _marker: PhantomData<dyn Fn(&Field) + Send + Sync>,
 }
 
@@ -62,7 +100,8 @@ impl<Field: ResourceField> ObjectOption<Field> {
         Self {
             access_opt: Default::default(),
             select_buf: String::new(),
-            expand_buf: String::new(),
+            expand_buf: Vec::new(),
+            raw_params: Vec::new(),
             _marker: PhantomData,
         }
     }
@@ -93,6 +132,21 @@ impl<Field: ResourceField> ObjectOption<Field> {
         self
     }
 
+    /// Specify how much of the resource object should be responded by mutating requests.
+    ///
+    /// It only makes sense for mutating requests like
+    /// [`OneDrive::update_item_with_option`][update_item].
+    ///
+    /// # See also
+    /// [`ReturnPreference`][return_pref]
+    ///
+    /// [update_item]: ../struct.OneDrive.html#method.update_item_with_option
+    /// [return_pref]: ./enum.ReturnPreference.html
+    pub fn prefer_return(mut self, preference: ReturnPreference) -> Self {
+        self.access_opt = self.access_opt.prefer_return(preference);
+        self
+    }
+
     /// Select only some fields of the resource object.
     ///
     /// See documentation of module [`onedrive_api::resource`][resource] for more details.
@@ -135,8 +189,7 @@ impl<Field: ResourceField> ObjectOption<Field> {
     }
 
     fn expand_raw(mut self, field: &str, select_children: Option<&[&str]>) -> Self {
-        let buf = &mut self.expand_buf;
-        write!(buf, ",{}", field).unwrap();
+        let mut buf = field.to_owned();
         if let Some(children) = select_children {
             write!(buf, "($select=").unwrap();
             for sel in children {
@@ -144,8 +197,85 @@ impl<Field: ResourceField> ObjectOption<Field> {
             }
             write!(buf, ")").unwrap();
         }
+        self.expand_buf.push(buf);
+        self
+    }
+
+    /// Add a raw query parameter to the request.
+    ///
+    /// This is an escape hatch for query options which are not modeled by this crate yet,
+    /// eg. newly introduced preview parameters. `key` and `value` are sent as-is
+    /// (but URL-encoded), and are not checked at all.
+    ///
+    /// # Note
+    /// If called more than once, all parameters will be added in order,
+    /// even if there are duplicated keys.
+    /// Setting a key which is also set by other methods (eg. `$select`) may cause errors.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/query-parameters)
+    pub fn raw_param(mut self, key: &str, value: &str) -> Self {
+        self.raw_params.push((key.to_owned(), value.to_owned()));
         self
     }
+
+    /// Merge another option into this one.
+    ///
+    /// Fields selected or expanded and raw parameters of both are kept, with duplicated
+    /// selected fields removed. A field expanded in both is expanded once, with the
+    /// nested options from `other`. For other settings (eg. [`if_match`][if_match]),
+    /// the ones set in `other` take precedence.
+    ///
+    /// [if_match]: #method.if_match
+    pub fn merge(mut self, other: Self) -> Self {
+        self.access_opt = self.access_opt.merge(other.access_opt);
+        for sel in other.select_buf.split(',').filter(|s| !s.is_empty()) {
+            if !self.select_buf.split(',').any(|s| s == sel) {
+                self = self.select_raw(&[sel]);
+            }
+        }
+        fn expand_field(expand: &str) -> &str {
+            expand.split('(').next().unwrap_or(expand)
+        }
+        for exp in other.expand_buf {
+            match self
+                .expand_buf
+                .iter_mut()
+                .find(|e| expand_field(e) == expand_field(&exp))
+            {
+                Some(e) => *e = exp,
+                None => self.expand_buf.push(exp),
+            }
+        }
+        self.raw_params.extend(other.raw_params);
+        self
+    }
+}
+
+impl ObjectOption<DriveItemField> {
+    /// Select well-known fields needed to sync drive items, that is,
+    /// [`DriveItemField::CORE`][core].
+    ///
+    /// It can be combined with [`select`][select] to select more fields.
+    ///
+    /// [core]: ../resource/enum.DriveItemField.html#associatedconstant.CORE
+    /// [select]: #method.select
+    pub fn select_core(self) -> Self {
+        self.select(DriveItemField::CORE)
+    }
+}
+
+impl ObjectOption<DriveField> {
+    /// Select fields identifying the drive and its owner, that is,
+    /// [`DriveField::IDENTITY`][identity].
+    ///
+    /// It can be combined with [`select`][select] to select more fields.
+    ///
+    /// [identity]: ../resource/enum.DriveField.html#associatedconstant.IDENTITY
+    /// [select]: #method.select
+    pub fn select_identity(self) -> Self {
+        self.select(DriveField::IDENTITY)
+    }
 }
 
 impl<Field: ResourceField> RequestBuilderTransformer for ObjectOption<Field> {
@@ -154,8 +284,11 @@ impl<Field: ResourceField> RequestBuilderTransformer for ObjectOption<Field> {
         if let Some(s) = self.select_buf.get(1..) {
             req = req.query(&[("$select", s)]);
         }
-        if let Some(s) = self.expand_buf.get(1..) {
-            req = req.query(&[("$expand", s)]);
+        if !self.expand_buf.is_empty() {
+            req = req.query(&[("$expand", self.expand_buf.join(","))]);
+        }
+        if !self.raw_params.is_empty() {
+            req = req.query(&self.raw_params);
         }
         req
     }
@@ -167,11 +300,24 @@ impl<Field: ResourceField> Default for ObjectOption<Field> {
     }
 }
 
+/// Select all fields in the iterator.
+impl<Field: ResourceField> std::iter::FromIterator<Field> for ObjectOption<Field> {
+    fn from_iter<I: IntoIterator<Item = Field>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |opt, field| opt.select(&[field]))
+    }
+}
+
 /// Option for GET-like requests for a collection of resource objects.
-#[derive(Debug)]
+///
+/// Like [`ObjectOption`][object_option], it can be cloned and merged.
+///
+/// [object_option]: ./struct.ObjectOption.html
+#[derive(Clone, Debug)]
 pub struct CollectionOption<Field> {
     obj_option: ObjectOption<Field>,
     order_buf: Option<String>,
+    filter_buf: Option<String>,
     page_size_buf: Option<String>,
     get_count_buf: bool,
 }
@@ -182,6 +328,7 @@ impl<Field: ResourceField> CollectionOption<Field> {
         Self {
             obj_option: Default::default(),
             order_buf: None,
+            filter_buf: None,
             page_size_buf: None,
             get_count_buf: false,
         }
@@ -237,6 +384,17 @@ impl<Field: ResourceField> CollectionOption<Field> {
         self
     }
 
+    /// Add a raw query parameter to the request.
+    ///
+    /// # See also
+    /// [`ObjectOption::raw_param`][raw_param]
+    ///
+    /// [raw_param]: ./struct.ObjectOption.html#method.raw_param
+    pub fn raw_param(mut self, key: &str, value: &str) -> Self {
+        self.obj_option = self.obj_option.raw_param(key, value);
+        self
+    }
+
     /// Specify the sort order of the items responsed.
     ///
     /// # Note
@@ -253,6 +411,21 @@ impl<Field: ResourceField> CollectionOption<Field> {
         self
     }
 
+    /// Filter the items responsed by an OData expression, like `name eq 'foo.txt'`.
+    ///
+    /// # Note
+    /// If called more than once, only the last call make sense.
+    ///
+    /// Support of filtering varies between drive types and APIs. Personal OneDrive
+    /// may ignore or reject some expressions.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/query-parameters#filter-parameter)
+    pub fn filter(mut self, expr: &str) -> Self {
+        self.filter_buf = Some(expr.to_owned());
+        self
+    }
+
     /// Specify the number of items per page.
     ///
     /// # Note
@@ -282,17 +455,53 @@ impl<Field: ResourceField> CollectionOption<Field> {
         self
     }
 
+    /// Merge another option into this one.
+    ///
+    /// For settings which only make sense once (eg. [`order_by`][order_by]),
+    /// the ones set in `other` take precedence.
+    ///
+    /// # See also
+    /// [`ObjectOption::merge`][merge]
+    ///
+    /// [order_by]: #method.order_by
+    /// [merge]: ./struct.ObjectOption.html#method.merge
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            obj_option: self.obj_option.merge(other.obj_option),
+            order_buf: other.order_buf.or(self.order_buf),
+            filter_buf: other.filter_buf.or(self.filter_buf),
+            page_size_buf: other.page_size_buf.or(self.page_size_buf),
+            get_count_buf: other.get_count_buf || self.get_count_buf,
+        }
+    }
+
     pub(crate) fn has_get_count(&self) -> bool {
         self.get_count_buf
     }
 }
 
+impl CollectionOption<DriveItemField> {
+    /// Select well-known fields needed to sync drive items.
+    ///
+    /// # See also
+    /// [`ObjectOption::select_core`][select_core]
+    ///
+    /// [select_core]: ./struct.ObjectOption.html#method.select_core
+    pub fn select_core(mut self) -> Self {
+        self.obj_option = self.obj_option.select_core();
+        self
+    }
+}
+
 impl<Field: ResourceField> RequestBuilderTransformer for CollectionOption<Field> {
     fn trans(self, mut req: RequestBuilder) -> RequestBuilder {
         req = self.obj_option.trans(req);
         if let Some(s) = &self.order_buf {
             req = req.query(&[("$orderby", s)]);
         }
+        if let Some(s) = &self.filter_buf {
+            req = req.query(&[("$filter", s)]);
+        }
         if let Some(s) = &self.page_size_buf {
             req = req.query(&[("$top", s)]);
         }
@@ -309,6 +518,16 @@ impl<Field: ResourceField> Default for CollectionOption<Field> {
     }
 }
 
+/// Select all fields in the iterator.
+impl<Field: ResourceField> std::iter::FromIterator<Field> for CollectionOption<Field> {
+    fn from_iter<I: IntoIterator<Item = Field>>(iter: I) -> Self {
+        Self {
+            obj_option: iter.into_iter().collect(),
+            ..Self::new()
+        }
+    }
+}
+
 /// Specify the sorting order.
 ///
 /// Used in [`CollectionOption::order_by`][order_by].
@@ -322,6 +541,30 @@ pub enum Order {
     Descending,
 }
 
+/// The preference of the response body of mutating requests, sent in `Prefer` header.
+///
+/// Used in [`ObjectOption::prefer_return`][obj_prefer] and
+/// [`DriveItemPutOption::prefer_return`][put_prefer].
+///
+/// # Note
+/// With `Minimal`, the server may respond HTTP 204 NO_CONTENT without a body. In this case,
+/// the `DriveItem` returned only contains [`e_tag`][e_tag] from the response header,
+/// and other fields are `None`.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/best-practices-concept#getting-minimal-responses)
+///
+/// [obj_prefer]: ./struct.ObjectOption.html#method.prefer_return
+/// [put_prefer]: ./struct.DriveItemPutOption.html#method.prefer_return
+/// [e_tag]: ../resource/struct.DriveItem.html#structfield.e_tag
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReturnPreference {
+    /// `return=minimal`. Respond as little as possible to reduce data transmission.
+    Minimal,
+    /// `return=representation`. Respond the full resource object.
+    Representation,
+}
+
 /// Option for PUT-like requests of `DriveItem`.
 #[derive(Debug, Default)]
 pub struct DriveItemPutOption {
@@ -360,6 +603,17 @@ impl DriveItemPutOption {
         self
     }
 
+    /// Specify how much of the resource object should be responded.
+    ///
+    /// # See also
+    /// [`ReturnPreference`][return_pref]
+    ///
+    /// [return_pref]: ./enum.ReturnPreference.html
+    pub fn prefer_return(mut self, preference: ReturnPreference) -> Self {
+        self.access_opt = self.access_opt.prefer_return(preference);
+        self
+    }
+
     pub(crate) fn get_conflict_behavior(&self) -> Option<ConflictBehavior> {
         self.conflict_behavior
     }
@@ -374,7 +628,19 @@ impl RequestBuilderTransformer for DriveItemPutOption {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::resource;
+    use crate::{resource, util::RequestBuilderExt as _};
+
+    fn query_of(option: impl RequestBuilderTransformer) -> String {
+        reqwest::Client::new()
+            .get("https://example.com/")
+            .apply(option)
+            .build()
+            .unwrap()
+            .url()
+            .query()
+            .unwrap_or_default()
+            .to_owned()
+    }
 
     fn _assert_send_sync<T: Send + Sync>() {}
 
@@ -391,4 +657,98 @@ mod tests {
     fn _assert_drive_item_put_option_is_send_sync() {
         _assert_send_sync::<DriveItemPutOption>();
     }
+
+    #[test]
+    fn test_prefer_return() {
+        let prefer_of = |option: DriveItemPutOption| {
+            reqwest::Client::new()
+                .patch("https://example.com/")
+                .apply(option)
+                .build()
+                .unwrap()
+                .headers()
+                .get("Prefer")
+                .map(|v| v.to_str().unwrap().to_owned())
+        };
+        assert_eq!(prefer_of(DriveItemPutOption::new()), None);
+        assert_eq!(
+            prefer_of(DriveItemPutOption::new().prefer_return(ReturnPreference::Minimal))
+                .as_deref(),
+            Some("return=minimal"),
+        );
+        assert_eq!(
+            prefer_of(DriveItemPutOption::new().prefer_return(ReturnPreference::Representation))
+                .as_deref(),
+            Some("return=representation"),
+        );
+    }
+
+    #[test]
+    fn test_raw_param() {
+        let opt = ObjectOption::<resource::DriveItemField>::new()
+            .select(&[resource::DriveItemField::id])
+            .raw_param("$search", "a b")
+            .raw_param("preview", "1");
+        assert_eq!(query_of(opt), "%24select=id&%24search=a+b&preview=1");
+
+        let opt = CollectionOption::<resource::DriveItemField>::new()
+            .raw_param("$skipToken", "x")
+            .page_size(2);
+        assert_eq!(query_of(opt), "%24skipToken=x&%24top=2");
+    }
+
+    #[test]
+    fn test_select_core() {
+        let opt = CollectionOption::<resource::DriveItemField>::new()
+            .select_core()
+            .select(&[resource::DriveItemField::web_url]);
+        assert_eq!(
+            query_of(opt),
+            "%24select=id%2Cname%2CeTag%2CcTag%2Csize%2Cfile%2Cfolder%2CparentReference%2CfileSystemInfo%2CwebUrl",
+        );
+    }
+
+    #[test]
+    fn test_select_identity() {
+        let opt = ObjectOption::<resource::DriveField>::new().select_identity();
+        assert_eq!(
+            query_of(opt),
+            "%24select=id%2Cname%2CdriveType%2Cowner%2CsharepointIds%2CwebUrl",
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        use resource::DriveItemField;
+
+        let base: CollectionOption<DriveItemField> = vec![DriveItemField::id, DriveItemField::name]
+            .into_iter()
+            .collect();
+        let base = base
+            .page_size(10)
+            .order_by(DriveItemField::name, Order::Ascending);
+        let opt = base.clone().merge(
+            CollectionOption::new()
+                .select(&[DriveItemField::name, DriveItemField::size])
+                .expand(DriveItemField::children, None)
+                .page_size(20),
+        );
+        assert_eq!(
+            query_of(opt),
+            "%24select=id%2Cname%2Csize&%24expand=children&%24orderby=name+asc&%24top=20",
+        );
+        assert_eq!(
+            query_of(base),
+            "%24select=id%2Cname&%24orderby=name+asc&%24top=10",
+        );
+
+        let opt = ObjectOption::<DriveItemField>::new()
+            .expand(DriveItemField::children, None)
+            .expand(DriveItemField::thumbnails, None)
+            .merge(ObjectOption::new().expand(DriveItemField::children, Some(&["id"])));
+        assert_eq!(
+            query_of(opt),
+            "%24expand=children%28%24select%3Did%2C%29%2Cthumbnails",
+        );
+    }
 }
@@ -42,7 +42,8 @@
 //! [expand]: ../option/struct.ObjectOption.html#method.expand
 //! [one_drive]: ../struct.OneDrive.html
 //! [drive_item_field]: ./enum.DriveItemField.html
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
 /// A semantic alias for URL string in resource objects.
 pub type Url = String;
@@ -187,13 +188,17 @@ define_resource_object! {
         pub last_modified_by: Option<JsonValue>,
         pub last_modified_date_time: Option<TimestampString>,
         pub name: Option<String>,
-        pub owner: Option<JsonValue>,
-        pub quota: Option<JsonValue>,
+        pub owner: Option<IdentitySet>,
+        pub quota: Option<Quota>,
         pub root: Option<DriveItem>,
-        pub sharepoint_ids: Option<JsonValue>,
+        pub sharepoint_ids: Option<SharepointIds>,
         pub special: Option<Vec<DriveItem>>,
         pub system: Option<JsonValue>,
         pub web_url: Option<Url>,
+
+        /// The `@odata.context` annotation describing the shape of the response.
+        [unselectable]
+        pub odata_context @"@odata.context": Option<ODataContext>,
     }
 
     /// DriveItem resource type
@@ -236,7 +241,7 @@ define_resource_object! {
         pub created_by_user: Option<JsonValue>,
         pub last_modified_by_user: Option<JsonValue>,
         pub permissions: Option<JsonValue>,
-        pub thumbnails: Option<JsonValue>,
+        pub thumbnails: Option<Vec<ThumbnailSet>>,
         pub versions: Option<JsonValue>,
 
         // Base item
@@ -248,7 +253,7 @@ define_resource_object! {
         pub last_modified_by: Option<JsonValue>,
         pub last_modified_date_time: Option<TimestampString>,
         pub name: Option<String>,
-        pub parent_reference: Option<JsonValue>,
+        pub parent_reference: Option<ItemReference>,
         pub web_url: Option<Url>,
 
         // Instance annotations
@@ -267,10 +272,699 @@ define_resource_object! {
         [unselectable]
         pub download_url @"@microsoft.graph.downloadUrl": Option<Url>,
 
+        /// The `@odata.context` annotation describing the shape of the response.
+        ///
+        /// It is only present on top-level items, not on items in a collection.
+        [unselectable]
+        pub odata_context @"@odata.context": Option<ODataContext>,
+
         // `@microsoft.graph.sourceUrl` is write-only
     }
 }
 
+impl DriveField {
+    /// Fields identifying a drive and who owns it: `id`, `name`, `driveType`,
+    /// `owner`, `sharepointIds` and `webUrl`.
+    ///
+    /// # See also
+    /// [`ObjectOption::select_identity`][select_identity]
+    ///
+    /// [select_identity]: ../option/struct.ObjectOption.html#method.select_identity
+    pub const IDENTITY: &'static [Self] = &[
+        Self::id,
+        Self::name,
+        Self::drive_type,
+        Self::owner,
+        Self::sharepoint_ids,
+        Self::web_url,
+    ];
+}
+
+impl DriveItemField {
+    /// Well-known fields needed to sync items: `id`, `name`, `eTag`, `cTag`, `size`,
+    /// `file` and `folder` facets, `parentReference` and `fileSystemInfo`.
+    ///
+    /// # See also
+    /// [`ObjectOption::select_core`][select_core]
+    ///
+    /// [select_core]: ../option/struct.ObjectOption.html#method.select_core
+    pub const CORE: &'static [Self] = &[
+        Self::id,
+        Self::name,
+        Self::e_tag,
+        Self::c_tag,
+        Self::size,
+        Self::file,
+        Self::folder,
+        Self::parent_reference,
+        Self::file_system_info,
+    ];
+}
+
+impl DriveItem {
+    /// Check if the content of the item has changed since `cached_c_tag` is got,
+    /// by comparing it with [`c_tag`][c_tag].
+    ///
+    /// Metadata-only changes, like renaming or moving, do not change `c_tag`, thus
+    /// re-downloading can be skipped if this returns `false`.
+    ///
+    /// # Note
+    /// If `c_tag` is missing, like being not [selected][select] or for folders
+    /// on some drive types, it is considered changed.
+    ///
+    /// [c_tag]: #structfield.c_tag
+    /// [select]: ../option/struct.ObjectOption.html#method.select
+    pub fn has_content_changed(&self, cached_c_tag: &Tag) -> bool {
+        self.c_tag.as_ref() != Some(cached_c_tag)
+    }
+}
+
+/// The `@odata.context` annotation of a response, like
+/// `https://graph.microsoft.com/v1.0/$metadata#drives('1234')/items/$entity`.
+///
+/// It describes the entity set or type of the response, which is useful to debug
+/// unexpected shapes of responses.
+///
+/// # See also
+/// [OData Docs](https://docs.oasis-open.org/odata/odata/v4.01/odata-v4.01-part1-protocol.html#sec_ContextURL)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct ODataContext(String);
+
+impl ODataContext {
+    /// View the raw context URL.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Get the service root, like `https://graph.microsoft.com/v1.0`.
+    ///
+    /// Return `None` if the context URL is not in the expected format.
+    pub fn service_root(&self) -> Option<&str> {
+        let idx = self.0.find("/$metadata#")?;
+        Some(&self.0[..idx])
+    }
+
+    /// Get the part after `$metadata#`, like `drives('1234')/items/$entity`.
+    ///
+    /// Return `None` if the context URL is not in the expected format.
+    pub fn path(&self) -> Option<&str> {
+        let idx = self.0.find("$metadata#")?;
+        Some(&self.0[idx + "$metadata#".len()..])
+    }
+
+    /// Check if the response is a single entity of an entity set, with `/$entity` suffix.
+    pub fn is_entity(&self) -> bool {
+        self.path().is_some_and(|path| path.ends_with("/$entity"))
+    }
+
+    /// Get the entity type of the response, like `driveItem` or `drive`.
+    ///
+    /// It is derived from the last segment of the path, and well-known
+    /// navigation properties are mapped to their types. Unknown segments are
+    /// returned as is.
+    pub fn entity_type(&self) -> Option<&str> {
+        let path = self.path()?;
+        let path = path
+            .strip_suffix("/$entity")
+            .or_else(|| path.strip_suffix("/$delta"))
+            .unwrap_or(path);
+        if let Some(ty) = path
+            .strip_prefix("Collection(")
+            .and_then(|s| s.strip_suffix(')'))
+        {
+            return Some(ty.trim_start_matches("microsoft.graph."));
+        }
+        let last = path.rsplit('/').next()?;
+        // Strip keys or selected fields, like `items('1234')` or `items(id,name)`.
+        let last = last.split('(').next()?;
+        Some(match last {
+            "items" | "children" | "root" | "special" | "delta" => "driveItem",
+            "drive" | "drives" => "drive",
+            "permissions" => "permission",
+            "thumbnails" => "thumbnailSet",
+            "versions" => "driveItemVersion",
+            "activities" => "itemActivity",
+            "" => return None,
+            other => other.trim_start_matches("microsoft.graph."),
+        })
+    }
+}
+
+impl From<String> for ODataContext {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<ODataContext> for String {
+    fn from(ctx: ODataContext) -> Self {
+        ctx.0
+    }
+}
+
+/// ItemReference resource type
+///
+/// The `ItemReference` resource provides information necessary to address
+/// a [`DriveItem`][drive_item] via the API.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/itemreference?view=graph-rest-1.0)
+///
+/// [drive_item]: ./struct.DriveItem.html
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub struct ItemReference {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drive_id: Option<DriveId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drive_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<ItemId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Path that can be used to navigate to the item, like `/drive/root:/dir`.
+    ///
+    /// It is not returned in results of [`OneDrive::track_root_changes_from_initial`][track]
+    /// and maybe some other APIs.
+    ///
+    /// [track]: ../struct.OneDrive.html#method.track_root_changes_from_initial
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sharepoint_ids: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_id: Option<String>,
+}
+
+impl ItemReference {
+    /// Get the part of [`path`][path] relative to the drive root, like `/dir`,
+    /// or `/` for the root itself.
+    ///
+    /// Return `None` if `path` is missing or in unknown format.
+    ///
+    /// [path]: #structfield.path
+    pub fn root_relative_path(&self) -> Option<&str> {
+        const ROOT: &str = "/root:";
+
+        let path = self.path.as_deref()?;
+        let pos = path.find(ROOT)?;
+        match &path[pos + ROOT.len()..] {
+            "" => Some("/"),
+            rel if rel.starts_with('/') => Some(rel),
+            _ => None,
+        }
+    }
+}
+
+/// IdentitySet resource type
+///
+/// A keyed collection of [`Identity`][identity] resources, representing a set of
+/// identities associated with various events for an item, such as created by or
+/// last modified by. In SharePoint, `group`, `siteUser` and `siteGroup` may also present.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/identityset?view=graph-rest-1.0)
+///
+/// [identity]: ./struct.Identity.html
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub struct IdentitySet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application: Option<Identity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<Identity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<Identity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_group: Option<Identity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_user: Option<Identity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<Identity>,
+}
+
+impl IdentitySet {
+    /// Iterate over all present identities, with users first.
+    pub fn identities(&self) -> impl Iterator<Item = &Identity> + '_ {
+        self.user
+            .iter()
+            .chain(&self.site_user)
+            .chain(&self.group)
+            .chain(&self.site_group)
+            .chain(&self.application)
+            .chain(&self.device)
+    }
+}
+
+/// Identity resource type
+///
+/// An identity of an actor, like a user, device or application.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/identity?view=graph-rest-1.0)
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub struct Identity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// The email address, which is only returned by some services like OneDrive for Business.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+/// SharepointIds resource type
+///
+/// SharePoint REST compatible identifiers of items in SharePoint.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/sharepointids?view=graph-rest-1.0)
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub struct SharepointIds {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_item_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_item_unique_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_url: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_id: Option<String>,
+}
+
+/// Quota resource type
+///
+/// The storage quota of a [`Drive`][drive]. All sizes are in bytes.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/quota?view=graph-rest-1.0)
+///
+/// [drive]: ./struct.Drive.html
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Quota {
+    /// Total space consumed by files in the recycle bin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<i64>,
+    /// Total space remaining before reaching the quota limit.
+    ///
+    /// It is negative if the drive is over quota.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<i64>,
+    /// The state of the quota, one of `normal`, `nearing`, `critical` or `exceeded`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// Total allowed storage space.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    /// Total space used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used: Option<u64>,
+}
+
+/// ThumbnailSet resource type
+///
+/// A keyed collection of [`Thumbnail`][thumbnail] resources in different sizes.
+/// It is returned by [`OneDrive::list_thumbnails`][list_thumbnails] and in
+/// [`DriveItem::thumbnails`][thumbnails] when expanded.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/thumbnailset?view=graph-rest-1.0)
+///
+/// [thumbnail]: ./struct.Thumbnail.html
+/// [list_thumbnails]: ../struct.OneDrive.html#method.list_thumbnails
+/// [thumbnails]: ./struct.DriveItem.html#structfield.thumbnails
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ThumbnailSet {
+    /// The id within the item's thumbnails collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// A 800x800 scaled thumbnail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large: Option<Thumbnail>,
+    /// A 176x176 scaled thumbnail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub medium: Option<Thumbnail>,
+    /// A 48x48 cropped thumbnail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub small: Option<Thumbnail>,
+    /// A thumbnail representing the original image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<Thumbnail>,
+    /// Thumbnails of custom sizes, keyed by the size identifier like `c300x400_crop`.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-list-thumbnails?view=graph-rest-1.0#size-options)
+    #[serde(flatten, deserialize_with = "deserialize_custom_thumbnails")]
+    pub custom: BTreeMap<String, Thumbnail>,
+}
+
+// Skip unknown non-thumbnail fields like `@odata.type`.
+fn deserialize_custom_thumbnails<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Thumbnail>, D::Error> {
+    let map = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    Ok(map
+        .into_iter()
+        .filter(|(key, _)| !key.starts_with('@'))
+        .filter_map(|(key, value)| Some((key, serde_json::from_value(value).ok()?)))
+        .collect())
+}
+
+/// Thumbnail resource type
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/thumbnail?view=graph-rest-1.0)
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Thumbnail {
+    /// The height of the thumbnail, in pixels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// The width of the thumbnail, in pixels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// The URL used to fetch the thumbnail content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+    /// The unique identifier of the item that provided the thumbnail.
+    ///
+    /// This is only available when a folder thumbnail is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_item_id: Option<ItemId>,
+}
+
+/// The Socket.IO endpoint for receiving real-time notifications of a drive.
+///
+/// It is returned by [`OneDrive::get_socket_io_endpoint`][get_endpoint].
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/subscriptions-socketio?view=graph-rest-1.0)
+///
+/// [get_endpoint]: ../struct.OneDrive.html#method.get_socket_io_endpoint
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SocketIoEndpoint {
+    /// An opaque identifier of the subscription.
+    pub id: String,
+    /// The URL to connect with a Socket.IO client.
+    pub notification_url: Url,
+}
+
+/// Subscription resource type
+///
+/// A subscription allows a client app to receive change notifications about changes
+/// to data in Microsoft Graph, through a webhook at [`notification_url`][notification_url].
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/subscription?view=graph-rest-1.0)
+///
+/// [notification_url]: #structfield.notification_url
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Subscription {
+    /// Unique identifier of the subscription.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The resource to be monitored, like `me/drive/root`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// Comma-separated change types to be notified. Only `updated` is supported for drive items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_type: Option<String>,
+    /// The URL of the endpoint receiving notifications. It must use HTTPS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_url: Option<Url>,
+    /// The date and time when the subscription expires.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_date_time: Option<TimestampString>,
+    /// An opaque value sent back in each notification, used to check the origin of notifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_state: Option<String>,
+}
+
+/// A collection of change notifications sent to the subscriber in one request.
+///
+/// This is the body of the POST requests sent to
+/// [`Subscription::notification_url`][notification_url].
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/changenotificationcollection?view=graph-rest-1.0)
+///
+/// [notification_url]: ./struct.Subscription.html#structfield.notification_url
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ChangeNotificationCollection {
+    /// The notifications.
+    pub value: Vec<ChangeNotification>,
+    /// JSON web tokens for validating the notifications, only present for notifications
+    /// with resource data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation_tokens: Option<Vec<String>>,
+}
+
+/// ChangeNotification resource type
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/changenotification?view=graph-rest-1.0)
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub struct ChangeNotification {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_expiration_date_time: Option<TimestampString>,
+    /// The [`Subscription::client_state`][client_state] of the subscription.
+    ///
+    /// [client_state]: ./struct.Subscription.html#structfield.client_state
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_data: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    /// The type of lifecycle notification, like `reauthorizationRequired`, if it is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lifecycle_event: Option<String>,
+}
+
+/// ItemActivity resource type (Beta)
+///
+/// An action performed on an item by a user or an app.
+///
+/// # See also
+/// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/itemactivity?view=graph-rest-beta)
+#[cfg(feature = "beta")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub struct ItemActivity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub action: ItemActionSet,
+    /// The identity set of the user or app performing the action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<IdentitySet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub times: Option<ItemActivityTimeSet>,
+    /// The item the action is performed on. Only present if expanded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drive_item: Option<DriveItem>,
+}
+
+/// The kind of an action in [`ItemActionSet`][action_set]. (Beta)
+///
+/// [action_set]: ./struct.ItemActionSet.html
+#[cfg(feature = "beta")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum ItemActionKind {
+    Comment,
+    Create,
+    Delete,
+    Edit,
+    Mention,
+    Move,
+    Rename,
+    Restore,
+    Share,
+    Version,
+}
+
+/// ItemActionSet resource type (Beta)
+///
+/// Facets of actions in an [`ItemActivity`][activity]. Usually only one of them is present.
+///
+/// # See also
+/// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/itemactionset?view=graph-rest-beta)
+///
+/// [activity]: ./struct.ItemActivity.html
+#[cfg(feature = "beta")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub struct ItemActionSet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<DeleteAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mention: Option<JsonValue>,
+    #[serde(rename = "move", skip_serializing_if = "Option::is_none")]
+    pub move_: Option<MoveAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rename: Option<RenameAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restore: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share: Option<ShareAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<JsonValue>,
+}
+
+#[cfg(feature = "beta")]
+impl ItemActionSet {
+    /// Get kinds of all actions present.
+    pub fn kinds(&self) -> Vec<ItemActionKind> {
+        use ItemActionKind::*;
+        [
+            (Comment, self.comment.is_some()),
+            (Create, self.create.is_some()),
+            (Delete, self.delete.is_some()),
+            (Edit, self.edit.is_some()),
+            (Mention, self.mention.is_some()),
+            (Move, self.move_.is_some()),
+            (Rename, self.rename.is_some()),
+            (Restore, self.restore.is_some()),
+            (Share, self.share.is_some()),
+            (Version, self.version.is_some()),
+        ]
+        .iter()
+        .filter(|(_, present)| *present)
+        .map(|(kind, _)| *kind)
+        .collect()
+    }
+}
+
+/// The `delete` action facet. (Beta)
+///
+/// # See also
+/// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/deleteaction?view=graph-rest-beta)
+#[cfg(feature = "beta")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct DeleteAction {
+    /// The name of the deleted item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The type of the deleted item, `File` or `Folder`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+}
+
+/// The `move` action facet. (Beta)
+///
+/// # See also
+/// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/moveaction?view=graph-rest-beta)
+#[cfg(feature = "beta")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct MoveAction {
+    /// The name of the location the item was moved from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// The name of the location the item was moved to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+/// The `rename` action facet. (Beta)
+///
+/// # See also
+/// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/renameaction?view=graph-rest-beta)
+#[cfg(feature = "beta")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RenameAction {
+    /// The previous name of the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_name: Option<String>,
+}
+
+/// The `share` action facet. (Beta)
+///
+/// # See also
+/// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/shareaction?view=graph-rest-beta)
+#[cfg(feature = "beta")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ShareAction {
+    /// Identity sets of users the item was shared with.
+    #[serde(default)]
+    pub recipients: Vec<IdentitySet>,
+}
+
+/// ItemActivityTimeSet resource type (Beta)
+///
+/// # See also
+/// [Microsoft Docs Beta](https://docs.microsoft.com/en-us/graph/api/resources/itemactivitytimeset?view=graph-rest-beta)
+#[cfg(feature = "beta")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ItemActivityTimeSet {
+    /// When the activity was observed to take place.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_date_time: Option<TimestampString>,
+    /// When the activity was recorded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_date_time: Option<TimestampString>,
+}
+
 /// The error resource type, returned whenever an error occurs in the processing of a request.
 ///
 /// Error responses follow the definition in the OData v4 specification for error responses.
@@ -278,7 +972,8 @@ define_resource_object! {
 /// **This struct is independent with [`OAuth2ErrorResponse`][oauth2_error_response] from OAuth2 API.**
 ///
 /// It may be contained in [`onedrive_api::Error`][error] returned by storage API
-/// (methods of [`OneDrive`][one_drive], [`ListChildrenFetcher`][list_children_fetcher], etc.).
+/// (methods of [`OneDrive`][one_drive], [`ListChildrenFetcher`][list_children_fetcher], etc.),
+/// or reported by a failed asynchronous operation.
 ///
 /// # See also
 /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/errors#error-resource-type)
@@ -287,7 +982,7 @@ define_resource_object! {
 /// [error]: ../struct.Error.html
 /// [one_drive]: ../struct.OneDrive.html
 /// [list_children_fetcher]: ../struct.ListChildrenFetcher.html
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
 #[non_exhaustive]
 pub struct ErrorResponse {
     /// OData `code`. Non-exhaustive.
@@ -298,12 +993,53 @@ pub struct ErrorResponse {
     /// - And maybe more.
     pub code: String,
     /// OData `message`. Usually to be human-readable.
+    ///
+    /// It is empty if not provided, eg. for errors of async operations.
+    #[serde(default)]
     pub message: String,
     /// OData `innererror`. An optional object with additional or more specific error codes.
     #[serde(rename = "innererror")]
     pub inner_error: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
+impl ErrorResponse {
+    /// Iterate over the chain of nested `innererror` objects, from the outermost one.
+    pub fn inner_errors(
+        &self,
+    ) -> impl Iterator<Item = &serde_json::Map<String, serde_json::Value>> + '_ {
+        std::iter::successors(self.inner_error.as_ref(), |inner| {
+            inner.get("innererror")?.as_object()
+        })
+    }
+
+    /// Iterate over all error codes, that is, [`code`][code] followed by `code` of
+    /// each [inner error][inner_errors] from the outermost one.
+    ///
+    /// [code]: #structfield.code
+    /// [inner_errors]: #method.inner_errors
+    pub fn codes(&self) -> impl Iterator<Item = &str> + '_ {
+        std::iter::once(&*self.code).chain(
+            self.inner_errors()
+                .filter_map(|inner| inner.get("code")?.as_str()),
+        )
+    }
+
+    /// Get the most specific error code, that is, the code of the innermost error having one.
+    pub fn innermost_code(&self) -> &str {
+        self.codes().last().unwrap_or(&self.code)
+    }
+
+    /// Check if `code` is the code of this error or any of its inner errors.
+    ///
+    /// Critical signals are usually nested, eg. `resyncChangesApplyDifferences`
+    /// in errors of [Track Changes API][track_changes] requiring a resync.
+    ///
+    /// [track_changes]: ../struct.OneDrive.html#method.track_changes_from_delta_url
+    pub fn has_code(&self, code: &str) -> bool {
+        self.codes().any(|c| c == code)
+    }
+}
+
 /// OAuth2 error response.
 ///
 /// **This struct is independent with [`ErrorResponse`][error_response] from storage API.**
@@ -356,4 +1092,213 @@ mod tests {
         assert_eq!(DriveItemField::web_dav_url.raw_name(), "webDavUrl");
         assert_eq!(DriveItemField::web_url.raw_name(), "webUrl");
     }
+
+    #[test]
+    fn test_drive_identity() {
+        let drive: Drive = serde_json::from_value(serde_json::json!({
+            "id": "b!abc",
+            "owner": {
+                "user": { "id": "1234", "displayName": "Alice", "email": "alice@example.com" },
+                "application": { "id": "app" },
+            },
+            "sharepointIds": { "siteId": "site", "tenantId": "tenant", "webId": "web" },
+            "quota": { "remaining": -1024, "state": "exceeded", "total": 4096, "used": 5120 },
+        }))
+        .unwrap();
+        let owner = drive.owner.as_ref().unwrap();
+        assert_eq!(
+            owner
+                .identities()
+                .filter_map(|id| id.id.as_deref())
+                .collect::<Vec<_>>(),
+            ["1234", "app"],
+        );
+        assert_eq!(
+            owner.user.as_ref().unwrap().display_name.as_deref(),
+            Some("Alice")
+        );
+        let ids = drive.sharepoint_ids.as_ref().unwrap();
+        assert_eq!(ids.site_id.as_deref(), Some("site"));
+        assert_eq!(ids.tenant_id.as_deref(), Some("tenant"));
+        assert_eq!(ids.list_id, None);
+        // Over quota.
+        assert_eq!(drive.quota.unwrap().remaining, Some(-1024));
+    }
+
+    #[test]
+    fn test_has_content_changed() {
+        let cached = Tag("\"c:{1234},1\"".to_owned());
+        let mut item = DriveItem::default();
+        assert!(item.has_content_changed(&cached));
+        item.c_tag = Some(cached.clone());
+        assert!(!item.has_content_changed(&cached));
+        item.c_tag = Some(Tag("\"c:{1234},2\"".to_owned()));
+        assert!(item.has_content_changed(&cached));
+    }
+
+    #[test]
+    fn test_odata_context() {
+        let cases = [
+            (
+                "https://graph.microsoft.com/v1.0/$metadata#drives('1234')/items/$entity",
+                Some("driveItem"),
+                true,
+            ),
+            (
+                "https://graph.microsoft.com/v1.0/$metadata#users('a%40b.com')/drive/root/children",
+                Some("driveItem"),
+                false,
+            ),
+            (
+                "https://graph.microsoft.com/v1.0/$metadata#drives/$entity",
+                Some("drive"),
+                true,
+            ),
+            (
+                "https://graph.microsoft.com/v1.0/$metadata#Collection(microsoft.graph.driveItem)",
+                Some("driveItem"),
+                false,
+            ),
+            (
+                "https://graph.microsoft.com/v1.0/$metadata#drives('1234')/items(id,name)/$entity",
+                Some("driveItem"),
+                true,
+            ),
+            ("invalid", None, false),
+        ];
+        for &(s, ty, is_entity) in &cases {
+            let ctx = ODataContext::from(s.to_owned());
+            assert_eq!(ctx.entity_type(), ty, "{}", s);
+            assert_eq!(ctx.is_entity(), is_entity, "{}", s);
+        }
+
+        let item: DriveItem = serde_json::from_str(
+            r#"{ "@odata.context": "https://graph.microsoft.com/v1.0/$metadata#drives('1')/items/$entity", "id": "2" }"#,
+        )
+        .unwrap();
+        let ctx = item.odata_context.unwrap();
+        assert_eq!(ctx.service_root(), Some("https://graph.microsoft.com/v1.0"));
+        assert_eq!(ctx.path(), Some("drives('1')/items/$entity"));
+    }
+
+    #[test]
+    fn test_root_relative_path() {
+        let cases = [
+            ("/drive/root:", Some("/")),
+            ("/drive/root:/a/b c", Some("/a/b c")),
+            ("/drives/1234/root:/a", Some("/a")),
+            ("/drive/items/1234", None),
+            ("/drive/root:a", None),
+        ];
+        for &(path, expect) in &cases {
+            let item_ref = ItemReference {
+                path: Some(path.to_owned()),
+                ..Default::default()
+            };
+            assert_eq!(item_ref.root_relative_path(), expect, "{}", path);
+        }
+        assert_eq!(ItemReference::default().root_relative_path(), None);
+    }
+
+    #[test]
+    fn test_thumbnail_set() {
+        let set: ThumbnailSet = serde_json::from_str(
+            r##"{
+                "id": "0",
+                "@odata.type": "#microsoft.graph.thumbnailSet",
+                "small": { "height": 48, "width": 48, "url": "https://example.com/small" },
+                "c300x400_crop": { "height": 400, "width": 300, "url": "https://example.com/c" }
+            }"##,
+        )
+        .unwrap();
+        assert_eq!(set.id.as_deref(), Some("0"));
+        let small = set.small.as_ref().unwrap();
+        assert_eq!((small.width, small.height), (Some(48), Some(48)));
+        assert!(set.large.is_none());
+        assert_eq!(set.custom.len(), 1);
+        assert_eq!(set.custom["c300x400_crop"].width, Some(300));
+    }
+
+    #[test]
+    fn test_change_notification_collection() {
+        let notifications: ChangeNotificationCollection = serde_json::from_str(
+            r#"{
+                "value": [
+                    {
+                        "subscriptionId": "7f105c7d-2dc5-4530-97cd-4e7ae6534c07",
+                        "subscriptionExpirationDateTime": "2016-03-19T22:11:09.952Z",
+                        "clientState": "secretClientValue",
+                        "changeType": "updated",
+                        "resource": "me/drive/root",
+                        "tenantId": "bb8775a4-4d8c-42cf-a1d4-4d58c2bb668f"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(notifications.validation_tokens, None);
+        assert_eq!(notifications.value.len(), 1);
+        let notification = &notifications.value[0];
+        assert_eq!(
+            notification.client_state.as_deref(),
+            Some("secretClientValue"),
+        );
+        assert_eq!(notification.change_type.as_deref(), Some("updated"));
+        assert_eq!(notification.resource.as_deref(), Some("me/drive/root"));
+    }
+
+    #[test]
+    fn test_error_code_chain() {
+        let resp: ErrorResponse = serde_json::from_str(
+            r#"{
+                "code": "resyncRequired",
+                "message": "Resync required",
+                "innererror": {
+                    "code": "resyncChangesApplyDifferences",
+                    "innererror": { "innererror": { "code": "innermost" } }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(resp.inner_errors().count(), 3);
+        assert_eq!(
+            resp.codes().collect::<Vec<_>>(),
+            [
+                "resyncRequired",
+                "resyncChangesApplyDifferences",
+                "innermost"
+            ],
+        );
+        assert_eq!(resp.innermost_code(), "innermost");
+        assert!(resp.has_code("resyncChangesApplyDifferences"));
+        assert!(!resp.has_code("resyncChangesUploadDifferences"));
+
+        let resp: ErrorResponse =
+            serde_json::from_str(r#"{ "code": "itemNotFound", "message": "" }"#).unwrap();
+        assert_eq!(resp.inner_errors().count(), 0);
+        assert_eq!(resp.innermost_code(), "itemNotFound");
+    }
+
+    #[cfg(feature = "beta")]
+    #[test]
+    fn test_item_activity() {
+        let activity: ItemActivity = serde_json::from_str(
+            r#"{
+                "id": "1",
+                "action": { "move": { "from": "Documents", "to": "Archive" } },
+                "actor": { "user": { "displayName": "Alice" } },
+                "times": { "recordedDateTime": "2020-01-01T00:00:00Z" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(activity.action.kinds(), [ItemActionKind::Move]);
+        let user = activity.actor.unwrap().user.unwrap();
+        assert_eq!(user.display_name.as_deref(), Some("Alice"));
+        let move_ = activity.action.move_.as_ref().unwrap();
+        assert_eq!(move_.to.as_deref(), Some("Archive"));
+        assert_eq!(
+            activity.times.unwrap().recorded_date_time.as_deref(),
+            Some("2020-01-01T00:00:00Z"),
+        );
+    }
 }
@@ -0,0 +1,258 @@
+//! Types for searching drive items through the Microsoft Search API.
+//!
+//! Compared with the legacy `search(q=)` on a drive, it provides hit highlights,
+//! aggregations (facets) and ranking across all drives of the tenant.
+//! Queries are sent through [`OneDrive::search_query`][search_query].
+//!
+//! # Note
+//! Microsoft Search API is only available for work or school accounts.
+//! It is not supported on personal OneDrive.
+//!
+//! # See also
+//! [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/search-api-overview?view=graph-rest-1.0)
+//!
+//! [search_query]: ../struct.OneDrive.html#method.search_query
+use crate::resource::DriveItem;
+use serde::{Deserialize, Serialize};
+
+/// A search query for drive items.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/searchrequest?view=graph-rest-1.0)
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    entity_types: [&'static str; 1],
+    query: QueryString,
+    from: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aggregations: Vec<AggregationOption>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aggregation_filters: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryString {
+    query_string: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AggregationOption {
+    field: String,
+    size: u32,
+}
+
+impl SearchQuery {
+    /// Create a query with [KQL][kql] query string, like `contoso filetype:docx`.
+    ///
+    /// [kql]: https://docs.microsoft.com/en-us/sharepoint/dev/general-development/keyword-query-language-kql-syntax-reference
+    pub fn new(query_string: impl Into<String>) -> Self {
+        Self {
+            entity_types: ["driveItem"],
+            query: QueryString {
+                query_string: query_string.into(),
+            },
+            from: 0,
+            size: None,
+            fields: Vec::new(),
+            aggregations: Vec::new(),
+            aggregation_filters: Vec::new(),
+        }
+    }
+
+    /// Set the 0-based offset of the first result, used for paging.
+    ///
+    /// Default to be 0.
+    pub fn from(mut self, from: u64) -> Self {
+        self.from = from;
+        self
+    }
+
+    /// Set the maximum number of results in a page.
+    ///
+    /// Default to be decided by the server.
+    pub fn size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Select properties of drive items to be returned.
+    ///
+    /// If called more than once, all fields mentioned will be selected.
+    /// `fields` should be raw camelCase field names.
+    pub fn fields(mut self, fields: &[&str]) -> Self {
+        self.fields.extend(fields.iter().map(|s| (*s).to_owned()));
+        self
+    }
+
+    /// Request an aggregation (facet) on a refinable field, like `FileType`,
+    /// with at most `size` buckets.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/search-concept-aggregation)
+    pub fn aggregation(mut self, field: &str, size: u32) -> Self {
+        self.aggregations.push(AggregationOption {
+            field: field.to_owned(),
+            size,
+        });
+        self
+    }
+
+    /// Only return results in the bucket of a previous aggregation, by its
+    /// [`aggregation_filter_token`][token].
+    ///
+    /// If called more than once, results should match all filters.
+    ///
+    /// # See also
+    /// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/search-concept-aggregation#refine-results-by-aggregation-buckets)
+    ///
+    /// [token]: ./struct.SearchBucket.html#structfield.aggregation_filter_token
+    pub fn aggregation_filter(mut self, token: impl Into<String>) -> Self {
+        self.aggregation_filters.push(token.into());
+        self
+    }
+
+    /// Get the offset of the first result.
+    pub fn get_from(&self) -> u64 {
+        self.from
+    }
+
+    /// Get the query for the next page following `response` of this query,
+    /// or `None` if there are no more results.
+    pub fn next_page(&self, response: &SearchResponse) -> Option<Self> {
+        if !response.more_results_available || response.hits.is_empty() {
+            return None;
+        }
+        Some(self.clone().from(self.from + response.hits.len() as u64))
+    }
+}
+
+/// A page of search results.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/searchhitscontainer?view=graph-rest-1.0)
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SearchResponse {
+    /// Search results in this page.
+    #[serde(default)]
+    pub hits: Vec<SearchHit>,
+    /// The total number of results. It is an estimation and may be inaccurate.
+    #[serde(default)]
+    pub total: u64,
+    /// Whether there are more results after this page.
+    #[serde(default)]
+    pub more_results_available: bool,
+    /// Results of requested aggregations.
+    #[serde(default)]
+    pub aggregations: Vec<SearchAggregation>,
+}
+
+/// A single search result.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/searchhit?view=graph-rest-1.0)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SearchHit {
+    /// The internal identifier of the hit.
+    pub hit_id: Option<String>,
+    /// The rank of the result, starting from 1.
+    pub rank: Option<u32>,
+    /// A summary of the result with matched terms highlighted by `<c0>` and `</c0>`.
+    pub summary: Option<String>,
+    /// The matched drive item.
+    pub resource: DriveItem,
+}
+
+/// The result of an aggregation.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/searchaggregation?view=graph-rest-1.0)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SearchAggregation {
+    /// The field aggregated on.
+    pub field: String,
+    /// Buckets of the aggregation.
+    #[serde(default)]
+    pub buckets: Vec<SearchBucket>,
+}
+
+/// A bucket of an aggregation.
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/resources/searchbucket?view=graph-rest-1.0)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SearchBucket {
+    /// The value of the field.
+    pub key: String,
+    /// The approximate number of results in this bucket.
+    pub count: u64,
+    /// A token to filter results by this bucket in later queries,
+    /// through [`SearchQuery::aggregation_filter`][filter].
+    ///
+    /// [filter]: ./struct.SearchQuery.html#method.aggregation_filter
+    pub aggregation_filter_token: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_serialize() {
+        let query = SearchQuery::new("contoso")
+            .size(10)
+            .fields(&["name", "size"])
+            .aggregation("FileType", 5)
+            .aggregation_filter("FileType:\"docx\"");
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            serde_json::json!({
+                "entityTypes": ["driveItem"],
+                "query": { "queryString": "contoso" },
+                "from": 0,
+                "size": 10,
+                "fields": ["name", "size"],
+                "aggregations": [{ "field": "FileType", "size": 5 }],
+                "aggregationFilters": ["FileType:\"docx\""],
+            }),
+        );
+    }
+
+    #[test]
+    fn test_next_page() {
+        let resp: SearchResponse = serde_json::from_value(serde_json::json!({
+            "hits": [
+                {
+                    "hitId": "1",
+                    "rank": 1,
+                    "summary": "<c0>contoso</c0> report",
+                    "resource": { "name": "report.docx" },
+                },
+            ],
+            "total": 2,
+            "moreResultsAvailable": true,
+        }))
+        .unwrap();
+        assert_eq!(resp.hits[0].resource.name.as_deref(), Some("report.docx"));
+
+        let query = SearchQuery::new("contoso").from(3);
+        assert_eq!(query.next_page(&resp).unwrap().get_from(), 4);
+
+        let resp = SearchResponse::default();
+        assert!(query.next_page(&resp).is_none());
+    }
+}
@@ -0,0 +1,652 @@
+use crate::{
+    auth::{Auth, TokenResponse},
+    clock::{Clock, Sleeper, SystemClock, TokioSleeper},
+    error::{Error, Result},
+};
+use std::{
+    convert::Infallible,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+/// An access token shared between [`OneDrive`][one_drive] instances and
+/// [`TokenRefresher`][refresher].
+///
+/// Cloning it is cheap, and all clones refer to the same token.
+/// When the token is replaced by [`set`][set], all subsequent requests of every
+/// [`OneDrive`][one_drive] using it will use the new one.
+///
+/// [one_drive]: ./struct.OneDrive.html
+/// [refresher]: ./struct.TokenRefresher.html
+/// [set]: #method.set
+#[derive(Clone)]
+pub struct SharedToken {
+    inner: Arc<RwLock<String>>,
+}
+
+// Avoid leaking the token in logs.
+impl fmt::Debug for SharedToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SharedToken(..)")
+    }
+}
+
+impl SharedToken {
+    /// Create a new shared token with an initial access token.
+    pub fn new(access_token: String) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(access_token)),
+        }
+    }
+
+    /// Get the current access token.
+    pub fn get(&self) -> String {
+        // The lock is never poisoned since nothing panics when holding it.
+        self.inner.read().unwrap().clone()
+    }
+
+    /// Replace the access token atomically.
+    pub fn set(&self, access_token: String) {
+        *self.inner.write().unwrap() = access_token;
+    }
+}
+
+impl From<String> for SharedToken {
+    fn from(access_token: String) -> Self {
+        Self::new(access_token)
+    }
+}
+
+type RefreshCallback = Box<dyn FnMut(&TokenResponse) + Send>;
+
+/// A refresher to renew the access token before it expires.
+///
+/// It is designed for long-lived services. The future returned by [`run`][run] can be spawned
+/// as a background task, and all [`OneDrive`][one_drive] instances created with
+/// [`shared_token`][shared_token] will always use a fresh token.
+///
+/// # Example
+/// ```no_run
+/// use onedrive_api::{Auth, DriveLocation, OneDrive, TokenRefresher};
+///
+/// # async fn run(auth: Auth, refresh_token: &str) -> onedrive_api::Result<()> {
+/// let token = auth.login_with_refresh_token(refresh_token, None).await?;
+/// let refresher = TokenRefresher::new(auth, &token, None);
+/// let drive = OneDrive::new(refresher.shared_token(), DriveLocation::me());
+/// let refresh_task = refresher.run();
+/// // Spawn `refresh_task` in background with your runtime,
+/// // then use `drive` as long as you want.
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [run]: #method.run
+/// [shared_token]: #method.shared_token
+/// [one_drive]: ./struct.OneDrive.html
+pub struct TokenRefresher {
+    auth: Auth,
+    client_secret: Option<String>,
+    refresh_token: String,
+    token: SharedToken,
+    expires_at: SystemTime,
+    // The whole lifetime of the current access token.
+    lifetime: Duration,
+    margin: Duration,
+    on_refresh: Option<RefreshCallback>,
+    store: Option<Arc<dyn TokenStore>>,
+    clock: Box<dyn Clock>,
+    sleeper: Box<dyn Sleeper>,
+}
+
+impl fmt::Debug for TokenRefresher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TokenRefresher")
+            .field("auth", &self.auth)
+            .field("token", &self.token)
+            .field("expires_at", &self.expires_at)
+            .field("lifetime", &self.lifetime)
+            .field("margin", &self.margin)
+            .field("store", &self.store)
+            .field("clock", &self.clock)
+            .field("sleeper", &self.sleeper)
+            .finish()
+    }
+}
+
+impl TokenRefresher {
+    /// The default time before the expiration to refresh the token.
+    pub const DEFAULT_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+    /// The time to wait before retrying when refreshing failed due to network errors.
+    pub const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+    /// Create a refresher from the response of a previous login.
+    ///
+    /// `client_secret` is passed to [`Auth::login_with_refresh_token`][login] when refreshing.
+    ///
+    /// # Panic
+    /// Panic if `token` contains no [`refresh_token`][refresh_token].
+    ///
+    /// [login]: ./struct.Auth.html#method.login_with_refresh_token
+    /// [refresh_token]: ./struct.TokenResponse.html#structfield.refresh_token
+    pub fn new(auth: Auth, token: &TokenResponse, client_secret: Option<String>) -> Self {
+        let refresh_token = token
+            .refresh_token
+            .clone()
+            .expect("Missing `refresh_token` for refreshing");
+        Self {
+            auth,
+            client_secret,
+            refresh_token,
+            token: SharedToken::new(token.access_token.clone()),
+            expires_at: SystemTime::now() + Duration::from_secs(token.expires_in_secs),
+            lifetime: Duration::from_secs(token.expires_in_secs),
+            margin: Self::DEFAULT_MARGIN,
+            on_refresh: None,
+            store: None,
+            clock: Box::new(SystemClock),
+            sleeper: Box::new(TokioSleeper),
+        }
+    }
+
+    /// Set the time before the expiration to refresh the token.
+    ///
+    /// It is clamped to half of the lifetime of the current token, so a token living
+    /// shorter than the margin is not refreshed again immediately after each refresh.
+    ///
+    /// Default to be [`DEFAULT_MARGIN`][default].
+    ///
+    /// [default]: #associatedconstant.DEFAULT_MARGIN
+    pub fn with_margin(mut self, margin: Duration) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Set a callback called after every successful refresh.
+    ///
+    /// Since a new refresh token is returned in each refresh,
+    /// it can be used to persist the latest refresh token.
+    pub fn with_on_refresh(mut self, f: impl FnMut(&TokenResponse) + Send + 'static) -> Self {
+        self.on_refresh = Some(Box::new(f));
+        self
+    }
+
+    /// Set a store to persist the latest refresh token after every successful refresh.
+    ///
+    /// # See also
+    /// [`TokenStore`][store]
+    ///
+    /// [store]: ./trait.TokenStore.html
+    pub fn with_store(mut self, store: impl TokenStore + 'static) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
+    /// Set the clock used to decide when to refresh.
+    ///
+    /// The remaining lifetime of the current token is kept, measured by the new clock.
+    ///
+    /// Default to be [`SystemClock`][system_clock].
+    ///
+    /// [system_clock]: ./struct.SystemClock.html
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        let remaining = self
+            .expires_at
+            .duration_since(self.clock.now())
+            .unwrap_or_default();
+        self.clock = Box::new(clock);
+        self.expires_at = self.clock.now() + remaining;
+        self
+    }
+
+    /// Set the timer used to wait between refreshes in [`run`][run].
+    ///
+    /// Default to be [`TokioSleeper`][tokio_sleeper].
+    ///
+    /// [run]: #method.run
+    /// [tokio_sleeper]: ./struct.TokioSleeper.html
+    pub fn with_sleeper(mut self, sleeper: impl Sleeper + 'static) -> Self {
+        self.sleeper = Box::new(sleeper);
+        self
+    }
+
+    /// Get the time when the current access token expires, according to the clock.
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+
+    /// Get the time to wait before the next refresh, according to the clock.
+    ///
+    /// It is zero if the token is already in the margin before the expiration.
+    pub fn next_refresh_in(&self) -> Duration {
+        let margin = self.margin.min(self.lifetime / 2);
+        let refresh_at = self
+            .expires_at
+            .checked_sub(margin)
+            .unwrap_or(self.expires_at);
+        refresh_at
+            .duration_since(self.clock.now())
+            .unwrap_or_default()
+    }
+
+    /// Get the token shared with [`OneDrive`][one_drive] instances.
+    ///
+    /// [one_drive]: ./struct.OneDrive.html
+    pub fn shared_token(&self) -> SharedToken {
+        self.token.clone()
+    }
+
+    /// Get the latest refresh token.
+    pub fn refresh_token(&self) -> &str {
+        &self.refresh_token
+    }
+
+    /// Refresh the access token immediately.
+    ///
+    /// The new token is used and the [callback][on_refresh] is called before saving
+    /// the new refresh token to the [store][store]. Saving runs on the blocking thread pool
+    /// of the current tokio runtime, if any.
+    ///
+    /// # Errors
+    /// Will return `Err` with [`Error::is_token_store_error`][store_err] being `true` if
+    /// the token is refreshed but saving to the store fails. The refresher is still updated
+    /// and can be used as usual.
+    ///
+    /// [on_refresh]: #method.with_on_refresh
+    /// [store]: #method.with_store
+    /// [store_err]: ./struct.Error.html#method.is_token_store_error
+    pub async fn refresh(&mut self) -> Result<()> {
+        let resp = self
+            .auth
+            .login_with_refresh_token(&self.refresh_token, self.client_secret.as_deref())
+            .await?;
+        self.token.set(resp.access_token.clone());
+        self.expires_at = self.clock.now() + Duration::from_secs(resp.expires_in_secs);
+        self.lifetime = Duration::from_secs(resp.expires_in_secs);
+        if let Some(refresh_token) = &resp.refresh_token {
+            self.refresh_token = refresh_token.clone();
+        }
+        if let Some(f) = &mut self.on_refresh {
+            f(&resp);
+        }
+        if let (Some(store), Some(refresh_token)) = (&self.store, resp.refresh_token) {
+            let store = store.clone();
+            let save = move || store.save(&refresh_token);
+            let ret = match tokio::runtime::Handle::try_current() {
+                Ok(handle) => handle
+                    .spawn_blocking(save)
+                    .await
+                    .unwrap_or_else(|err| Err(io::Error::other(err))),
+                Err(_) => save(),
+            };
+            ret.map_err(Error::from_token_store)?;
+        }
+        Ok(())
+    }
+
+    /// Keep refreshing the access token before it expires.
+    ///
+    /// Network errors are retried after [`RETRY_INTERVAL`][retry]. Failures of saving
+    /// to the store are logged and do not stop refreshing. It returns only
+    /// if the refresh is rejected by the server (eg. the refresh token is revoked).
+    ///
+    /// [retry]: #associatedconstant.RETRY_INTERVAL
+    pub async fn run(mut self) -> Result<Infallible> {
+        loop {
+            self.sleeper.sleep(self.next_refresh_in()).await;
+            match self.refresh().await {
+                Ok(()) => {}
+                Err(err) if err.is_token_store_error() => {
+                    log::warn!("Failed to save the refresh token: {}", err);
+                }
+                Err(err) if is_rejected(&err) => return Err(err),
+                Err(_) => self.sleeper.sleep(Self::RETRY_INTERVAL).await,
+            }
+        }
+    }
+}
+
+fn is_rejected(err: &Error) -> bool {
+    err.oauth2_error_response().is_some()
+}
+
+/// A persistent storage of refresh tokens.
+///
+/// Implementations should never keep refresh tokens in plaintext on disk.
+/// [`FileTokenStore`][file_store] encrypts them with a [`TokenCipher`][cipher],
+/// while stores backed by the OS keychain can save them directly.
+///
+/// [file_store]: ./struct.FileTokenStore.html
+/// [cipher]: ./trait.TokenCipher.html
+pub trait TokenStore: fmt::Debug + Send + Sync {
+    /// Load the saved refresh token, or `None` if nothing is saved.
+    fn load(&self) -> io::Result<Option<String>>;
+
+    /// Save the refresh token, replacing the previous one.
+    fn save(&self, refresh_token: &str) -> io::Result<()>;
+}
+
+/// An encryption hook used by [`FileTokenStore`][file_store].
+///
+/// # Example
+/// A cipher using a key kept in the OS keychain (via the `keyring` crate).
+#[cfg_attr(feature = "token-encryption", doc = "```")]
+#[cfg_attr(not(feature = "token-encryption"), doc = "```ignore")]
+/// # mod keyring {
+/// #     #[derive(Debug)]
+/// #     pub struct Entry;
+/// #     impl Entry {
+/// #         pub fn get_password(&self) -> std::io::Result<String> {
+/// #             unimplemented!()
+/// #         }
+/// #     }
+/// # }
+/// use onedrive_api::{AesGcmCipher, TokenCipher};
+///
+/// #[derive(Debug)]
+/// struct KeychainCipher(keyring::Entry);
+///
+/// impl KeychainCipher {
+///     fn cipher(&self) -> std::io::Result<AesGcmCipher> {
+///         let key = self.0.get_password().map_err(|err| std::io::Error::other(err))?;
+///         let key = base64::decode(key).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+///         Ok(AesGcmCipher::new(&key).expect("Invalid key length"))
+///     }
+/// }
+///
+/// impl TokenCipher for KeychainCipher {
+///     fn encrypt(&self, plaintext: &[u8]) -> std::io::Result<Vec<u8>> {
+///         self.cipher()?.encrypt(plaintext)
+///     }
+///
+///     fn decrypt(&self, ciphertext: &[u8]) -> std::io::Result<Vec<u8>> {
+///         self.cipher()?.decrypt(ciphertext)
+///     }
+/// }
+/// ```
+///
+/// On Windows, `CryptProtectData`/`CryptUnprotectData` from DPAPI can be wrapped the same way.
+///
+/// [file_store]: ./struct.FileTokenStore.html
+pub trait TokenCipher: fmt::Debug + Send + Sync {
+    /// Encrypt the plaintext.
+    fn encrypt(&self, plaintext: &[u8]) -> io::Result<Vec<u8>>;
+
+    /// Decrypt the ciphertext produced by [`encrypt`][encrypt].
+    ///
+    /// [encrypt]: #tymethod.encrypt
+    fn decrypt(&self, ciphertext: &[u8]) -> io::Result<Vec<u8>>;
+}
+
+/// A [`TokenStore`][store] saving the encrypted refresh token in a file.
+///
+/// [store]: ./trait.TokenStore.html
+#[derive(Debug)]
+pub struct FileTokenStore<C> {
+    path: PathBuf,
+    cipher: C,
+}
+
+impl<C: TokenCipher> FileTokenStore<C> {
+    /// Create a store saving to `path`, encrypted by `cipher`.
+    pub fn new(path: impl Into<PathBuf>, cipher: C) -> Self {
+        Self {
+            path: path.into(),
+            cipher,
+        }
+    }
+
+    /// Get the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<C: TokenCipher> TokenStore for FileTokenStore<C> {
+    fn load(&self) -> io::Result<Option<String>> {
+        let ciphertext = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let plaintext = self.cipher.decrypt(&ciphertext)?;
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    // Write to a new temporary file readable only by the owner first, and then rename it,
+    // so the file is never left partially written. A stale temporary file left by a crash
+    // is removed rather than reused, since it may have looser permissions.
+    fn save(&self, refresh_token: &str) -> io::Result<()> {
+        use io::Write as _;
+
+        let ciphertext = self.cipher.encrypt(refresh_token.as_bytes())?;
+        let mut temp_name = self.path.file_name().unwrap_or_default().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = self.path.with_file_name(temp_name);
+
+        match fs::remove_file(&temp_path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let ret = options.open(&temp_path).and_then(|mut file| {
+            file.write_all(&ciphertext)?;
+            file.sync_all()
+        });
+        match ret.and_then(|()| fs::rename(&temp_path, &self.path)) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                let _ = fs::remove_file(&temp_path);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// A [`TokenCipher`][cipher] using AES-256-GCM.
+///
+/// A random nonce is generated for each encryption and prepended to the ciphertext.
+///
+/// [cipher]: ./trait.TokenCipher.html
+#[cfg(feature = "token-encryption")]
+pub struct AesGcmCipher {
+    key: ring::aead::LessSafeKey,
+    rng: ring::rand::SystemRandom,
+}
+
+#[cfg(feature = "token-encryption")]
+impl fmt::Debug for AesGcmCipher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("AesGcmCipher(..)")
+    }
+}
+
+#[cfg(feature = "token-encryption")]
+impl AesGcmCipher {
+    /// The length of the key in bytes.
+    pub const KEY_LEN: usize = 32;
+
+    /// Create a cipher with the key given.
+    ///
+    /// Return `None` if the length of `key` is not [`KEY_LEN`][key_len].
+    ///
+    /// [key_len]: #associatedconstant.KEY_LEN
+    pub fn new(key: &[u8]) -> Option<Self> {
+        let key = ring::aead::UnboundKey::new(&ring::aead::AES_256_GCM, key).ok()?;
+        Some(Self {
+            key: ring::aead::LessSafeKey::new(key),
+            rng: ring::rand::SystemRandom::new(),
+        })
+    }
+}
+
+#[cfg(feature = "token-encryption")]
+impl TokenCipher for AesGcmCipher {
+    fn encrypt(&self, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+        use ring::{aead, rand::SecureRandom as _};
+
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| io::Error::other("Failed to generate nonce"))?;
+        let mut buf = plaintext.to_owned();
+        self.key
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut buf,
+            )
+            .map_err(|_| io::Error::other("Failed to encrypt"))?;
+        let mut ret = nonce.to_vec();
+        ret.extend_from_slice(&buf);
+        Ok(ret)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> io::Result<Vec<u8>> {
+        use ring::aead;
+
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Failed to decrypt");
+        if ciphertext.len() < aead::NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, data) = ciphertext.split_at(aead::NONCE_LEN);
+        let nonce = aead::Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+        let mut buf = data.to_owned();
+        let len = self
+            .key
+            .open_in_place(nonce, aead::Aad::empty(), &mut buf)
+            .map_err(|_| invalid())?
+            .len();
+        buf.truncate(len);
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct XorCipher(u8);
+
+    impl TokenCipher for XorCipher {
+        fn encrypt(&self, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+            Ok(plaintext.iter().map(|b| b ^ self.0).collect())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> io::Result<Vec<u8>> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    #[derive(Debug)]
+    struct FakeClock(RwLock<SystemTime>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> SystemTime {
+            *self.0.read().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_refresher_timing() {
+        let token: TokenResponse = serde_json::from_value(serde_json::json!({
+            "token_type": "Bearer",
+            "scope": "Files.Read",
+            "expires_in": 3600,
+            "access_token": "access-token",
+            "refresh_token": "refresh-token",
+        }))
+        .unwrap();
+        let auth = Auth::new(
+            "client-id".to_owned(),
+            crate::Permission::new_read(),
+            "https://example.com".to_owned(),
+        );
+        let start = std::time::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let clock = Arc::new(FakeClock(RwLock::new(start)));
+        let refresher = TokenRefresher::new(auth, &token, None)
+            .with_margin(Duration::from_secs(600))
+            .with_clock(clock.clone());
+
+        let expires_at = refresher.expires_at();
+        assert!(expires_at <= start + Duration::from_secs(3600));
+        assert!(expires_at > start + Duration::from_secs(3500));
+        assert_eq!(
+            refresher.next_refresh_in(),
+            expires_at.duration_since(start).unwrap() - Duration::from_secs(600),
+        );
+
+        *clock.0.write().unwrap() = expires_at - Duration::from_secs(60);
+        assert_eq!(refresher.next_refresh_in(), Duration::from_secs(0));
+
+        // The margin is clamped to half of the lifetime.
+        let refresher = refresher.with_margin(Duration::from_secs(7200));
+        *clock.0.write().unwrap() = expires_at - Duration::from_secs(3600);
+        assert_eq!(refresher.next_refresh_in(), Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn test_file_token_store() {
+        // Remove files even if any assertion fails.
+        struct Cleanup(Vec<PathBuf>);
+        impl Drop for Cleanup {
+            fn drop(&mut self) {
+                for path in &self.0 {
+                    let _ = fs::remove_file(path);
+                }
+            }
+        }
+
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let name = format!("onedrive-api-token-{}-{}", std::process::id(), nanos);
+        let path = std::env::temp_dir().join(&name);
+        let temp_path = std::env::temp_dir().join(format!("{}.tmp", name));
+        let _cleanup = Cleanup(vec![path.clone(), temp_path.clone()]);
+        let store = FileTokenStore::new(&path, XorCipher(0x5A));
+        assert_eq!(store.load().unwrap(), None);
+
+        // A stale temporary file with looser permissions is not reused.
+        fs::write(&temp_path, b"stale").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+            fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o644)).unwrap();
+        }
+
+        store.save("refresh-token").unwrap();
+        assert!(!temp_path.exists());
+        assert_ne!(fs::read(&path).unwrap(), b"refresh-token");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(store.load().unwrap().as_deref(), Some("refresh-token"));
+    }
+
+    #[cfg(feature = "token-encryption")]
+    #[test]
+    fn test_aes_gcm_cipher() {
+        assert!(AesGcmCipher::new(&[0; 16]).is_none());
+        let cipher = AesGcmCipher::new(&[0x42; AesGcmCipher::KEY_LEN]).unwrap();
+        let encrypted = cipher.encrypt(b"refresh-token").unwrap();
+        assert_ne!(&encrypted[..], b"refresh-token");
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), b"refresh-token");
+
+        let mut tampered = encrypted;
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(cipher.decrypt(&tampered).is_err());
+    }
+}
@@ -0,0 +1,870 @@
+use crate::{
+    error::{Error, Result},
+    onedrive::{UploadCheckpoint, UploadSession},
+    resource::{DriveItem, Tag},
+    util::{handle_error_response, limit_body_size, RequestBuilderExt as _},
+};
+use bytes::{Bytes, BytesMut};
+use reqwest::{header, Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::{fmt, future::Future, pin::Pin};
+
+/// The future returned by [`Transfer::transfer_next`][transfer_next] and
+/// [`Transfer::cancel`][cancel].
+///
+/// [transfer_next]: ./trait.Transfer.html#tymethod.transfer_next
+/// [cancel]: ./trait.Transfer.html#tymethod.cancel
+pub type TransferFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// The state of a [`Transfer`][transfer].
+///
+/// [transfer]: ./trait.Transfer.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferState {
+    /// The transfer is in progress or ready to continue.
+    Running,
+    /// The transfer is paused, and will not continue until resumed.
+    Paused,
+    /// The transfer is completed successfully.
+    Completed,
+    /// The transfer is cancelled, and cannot be resumed.
+    Cancelled,
+}
+
+impl TransferState {
+    /// Check if the transfer is completed or cancelled.
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Cancelled)
+    }
+}
+
+/// The progress of a [`Transfer`][transfer].
+///
+/// [transfer]: ./trait.Transfer.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// The number of bytes transferred.
+    pub transferred: u64,
+    /// The total number of bytes to transfer, if known.
+    pub total: Option<u64>,
+}
+
+impl TransferProgress {
+    /// Get the fraction transferred in `0.0..=1.0`, or `None` if the total is unknown.
+    ///
+    /// An empty transfer is considered fully transferred.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total? {
+            0 => Some(1.0),
+            total => Some(self.transferred as f64 / total as f64),
+        }
+    }
+}
+
+/// A resumable in-flight transfer, to be managed uniformly by application UIs.
+///
+/// Pausing only takes effect between parts. A paused transfer does not send any request
+/// until it is resumed. The state returned by [`save_state`][save_state] can be persisted
+/// to continue the transfer later, even after the process restarts.
+///
+/// It is object safe, so transfers of different kinds can be stored as `Box<dyn Transfer>`
+/// and driven by [`transfer_next`][transfer_next] uniformly.
+///
+/// # See also
+/// [`UploadTransfer`][upload], [`DownloadTransfer`][download], [`TransferQueue`][queue]
+///
+/// [save_state]: #tymethod.save_state
+/// [transfer_next]: #tymethod.transfer_next
+/// [upload]: ./struct.UploadTransfer.html
+/// [download]: ./struct.DownloadTransfer.html
+/// [queue]: ./struct.TransferQueue.html
+pub trait Transfer: fmt::Debug + Send {
+    /// Get the current progress.
+    fn progress(&self) -> TransferProgress;
+
+    /// Get the current state.
+    fn state(&self) -> TransferState;
+
+    /// Pause the transfer. Do nothing if it is not running.
+    fn pause(&mut self);
+
+    /// Resume a paused transfer. Do nothing if it is not paused.
+    fn resume(&mut self);
+
+    /// Transfer the next part if the transfer is running. Do nothing otherwise.
+    ///
+    /// On error, the progress is unchanged and the part can be retried by calling it again.
+    fn transfer_next<'a>(&'a mut self, client: &'a Client) -> TransferFuture<'a>;
+
+    /// Cancel the transfer and clean up the remote state of it.
+    ///
+    /// Do nothing if it is already finished.
+    fn cancel<'a>(&'a mut self, client: &'a Client) -> TransferFuture<'a>;
+
+    /// Serialize the state required to continue the transfer later.
+    fn save_state(&self) -> serde_json::Value;
+}
+
+/// A file upload through an [`UploadSession`][session], driven part by part.
+///
+/// The content to upload is kept in memory. Its state is an
+/// [`UploadCheckpoint`][checkpoint], which can be used to resume the upload after
+/// the process restarts.
+///
+/// # Example
+/// ```
+/// use onedrive_api::{ItemLocation, OneDrive, Transfer, UploadTransfer};
+///
+/// # async fn run(onedrive: &OneDrive, data: Vec<u8>) -> onedrive_api::Result<()> {
+/// let (session, _) = onedrive
+///     .new_upload_session(ItemLocation::from_path("/file.bin").unwrap())
+///     .await?;
+/// let mut transfer = UploadTransfer::new(&session, data);
+/// while !transfer.state().is_finished() {
+///     transfer.upload_next(onedrive.client()).await?;
+///     println!("{:?}", transfer.progress().fraction());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [session]: ./struct.UploadSession.html
+/// [checkpoint]: ./struct.UploadCheckpoint.html
+#[derive(Debug)]
+pub struct UploadTransfer {
+    checkpoint: UploadCheckpoint,
+    data: Bytes,
+    part_size: usize,
+    state: TransferState,
+}
+
+impl UploadTransfer {
+    /// The default size of each part, 10 MiB.
+    pub const DEFAULT_PART_SIZE: usize = 32 * Self::PART_SIZE_UNIT;
+
+    const PART_SIZE_UNIT: usize = 320 << 10; // 320 KiB
+
+    /// Create a transfer uploading `data` through `session` from the beginning.
+    pub fn new(session: &UploadSession, data: impl Into<Bytes>) -> Self {
+        let data = data.into();
+        let checkpoint = UploadCheckpoint::new(session, data.len() as u64);
+        Self::from_checkpoint(checkpoint, data)
+    }
+
+    /// Continue a transfer from a checkpoint, eg. restored from
+    /// [`save_state`][save_state].
+    ///
+    /// The checkpoint should be validated by [`UploadCheckpoint::resume`][resume] first,
+    /// and updated to [`ResumedUpload::offset`][offset] if it differs.
+    ///
+    /// # Panic
+    /// Panic if the length of `data` mismatches the file size of `checkpoint`.
+    ///
+    /// [save_state]: ./trait.Transfer.html#tymethod.save_state
+    /// [resume]: ./struct.UploadCheckpoint.html#method.resume
+    /// [offset]: ./struct.ResumedUpload.html#structfield.offset
+    pub fn from_checkpoint(checkpoint: UploadCheckpoint, data: impl Into<Bytes>) -> Self {
+        let data = data.into();
+        assert_eq!(
+            data.len() as u64,
+            checkpoint.file_size(),
+            "Data size mismatches the checkpoint",
+        );
+        Self {
+            checkpoint,
+            data,
+            part_size: Self::DEFAULT_PART_SIZE,
+            state: TransferState::Running,
+        }
+    }
+
+    /// Restore a transfer from the state returned by [`save_state`][save_state].
+    ///
+    /// See [`from_checkpoint`][from_checkpoint] for details.
+    ///
+    /// # Errors
+    /// Will return `Err` with [`Error::is_deserialize_error`][deserialize] being `true`
+    /// if `state` is malformed, or with [`Error::is_invalid_argument`][invalid] being
+    /// `true` if the length of `data` mismatches the file size in `state`.
+    ///
+    /// [save_state]: ./trait.Transfer.html#tymethod.save_state
+    /// [from_checkpoint]: #method.from_checkpoint
+    /// [deserialize]: ./struct.Error.html#method.is_deserialize_error
+    /// [invalid]: ./struct.Error.html#method.is_invalid_argument
+    pub fn restore(state: serde_json::Value, data: impl Into<Bytes>) -> Result<Self> {
+        let checkpoint: UploadCheckpoint =
+            serde_json::from_value(state).map_err(Error::from_deserialize)?;
+        let data = data.into();
+        if data.len() as u64 != checkpoint.file_size() {
+            return Err(Error::invalid_argument(
+                "Data size mismatches the saved state",
+            ));
+        }
+        Ok(Self::from_checkpoint(checkpoint, data))
+    }
+
+    /// Set the size of each part to upload.
+    ///
+    /// # Panic
+    /// Panic if `part_size` is zero, not a multiple of 320 KiB, or larger than
+    /// [`UploadSession::MAX_PART_SIZE`][max_part_size].
+    ///
+    /// [max_part_size]: ./struct.UploadSession.html#associatedconstant.MAX_PART_SIZE
+    // `usize::is_multiple_of` requires Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        assert!(
+            part_size != 0
+                && part_size % Self::PART_SIZE_UNIT == 0
+                && part_size <= UploadSession::MAX_PART_SIZE,
+            "Invalid part size",
+        );
+        self.part_size = part_size;
+        self
+    }
+
+    /// Get the checkpoint of the uploaded prefix.
+    pub fn checkpoint(&self) -> &UploadCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Upload the next part if the transfer is running.
+    ///
+    /// # Response
+    /// Return `Some(<newly_created_drive_item>)` if the last part is uploaded.
+    /// Otherwise, including when the transfer is not running, return `None`.
+    ///
+    /// # Errors
+    /// Same as [`UploadSession::upload_part`][upload_part]. The progress is unchanged and
+    /// the part can be retried by calling it again.
+    ///
+    /// # Panic
+    /// Panic if the file is empty, which cannot be uploaded through upload sessions.
+    ///
+    /// [upload_part]: ./struct.UploadSession.html#method.upload_part
+    pub async fn upload_next(&mut self, client: &Client) -> Result<Option<DriveItem>> {
+        if self.state != TransferState::Running {
+            return Ok(None);
+        }
+        let file_size = self.checkpoint.file_size();
+        let start = self.checkpoint.uploaded();
+        let end = file_size.min(start + self.part_size as u64);
+        let part = self.data.slice(start as usize..end as usize);
+        let ret = self
+            .checkpoint
+            .session()
+            .upload_part(part, start..end, file_size, client)
+            .await?;
+        self.checkpoint.update_uploaded(end);
+        if ret.is_some() {
+            self.state = TransferState::Completed;
+        }
+        Ok(ret)
+    }
+}
+
+impl Transfer for UploadTransfer {
+    fn progress(&self) -> TransferProgress {
+        TransferProgress {
+            transferred: self.checkpoint.uploaded(),
+            total: Some(self.checkpoint.file_size()),
+        }
+    }
+
+    fn state(&self) -> TransferState {
+        self.state
+    }
+
+    fn pause(&mut self) {
+        if self.state == TransferState::Running {
+            self.state = TransferState::Paused;
+        }
+    }
+
+    fn resume(&mut self) {
+        if self.state == TransferState::Paused {
+            self.state = TransferState::Running;
+        }
+    }
+
+    fn transfer_next<'a>(&'a mut self, client: &'a Client) -> TransferFuture<'a> {
+        Box::pin(async move { self.upload_next(client).await.map(|_| ()) })
+    }
+
+    fn cancel<'a>(&'a mut self, client: &'a Client) -> TransferFuture<'a> {
+        Box::pin(async move {
+            if self.state.is_finished() {
+                return Ok(());
+            }
+            match self.checkpoint.session().delete(client).await {
+                Ok(()) => {}
+                // Nothing to clean up.
+                Err(err) if err.is_session_expired() => {}
+                Err(err) => return Err(err),
+            }
+            self.state = TransferState::Cancelled;
+            Ok(())
+        })
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(&self.checkpoint).expect("Checkpoint is always serializable")
+    }
+}
+
+/// A file download through its pre-authenticated download url, driven part by part
+/// with ranged requests.
+///
+/// Each part is requested with `If-Match` of the `eTag` of the item, so the download fails
+/// instead of mixing contents if the item is modified in the middle. The downloaded content
+/// is kept in memory. Its state records the download url, the `eTag` and the offset of
+/// the downloaded prefix, which can be used to resume the download after the process
+/// restarts.
+///
+/// # Note
+/// The download url is short-lived (usually about an hour). Restoring a transfer after
+/// it expires fails with HTTP 401 or 403, and a new transfer should be started with
+/// a fresh item.
+///
+/// # Example
+/// ```
+/// use onedrive_api::{DownloadTransfer, ItemLocation, OneDrive, Transfer};
+///
+/// # async fn run(onedrive: &OneDrive) -> onedrive_api::Result<()> {
+/// let item = onedrive
+///     .get_item(ItemLocation::from_path("/file.bin").unwrap())
+///     .await?;
+/// let mut transfer = DownloadTransfer::new(&item)?;
+/// while !transfer.state().is_finished() {
+///     transfer.download_next(onedrive.client()).await?;
+///     println!("{:?}", transfer.progress().fraction());
+/// }
+/// let data = transfer.into_data();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DownloadTransfer {
+    checkpoint: DownloadCheckpoint,
+    data: BytesMut,
+    part_size: usize,
+    state: TransferState,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadCheckpoint {
+    download_url: String,
+    e_tag: Tag,
+    file_size: u64,
+    downloaded: u64,
+}
+
+impl DownloadTransfer {
+    /// The default size of each part, 10 MiB.
+    pub const DEFAULT_PART_SIZE: usize = 10 << 20;
+
+    /// Create a transfer downloading `item` from the beginning.
+    ///
+    /// # Errors
+    /// Will return `Err` with [`Error::is_invalid_argument`][invalid] being `true` if
+    /// `download_url`, `e_tag` or `size` of `item` is missing, eg. not selected or
+    /// `item` is not a file.
+    ///
+    /// [invalid]: ./struct.Error.html#method.is_invalid_argument
+    pub fn new(item: &DriveItem) -> Result<Self> {
+        let checkpoint = DownloadCheckpoint {
+            download_url: item
+                .download_url
+                .as_ref()
+                .ok_or_else(|| Error::invalid_argument("Missing field `downloadUrl`"))?
+                .to_string(),
+            e_tag: item
+                .e_tag
+                .clone()
+                .ok_or_else(|| Error::invalid_argument("Missing field `eTag`"))?,
+            file_size: item
+                .size
+                .ok_or_else(|| Error::invalid_argument("Missing field `size`"))?
+                .max(0) as u64,
+            downloaded: 0,
+        };
+        Ok(Self::from_checkpoint(checkpoint, Bytes::new()))
+    }
+
+    fn from_checkpoint(checkpoint: DownloadCheckpoint, downloaded: Bytes) -> Self {
+        let state = if checkpoint.downloaded == checkpoint.file_size {
+            TransferState::Completed
+        } else {
+            TransferState::Running
+        };
+        Self {
+            checkpoint,
+            data: BytesMut::from(&downloaded[..]),
+            part_size: Self::DEFAULT_PART_SIZE,
+            state,
+        }
+    }
+
+    /// Restore a transfer from the state returned by [`save_state`][save_state],
+    /// with the `downloaded` prefix of the content.
+    ///
+    /// # Errors
+    /// Will return `Err` with [`Error::is_deserialize_error`][deserialize] being `true`
+    /// if `state` is malformed, or with [`Error::is_invalid_argument`][invalid] being
+    /// `true` if the length of `downloaded` mismatches the offset in `state`.
+    ///
+    /// [save_state]: ./trait.Transfer.html#tymethod.save_state
+    /// [deserialize]: ./struct.Error.html#method.is_deserialize_error
+    /// [invalid]: ./struct.Error.html#method.is_invalid_argument
+    pub fn restore(state: serde_json::Value, downloaded: impl Into<Bytes>) -> Result<Self> {
+        let checkpoint: DownloadCheckpoint =
+            serde_json::from_value(state).map_err(Error::from_deserialize)?;
+        let downloaded = downloaded.into();
+        if downloaded.len() as u64 != checkpoint.downloaded
+            || checkpoint.downloaded > checkpoint.file_size
+        {
+            return Err(Error::invalid_argument(
+                "Downloaded size mismatches the saved state",
+            ));
+        }
+        Ok(Self::from_checkpoint(checkpoint, downloaded))
+    }
+
+    /// Set the size of each part to download.
+    ///
+    /// # Panic
+    /// Panic if `part_size` is zero.
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        assert!(part_size != 0, "Invalid part size");
+        self.part_size = part_size;
+        self
+    }
+
+    /// Get the downloaded prefix of the content.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Take the downloaded content.
+    pub fn into_data(self) -> Bytes {
+        self.data.freeze()
+    }
+
+    /// Download the next part if the transfer is running.
+    ///
+    /// # Response
+    /// Return `true` if the last part is downloaded. Otherwise, including when
+    /// the transfer is not running, return `false`.
+    ///
+    /// # Errors
+    /// Will return `Err` with HTTP 412 PRECONDITION_FAILED if the item is modified after
+    /// the transfer started. The progress is unchanged on errors, and the part can be
+    /// retried by calling it again.
+    pub async fn download_next(&mut self, client: &Client) -> Result<bool> {
+        if self.state != TransferState::Running {
+            return Ok(false);
+        }
+        let DownloadCheckpoint {
+            download_url,
+            e_tag,
+            file_size,
+            downloaded: start,
+        } = &self.checkpoint;
+        let (file_size, start) = (*file_size, *start);
+        let end = file_size.min(start + self.part_size as u64);
+        if start < end {
+            // No bearer auth.
+            let resp = client
+                .get(download_url)
+                // Inclusive.
+                .header(header::RANGE, format!("bytes={}-{}", start, end - 1))
+                .header(header::IF_MATCH, e_tag.as_str())
+                .send_logged()
+                .await?;
+            let resp = handle_error_response(resp).await?;
+            // The whole content is responded if the server ignores `Range`.
+            let is_whole = resp.status() == StatusCode::OK && start == 0 && end == file_size;
+            if resp.status() != StatusCode::PARTIAL_CONTENT && !is_whole {
+                return Err(Error::unexpected_response(
+                    "Expect partial content of download",
+                ));
+            }
+            let part = limit_body_size(resp, Some(end - start))
+                .await?
+                .bytes()
+                .await?;
+            if part.len() as u64 != end - start {
+                return Err(Error::unexpected_response(
+                    "Downloaded part size mismatches the requested range",
+                ));
+            }
+            self.data.extend_from_slice(&part);
+            self.checkpoint.downloaded = end;
+        }
+        if end == file_size {
+            self.state = TransferState::Completed;
+        }
+        Ok(self.state == TransferState::Completed)
+    }
+}
+
+impl Transfer for DownloadTransfer {
+    fn progress(&self) -> TransferProgress {
+        TransferProgress {
+            transferred: self.checkpoint.downloaded,
+            total: Some(self.checkpoint.file_size),
+        }
+    }
+
+    fn state(&self) -> TransferState {
+        self.state
+    }
+
+    fn pause(&mut self) {
+        if self.state == TransferState::Running {
+            self.state = TransferState::Paused;
+        }
+    }
+
+    fn resume(&mut self) {
+        if self.state == TransferState::Paused {
+            self.state = TransferState::Running;
+        }
+    }
+
+    fn transfer_next<'a>(&'a mut self, client: &'a Client) -> TransferFuture<'a> {
+        Box::pin(async move { self.download_next(client).await.map(|_| ()) })
+    }
+
+    fn cancel<'a>(&'a mut self, _client: &'a Client) -> TransferFuture<'a> {
+        // There is no remote state to clean up.
+        if !self.state.is_finished() {
+            self.state = TransferState::Cancelled;
+        }
+        Box::pin(async { Ok(()) })
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(&self.checkpoint).expect("Checkpoint is always serializable")
+    }
+}
+
+/// A queue of transfers of any kind, driven one by one in order.
+///
+/// It is a [`Transfer`][transfer] itself, reporting the total progress of all transfers,
+/// so a bulk of transfers can be managed like a single one. Pausing the queue stops driving
+/// any transfer, while paused transfers in a running queue are skipped.
+///
+/// # Example
+/// ```
+/// use onedrive_api::{Transfer, TransferQueue};
+///
+/// # async fn run(
+/// #     onedrive: &onedrive_api::OneDrive,
+/// #     transfers: Vec<Box<dyn Transfer>>,
+/// # ) -> onedrive_api::Result<()> {
+/// let mut queue = transfers.into_iter().collect::<TransferQueue>();
+/// while !queue.state().is_finished() {
+///     queue.transfer_next(onedrive.client()).await?;
+///     println!("{:?}", queue.progress().fraction());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [transfer]: ./trait.Transfer.html
+#[derive(Debug, Default)]
+pub struct TransferQueue {
+    transfers: Vec<Box<dyn Transfer>>,
+    paused: bool,
+    cancelled: bool,
+}
+
+impl TransferQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a transfer to the end of the queue.
+    pub fn push(&mut self, transfer: Box<dyn Transfer>) {
+        self.transfers.push(transfer);
+    }
+
+    /// Get transfers in the queue, eg. to show or pause each of them.
+    pub fn transfers(&self) -> &[Box<dyn Transfer>] {
+        &self.transfers
+    }
+
+    /// Get mutable transfers in the queue.
+    pub fn transfers_mut(&mut self) -> &mut [Box<dyn Transfer>] {
+        &mut self.transfers
+    }
+
+    /// Take all transfers out of the queue.
+    pub fn into_transfers(self) -> Vec<Box<dyn Transfer>> {
+        self.transfers
+    }
+}
+
+impl std::iter::FromIterator<Box<dyn Transfer>> for TransferQueue {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Transfer>>>(iter: I) -> Self {
+        Self {
+            transfers: iter.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Transfer for TransferQueue {
+    /// Get the sum of progress of all transfers.
+    ///
+    /// The total is unknown if any of them is unknown.
+    fn progress(&self) -> TransferProgress {
+        self.transfers.iter().map(|t| t.progress()).fold(
+            TransferProgress {
+                transferred: 0,
+                total: Some(0),
+            },
+            |acc, p| TransferProgress {
+                transferred: acc.transferred + p.transferred,
+                total: acc.total.zip(p.total).map(|(a, b)| a + b),
+            },
+        )
+    }
+
+    /// Get the state of the queue.
+    ///
+    /// It is completed if all transfers are finished, including cancelled ones.
+    fn state(&self) -> TransferState {
+        if self.cancelled {
+            TransferState::Cancelled
+        } else if self.transfers.iter().all(|t| t.state().is_finished()) {
+            TransferState::Completed
+        } else if self.paused {
+            TransferState::Paused
+        } else {
+            TransferState::Running
+        }
+    }
+
+    fn pause(&mut self) {
+        if self.state() == TransferState::Running {
+            self.paused = true;
+        }
+    }
+
+    fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Transfer the next part of the first running transfer.
+    fn transfer_next<'a>(&'a mut self, client: &'a Client) -> TransferFuture<'a> {
+        Box::pin(async move {
+            if self.state() != TransferState::Running {
+                return Ok(());
+            }
+            match self
+                .transfers
+                .iter_mut()
+                .find(|t| t.state() == TransferState::Running)
+            {
+                Some(t) => t.transfer_next(client).await,
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Cancel all unfinished transfers.
+    ///
+    /// If any of them fails, the rest are still cancelled and the first error is returned.
+    /// The queue is cancelled only if all of them succeed.
+    fn cancel<'a>(&'a mut self, client: &'a Client) -> TransferFuture<'a> {
+        Box::pin(async move {
+            if self.state().is_finished() {
+                return Ok(());
+            }
+            let mut ret = Ok(());
+            for t in &mut self.transfers {
+                if let Err(err) = t.cancel(client).await {
+                    ret = ret.and(Err(err));
+                }
+            }
+            self.cancelled = ret.is_ok();
+            ret
+        })
+    }
+
+    /// Serialize states of all transfers as an array, in order.
+    ///
+    /// Transfers should be restored from each state by their own types, and collected
+    /// into a new queue.
+    fn save_state(&self) -> serde_json::Value {
+        self.transfers.iter().map(|t| t.save_state()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt as _;
+
+    fn transfer(len: usize) -> UploadTransfer {
+        let session = UploadSession::from_upload_url("https://example.com/upload".to_owned());
+        UploadTransfer::new(&session, vec![0u8; len])
+    }
+
+    #[test]
+    fn test_upload_transfer_state() {
+        let mut t = transfer(100);
+        assert_eq!(t.state(), TransferState::Running);
+        assert_eq!(
+            t.progress(),
+            TransferProgress {
+                transferred: 0,
+                total: Some(100),
+            },
+        );
+        assert_eq!(t.progress().fraction(), Some(0.0));
+
+        t.pause();
+        assert_eq!(t.state(), TransferState::Paused);
+        // No request is sent when paused.
+        let client = Client::new();
+        assert!(t
+            .upload_next(&client)
+            .now_or_never()
+            .unwrap()
+            .unwrap()
+            .is_none());
+        t.resume();
+        assert_eq!(t.state(), TransferState::Running);
+
+        let mut boxed: Box<dyn Transfer> = Box::new(t);
+        boxed.pause();
+        assert_eq!(boxed.state(), TransferState::Paused);
+    }
+
+    #[test]
+    fn test_upload_transfer_save_state() {
+        let mut checkpoint = transfer(100).checkpoint().clone();
+        checkpoint.update_uploaded(40);
+        let t = UploadTransfer::from_checkpoint(checkpoint.clone(), vec![0u8; 100]);
+        assert_eq!(t.progress().fraction(), Some(0.4));
+
+        let restored = UploadTransfer::restore(t.save_state(), vec![0u8; 100]).unwrap();
+        assert_eq!(restored.checkpoint(), &checkpoint);
+        assert!(
+            UploadTransfer::restore(serde_json::json!({}), vec![0u8; 100])
+                .unwrap_err()
+                .is_deserialize_error()
+        );
+        assert!(UploadTransfer::restore(t.save_state(), vec![0u8; 99])
+            .unwrap_err()
+            .is_invalid_argument());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid part size")]
+    fn test_upload_transfer_part_size() {
+        let _ = transfer(100).with_part_size(1000);
+    }
+
+    fn download(size: i64) -> DownloadTransfer {
+        let item = serde_json::from_value(serde_json::json!({
+            "@microsoft.graph.downloadUrl": "https://example.com/download",
+            "eTag": "tag",
+            "size": size,
+        }))
+        .unwrap();
+        DownloadTransfer::new(&item).unwrap()
+    }
+
+    #[test]
+    fn test_download_transfer() {
+        let item = serde_json::from_value(serde_json::json!({ "size": 100 })).unwrap();
+        assert!(DownloadTransfer::new(&item)
+            .unwrap_err()
+            .is_invalid_argument());
+
+        let mut t = download(100);
+        assert_eq!(t.state(), TransferState::Running);
+        assert_eq!(t.progress().fraction(), Some(0.0));
+        t.pause();
+        // No request is sent when paused.
+        let client = Client::new();
+        assert!(!t.download_next(&client).now_or_never().unwrap().unwrap());
+
+        let mut state = t.save_state();
+        assert_eq!(state["eTag"], "tag");
+        state["downloaded"] = 40.into();
+        let t = DownloadTransfer::restore(state.clone(), vec![1u8; 40]).unwrap();
+        assert_eq!(t.state(), TransferState::Running);
+        assert_eq!(t.progress().fraction(), Some(0.4));
+        assert_eq!(t.data(), &[1u8; 40][..]);
+        assert_eq!(t.save_state(), state);
+        assert!(DownloadTransfer::restore(state, vec![0u8; 39])
+            .unwrap_err()
+            .is_invalid_argument());
+
+        // Nothing to download for an empty file.
+        let t = download(0);
+        assert_eq!(t.state(), TransferState::Completed);
+        assert!(t.into_data().is_empty());
+    }
+
+    #[test]
+    fn test_transfer_queue() {
+        let client = Client::new();
+        assert_eq!(TransferQueue::new().state(), TransferState::Completed);
+
+        let mut checkpoint = transfer(100).checkpoint().clone();
+        checkpoint.update_uploaded(40);
+        let upload = UploadTransfer::from_checkpoint(checkpoint, vec![0u8; 100]);
+        let mut queue = vec![
+            Box::new(upload) as Box<dyn Transfer>,
+            Box::new(download(50)),
+        ]
+        .into_iter()
+        .collect::<TransferQueue>();
+        assert_eq!(
+            queue.progress(),
+            TransferProgress {
+                transferred: 40,
+                total: Some(150),
+            },
+        );
+        assert_eq!(queue.state(), TransferState::Running);
+        assert_eq!(queue.save_state().as_array().unwrap().len(), 2);
+
+        queue.pause();
+        assert_eq!(queue.state(), TransferState::Paused);
+        // No request is sent when paused.
+        queue
+            .transfer_next(&client)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        queue.resume();
+        assert_eq!(queue.state(), TransferState::Running);
+
+        // Paused transfers are skipped.
+        for t in queue.transfers_mut() {
+            t.pause();
+        }
+        queue
+            .transfer_next(&client)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(queue.progress().transferred, 40);
+
+        let mut queue = vec![Box::new(download(50)) as Box<dyn Transfer>]
+            .into_iter()
+            .collect::<TransferQueue>();
+        queue.cancel(&client).now_or_never().unwrap().unwrap();
+        assert_eq!(queue.state(), TransferState::Cancelled);
+        assert_eq!(queue.transfers()[0].state(), TransferState::Cancelled);
+    }
+}
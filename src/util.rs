@@ -1,10 +1,14 @@
 use crate::{
     error::{Error, Result},
-    resource::{DriveId, ErrorResponse, ItemId, OAuth2ErrorResponse},
+    resource::{DriveId, DriveItem, ErrorResponse, ItemId, OAuth2ErrorResponse, Tag},
 };
-use reqwest::{RequestBuilder, Response, StatusCode};
-use serde::{de, Deserialize};
-use url::PathSegmentsMut;
+use reqwest::{header, Client, Request, RequestBuilder, Response, StatusCode};
+use serde::{de, Deserialize, Serialize};
+use std::{
+    future::Future,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use url::{PathSegmentsMut, Url};
 
 /// Specify the location of a `Drive` resource.
 ///
@@ -169,6 +173,19 @@ impl<'a> ItemLocation<'a> {
     }
 }
 
+impl<'a> ItemLocation<'a> {
+    /// The name of the item, if it is located by a path (other than the root) or
+    /// as a child of an item.
+    pub(crate) fn name(&self) -> Option<&'a str> {
+        match self.inner {
+            ItemLocationEnum::Path(path) => path.trim_end_matches('/').rsplit('/').next(),
+            ItemLocationEnum::Id(_) => None,
+            ItemLocationEnum::ChildOfId { child_name, .. } => Some(child_name),
+        }
+        .filter(|name| !name.is_empty())
+    }
+}
+
 impl<'a> From<&'a ItemId> for ItemLocation<'a> {
     fn from(id: &'a ItemId) -> Self {
         Self::from_id(id)
@@ -211,6 +228,112 @@ impl AsRef<str> for FileName {
     }
 }
 
+/// An opaque delta link of Track Changes API, representing a snapshot of a folder.
+///
+/// It can be serialized and persisted for tracking changes later, with the time
+/// it is obtained, which can be used to discard too old tokens. Changes are tracked from it
+/// through [`OneDrive::track_changes_from_delta_token`][track_token].
+///
+/// # See also
+/// [Microsoft Docs](https://docs.microsoft.com/en-us/graph/api/driveitem-delta?view=graph-rest-1.0)
+///
+/// [track_token]: ./struct.OneDrive.html#method.track_changes_from_delta_token
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeltaToken {
+    delta_url: String,
+    #[serde(default)]
+    obtained_at: Option<SystemTime>,
+}
+
+impl DeltaToken {
+    /// Wrap a delta url obtained just now, according to the system clock.
+    pub fn new(delta_url: String) -> Self {
+        Self::new_at(delta_url, SystemTime::now())
+    }
+
+    /// Wrap a delta url obtained at time `obtained_at`, eg. from
+    /// [`OneDrive::clock`][clock].
+    ///
+    /// [clock]: ./struct.OneDrive.html#method.clock
+    pub fn new_at(delta_url: String, obtained_at: SystemTime) -> Self {
+        Self {
+            delta_url,
+            obtained_at: Some(obtained_at),
+        }
+    }
+
+    /// Wrap a delta url obtained at an unknown time.
+    pub fn from_delta_url(delta_url: String) -> Self {
+        Self {
+            delta_url,
+            obtained_at: None,
+        }
+    }
+
+    /// Get the underlying delta url.
+    pub fn delta_url(&self) -> &str {
+        &self.delta_url
+    }
+
+    /// Unwrap into the underlying delta url.
+    pub fn into_delta_url(self) -> String {
+        self.delta_url
+    }
+
+    /// Get the opaque token in the `token` query parameter of the delta url, if any.
+    pub fn token(&self) -> Option<String> {
+        let url = Url::parse(&self.delta_url).ok()?;
+        url.query_pairs()
+            .find(|(k, _)| k == "token" || k == "$deltatoken")
+            .map(|(_, v)| v.into_owned())
+    }
+
+    /// Get the time when the delta url is obtained, if known.
+    pub fn obtained_at(&self) -> Option<SystemTime> {
+        self.obtained_at
+    }
+
+    /// Get the time elapsed since the delta url is obtained according to the system clock,
+    /// if known.
+    pub fn age(&self) -> Option<Duration> {
+        self.age_at(SystemTime::now())
+    }
+
+    /// Get the time elapsed since the delta url is obtained until time `now`, eg.
+    /// from [`OneDrive::clock`][clock], if known.
+    ///
+    /// [clock]: ./struct.OneDrive.html#method.clock
+    pub fn age_at(&self, now: SystemTime) -> Option<Duration> {
+        now.duration_since(self.obtained_at?)
+            .ok()
+            .or(Some(Duration::from_secs(0)))
+    }
+
+    /// Check if the delta url targets the drive `drive`.
+    ///
+    /// The drive is compared in the form it is addressed in the url. So the same drive
+    /// addressed differently, like `DriveLocation::me()` and `DriveLocation::from_id(..)`,
+    /// are considered different.
+    pub fn targets_drive(&self, drive: &DriveLocation) -> bool {
+        let url = match Url::parse(&self.delta_url) {
+            Ok(url) => url,
+            Err(_) => return false,
+        };
+        let mut expect = Url::parse("path:///").unwrap();
+        drive.extend_into(&mut expect.path_segments_mut().unwrap());
+        let (mut segs, expect_segs) = match (url.path_segments(), expect.path_segments()) {
+            (Some(segs), Some(expect_segs)) => (segs, expect_segs),
+            _ => return false,
+        };
+        // Skip the API version, like `v1.0` or `beta`.
+        segs.next();
+        expect_segs.filter(|seg| !seg.is_empty()).all(|expect| {
+            segs.next()
+                .is_some_and(|seg| seg.eq_ignore_ascii_case(expect))
+        })
+    }
+}
+
 pub(crate) trait ApiPathComponent {
     fn extend_into(&self, buf: &mut PathSegmentsMut);
 }
@@ -255,30 +378,116 @@ pub(crate) trait RequestBuilderTransformer {
 
 pub(crate) trait RequestBuilderExt: Sized {
     fn apply(self, trans: impl RequestBuilderTransformer) -> Self;
+    fn send_logged(self) -> BoxFuture<reqwest::Result<Response>>;
 }
 
 impl RequestBuilderExt for RequestBuilder {
     fn apply(self, trans: impl RequestBuilderTransformer) -> Self {
         trans.trans(self)
     }
+
+    fn send_logged(self) -> BoxFuture<reqwest::Result<Response>> {
+        let (client, req) = self.build_split();
+        match req {
+            Ok(req) => Box::pin(execute_logged(&client, req)),
+            Err(err) => Box::pin(async { Err(err) }),
+        }
+    }
 }
 
-type BoxFuture<T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'static>>;
+/// Execute `req`, logging the method, redacted url, status and timing at debug level.
+pub(crate) fn execute_logged(
+    client: &Client,
+    req: Request,
+) -> impl Future<Output = reqwest::Result<Response>> + Send + 'static {
+    let logged = if log::log_enabled!(log::Level::Debug) {
+        Some((req.method().clone(), redact_url(req.url()), Instant::now()))
+    } else {
+        None
+    };
+    let resp = client.execute(req);
+    async move {
+        let ret = resp.await;
+        if let Some((method, url, start)) = logged {
+            let elapsed = start.elapsed();
+            match &ret {
+                Ok(resp) => log::debug!("{} {} -> {} in {:?}", method, url, resp.status(), elapsed),
+                // The error message contains the unredacted url.
+                Err(err) if err.is_timeout() => {
+                    log::debug!("{} {} -> timed out in {:?}", method, url, elapsed)
+                }
+                Err(_) => log::debug!("{} {} -> failed in {:?}", method, url, elapsed),
+            }
+        }
+        ret
+    }
+}
+
+/// Redact credentials in `url` for logging.
+///
+/// Urls outside Microsoft Graph and the login endpoint, like download urls and upload
+/// session urls, are pre-authenticated. Everything but their origin is redacted.
+/// For others, query parameters carrying credentials are redacted.
+pub(crate) fn redact_url(url: &Url) -> String {
+    const TRUSTED_HOSTS: &[&str] = &["graph.microsoft.com", "login.microsoftonline.com"];
+    const SECRET_PARAMS: &[&str] = &[
+        "access_token",
+        "client_secret",
+        "code",
+        "refresh_token",
+        "tempauth",
+    ];
+    const REDACTED: &str = "REDACTED";
+
+    if !url
+        .host_str()
+        .is_some_and(|host| TRUSTED_HOSTS.contains(&host))
+    {
+        return format!("{}/{}", url.origin().ascii_serialization(), REDACTED);
+    }
+    let mut url = url.clone();
+    if url.query().is_some() {
+        let pairs = url
+            .query_pairs()
+            .map(|(k, v)| {
+                let v = if SECRET_PARAMS.contains(&&*k) {
+                    REDACTED.into()
+                } else {
+                    v
+                };
+                (k.into_owned(), v.into_owned())
+            })
+            .collect::<Vec<_>>();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.into()
+}
+
+type BoxFuture<T> = std::pin::Pin<Box<dyn Future<Output = T> + Send + 'static>>;
 
 // TODO: Avoid boxing?
+//
+// `strict` is whether HTTP 202 ACCEPTED without `Location` is an error,
+// see `accepted_job`.
 pub(crate) trait ResponseExt: Sized {
-    fn parse<T: de::DeserializeOwned>(self) -> BoxFuture<Result<T>>;
-    fn parse_optional<T: de::DeserializeOwned>(self) -> BoxFuture<Result<Option<T>>>;
-    fn parse_no_content(self) -> BoxFuture<Result<()>>;
+    fn parse<T: de::DeserializeOwned>(self, strict: bool) -> BoxFuture<Result<T>>;
+    fn parse_optional<T: de::DeserializeOwned>(self, strict: bool) -> BoxFuture<Result<Option<T>>>;
+    fn parse_no_content(self, strict: bool) -> BoxFuture<Result<()>>;
+    fn parse_drive_item(self, strict: bool) -> BoxFuture<Result<DriveItem>>;
+    fn parse_async_job(self, strict: bool) -> BoxFuture<Result<String>>;
 }
 
 impl ResponseExt for Response {
-    fn parse<T: de::DeserializeOwned>(self) -> BoxFuture<Result<T>> {
-        Box::pin(async move { Ok(handle_error_response(self).await?.json().await?) })
+    fn parse<T: de::DeserializeOwned>(self, strict: bool) -> BoxFuture<Result<T>> {
+        Box::pin(async move {
+            expect_no_job(&self, strict)?;
+            Ok(handle_error_response(self).await?.json().await?)
+        })
     }
 
-    fn parse_optional<T: de::DeserializeOwned>(self) -> BoxFuture<Result<Option<T>>> {
+    fn parse_optional<T: de::DeserializeOwned>(self, strict: bool) -> BoxFuture<Result<Option<T>>> {
         Box::pin(async move {
+            expect_no_job(&self, strict)?;
             match self.status() {
                 StatusCode::NOT_MODIFIED | StatusCode::ACCEPTED => Ok(None),
                 _ => Ok(Some(handle_error_response(self).await?.json().await?)),
@@ -286,12 +495,78 @@ impl ResponseExt for Response {
         })
     }
 
-    fn parse_no_content(self) -> BoxFuture<Result<()>> {
+    fn parse_no_content(self, strict: bool) -> BoxFuture<Result<()>> {
         Box::pin(async move {
+            expect_no_job(&self, strict)?;
             handle_error_response(self).await?;
             Ok(())
         })
     }
+
+    // Some mutation APIs omit `eTag` in the body, but the header is always there.
+    // With `Prefer: return=minimal`, the body may be omitted entirely.
+    fn parse_drive_item(self, strict: bool) -> BoxFuture<Result<DriveItem>> {
+        Box::pin(async move {
+            expect_no_job(&self, strict)?;
+            let resp = handle_error_response(self).await?;
+            let e_tag = resp
+                .headers()
+                .get(header::ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| Tag(s.to_owned()));
+            if resp.status() == StatusCode::NO_CONTENT {
+                return Ok(DriveItem {
+                    e_tag,
+                    ..Default::default()
+                });
+            }
+            let mut item: DriveItem = resp.json().await?;
+            if item.e_tag.is_none() {
+                item.e_tag = e_tag;
+            }
+            Ok(item)
+        })
+    }
+
+    fn parse_async_job(self, strict: bool) -> BoxFuture<Result<String>> {
+        Box::pin(async move {
+            let job = accepted_job(&self, strict)?;
+            let resp = handle_error_response(self).await?;
+            job.or_else(|| location_header(&resp)).ok_or_else(|| {
+                Error::unexpected_response("Missing or invalid header `Location` for async job")
+            })
+        })
+    }
+}
+
+fn location_header(resp: &Response) -> Option<String> {
+    let url = resp.headers().get(header::LOCATION)?.to_str().ok()?;
+    Some(url.to_owned())
+}
+
+/// Get the async job monitor url if `resp` is HTTP 202 ACCEPTED with `Location`.
+///
+/// All responses go through it. An HTTP 202 ACCEPTED without `Location` is
+/// only rejected if `strict` is set, since some APIs (eg. uploading parts)
+/// use it for plain success.
+pub(crate) fn accepted_job(resp: &Response, strict: bool) -> Result<Option<String>> {
+    if resp.status() != StatusCode::ACCEPTED {
+        return Ok(None);
+    }
+    match location_header(resp) {
+        Some(url) => Ok(Some(url)),
+        None if strict => Err(Error::async_job_accepted(None)),
+        None => Ok(None),
+    }
+}
+
+/// For APIs not expecting async jobs, report the monitor url of an unexpected
+/// one through the error, since their results cannot carry it.
+fn expect_no_job(resp: &Response, strict: bool) -> Result<()> {
+    match accepted_job(resp, strict)? {
+        Some(url) => Err(Error::async_job_accepted(Some(url))),
+        None => Ok(()),
+    }
 }
 
 pub(crate) async fn handle_error_response(resp: Response) -> Result<Response> {
@@ -305,9 +580,41 @@ pub(crate) async fn handle_error_response(resp: Response) -> Result<Response> {
     if status.is_success() || status.is_redirection() {
         Ok(resp)
     } else {
+        // Only delay-seconds is used by Microsoft Graph.
+        let retry_after = resp
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok()?.parse().ok())
+            .map(Duration::from_secs);
         let resp: Resp = resp.json().await?;
-        Err(Error::from_error_response(status, resp.error))
+        Err(Error::from_error_response(status, resp.error, retry_after))
+    }
+}
+
+/// Receive the whole body of `resp`, failing if it is larger than `limit` bytes.
+///
+/// The body is checked against `Content-Length` first if any, and then while receiving,
+/// so it never buffers more than `limit` bytes.
+pub(crate) async fn limit_body_size(mut resp: Response, limit: Option<u64>) -> Result<Response> {
+    let limit = match limit {
+        Some(limit) => limit,
+        None => return Ok(resp),
+    };
+    if resp.content_length().is_some_and(|len| len > limit) {
+        return Err(Error::response_too_large(limit));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Err(Error::response_too_large(limit));
+        }
+        body.extend_from_slice(&chunk);
     }
+    let mut limited = http::Response::new(body);
+    *limited.status_mut() = resp.status();
+    *limited.version_mut() = resp.version();
+    *limited.headers_mut() = resp.headers().clone();
+    Ok(limited.into())
 }
 
 pub(crate) async fn handle_oauth2_error_response(resp: Response) -> Result<Response> {
@@ -319,3 +626,67 @@ pub(crate) async fn handle_oauth2_error_response(resp: Response) -> Result<Respo
         Err(Error::from_oauth2_error_response(status, resp))
     }
 }
+
+/// Parse an RFC 3339 timestamp returned by API, like `2015-01-29T09:21:55.523Z`.
+pub(crate) fn parse_timestamp(s: &str) -> Option<SystemTime> {
+    fn num(s: &str) -> Option<u32> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    }
+
+    let (date, time) = s.split_at(s.find(['T', 't'])?);
+    let time = &time[1..];
+    let (year, month, day) = match date.split('-').collect::<Vec<_>>()[..] {
+        [y, m, d] if y.len() == 4 && m.len() == 2 && d.len() == 2 => (num(y)?, num(m)?, num(d)?),
+        _ => return None,
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (time, offset_secs) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0i64)
+    } else {
+        let pos = time.rfind(['+', '-'])?;
+        let (time, offset) = time.split_at(pos);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (h, m) = offset[1..].split_once(':')?;
+        (time, sign * i64::from(num(h)? * 3600 + num(m)? * 60))
+    };
+    let (time, nanos) = match time.split_once('.') {
+        Some((time, frac)) => {
+            let digits = frac.get(..9).unwrap_or(frac);
+            let scale = 10u32.pow(9 - digits.len() as u32);
+            (time, num(digits)? * scale)
+        }
+        None => (time, 0),
+    };
+    let (hour, minute, second) = match time.split(':').collect::<Vec<_>>()[..] {
+        [h, m, s] if h.len() == 2 && m.len() == 2 && s.len() == 2 => (num(h)?, num(m)?, num(s)?),
+        _ => return None,
+    };
+    if hour >= 24 || minute >= 60 || second > 60 {
+        return None;
+    }
+
+    // Days from civil, see: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let y = i64::from(year) - i64::from(month <= 2);
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86400 + i64::from(hour * 3600 + minute * 60 + second) - offset_secs;
+    let whole_secs = Duration::from_secs(secs.unsigned_abs());
+    // The fractional part always moves forward, even for times before the epoch.
+    let time = if secs >= 0 {
+        UNIX_EPOCH.checked_add(whole_secs)
+    } else {
+        UNIX_EPOCH.checked_sub(whole_secs)
+    };
+    time?.checked_add(Duration::from_nanos(u64::from(nanos)))
+}